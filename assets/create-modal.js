@@ -7,6 +7,7 @@
   function getConfig() {
     return {
       repo: document.getElementById('create-github-repo').value,
+      repoUrl: document.getElementById('create-repo-url').value,
       branch: document.getElementById('create-branch').value,
       subdir: document.getElementById('create-subdir').value,
       nextId: document.getElementById('create-next-id').value,
@@ -51,7 +52,7 @@
     const path = buildPath(title);
     if (path === '...') return null;
 
-    return 'https://github.com/' + config.repo + '/new/' + config.branch + '?filename=' + encodeURIComponent(path);
+    return config.repoUrl + '/new/' + config.branch + '?filename=' + encodeURIComponent(path);
   }
 
   // Update path preview as user types
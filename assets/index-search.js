@@ -15,11 +15,15 @@
       const title = item.getAttribute('data-title') ?? '';
       const id = item.getAttribute('data-id') ?? '';
       const authors = item.getAttribute('data-authors') ?? '';
+      const owners = item.getAttribute('data-owners') ?? '';
+      const teams = item.getAttribute('data-teams') ?? '';
       const match =
         normalized === '' ||
         matches(title) ||
         matches(id) ||
-        matches(authors);
+        matches(authors) ||
+        matches(owners) ||
+        matches(teams);
       item.style.display = match ? '' : 'none';
       if (match) visible += 1;
     });
@@ -0,0 +1,963 @@
+// Server-side Mermaid rendering for `dossiers build`, evaluated in an embedded
+// QuickJS runtime ahead of assets/mermaid.min.js (see src/mermaid.rs).
+//
+// Mermaid expects a browser, so this provides just enough of a DOM for it to
+// lay out and serialise diagrams. Text is measured with fixed per-character
+// metrics; geometry comes from element attributes, not a rendering engine.
+(() => {
+  const SVG_NS = "http://www.w3.org/2000/svg";
+  const XHTML_NS = "http://www.w3.org/1999/xhtml";
+  const CHAR_WIDTH = 0.6;
+  const LINE_HEIGHT = 1.2;
+  const DEFAULT_FONT_SIZE = 16;
+  const CONTAINER_WIDTH = 800;
+
+  const escapeText = (s) =>
+    String(s).replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;");
+  const escapeAttr = (s) => escapeText(s).replace(/"/g, "&quot;");
+  const camelToKebab = (s) => s.replace(/[A-Z]/g, (c) => "-" + c.toLowerCase());
+
+  class Style {
+    constructor(owner) {
+      Object.defineProperty(this, "_owner", { value: owner, enumerable: false });
+      Object.defineProperty(this, "_props", { value: new Map(), enumerable: false });
+    }
+    setProperty(name, value) {
+      if (value === null || value === undefined || value === "") this._props.delete(name);
+      else this._props.set(name, String(value));
+    }
+    getPropertyValue(name) {
+      return this._props.get(name) || "";
+    }
+    removeProperty(name) {
+      this._props.delete(name);
+    }
+    get cssText() {
+      return Array.from(this._props, ([k, v]) => `${k}: ${v};`).join(" ");
+    }
+    set cssText(text) {
+      this._props.clear();
+      String(text || "")
+        .split(";")
+        .forEach((decl) => {
+          const idx = decl.indexOf(":");
+          if (idx > 0) this._props.set(decl.slice(0, idx).trim(), decl.slice(idx + 1).trim());
+        });
+    }
+  }
+  const styleProxy = (owner) =>
+    new Proxy(new Style(owner), {
+      get(target, prop) {
+        if (prop in target || typeof prop !== "string") {
+          const value = target[prop];
+          return typeof value === "function" ? value.bind(target) : value;
+        }
+        return target.getPropertyValue(camelToKebab(prop));
+      },
+      set(target, prop, value) {
+        if (prop === "cssText") target.cssText = value;
+        else target.setProperty(camelToKebab(prop), value);
+        return true;
+      },
+    });
+
+  class Node {
+    constructor(ownerDocument) {
+      this.ownerDocument = ownerDocument;
+      this.parentNode = null;
+      this.childNodes = [];
+    }
+    get parentElement() {
+      return this.parentNode && this.parentNode.nodeType === 1 ? this.parentNode : null;
+    }
+    get firstChild() {
+      return this.childNodes[0] || null;
+    }
+    get lastChild() {
+      return this.childNodes[this.childNodes.length - 1] || null;
+    }
+    get nextSibling() {
+      if (!this.parentNode) return null;
+      const siblings = this.parentNode.childNodes;
+      return siblings[siblings.indexOf(this) + 1] || null;
+    }
+    get previousSibling() {
+      if (!this.parentNode) return null;
+      const siblings = this.parentNode.childNodes;
+      return siblings[siblings.indexOf(this) - 1] || null;
+    }
+    get isConnected() {
+      let node = this;
+      while (node.parentNode) node = node.parentNode;
+      return node.nodeType === 9;
+    }
+    appendChild(child) {
+      return this.insertBefore(child, null);
+    }
+    insertBefore(child, ref) {
+      if (child.nodeType === 11) {
+        for (const c of child.childNodes.slice()) this.insertBefore(c, ref);
+        return child;
+      }
+      if (child.parentNode) child.parentNode.removeChild(child);
+      const idx = ref ? this.childNodes.indexOf(ref) : -1;
+      if (idx < 0) this.childNodes.push(child);
+      else this.childNodes.splice(idx, 0, child);
+      child.parentNode = this;
+      return child;
+    }
+    removeChild(child) {
+      const idx = this.childNodes.indexOf(child);
+      if (idx >= 0) this.childNodes.splice(idx, 1);
+      child.parentNode = null;
+      return child;
+    }
+    replaceChild(next, prev) {
+      this.insertBefore(next, prev);
+      return this.removeChild(prev);
+    }
+    remove() {
+      if (this.parentNode) this.parentNode.removeChild(this);
+    }
+    contains(other) {
+      for (let node = other; node; node = node.parentNode) if (node === this) return true;
+      return false;
+    }
+    hasChildNodes() {
+      return this.childNodes.length > 0;
+    }
+    get textContent() {
+      return this.childNodes.map((c) => c.textContent).join("");
+    }
+    set textContent(value) {
+      this.childNodes.forEach((c) => (c.parentNode = null));
+      this.childNodes = [];
+      if (value !== null && value !== undefined && value !== "") {
+        this.appendChild(new Text(this.ownerDocument, String(value)));
+      }
+    }
+    addEventListener() {}
+    removeEventListener() {}
+    dispatchEvent() {
+      return true;
+    }
+    cloneNode(deep) {
+      const copy = this._shallowClone();
+      if (deep) this.childNodes.forEach((c) => copy.appendChild(c.cloneNode(true)));
+      return copy;
+    }
+  }
+
+  class Text extends Node {
+    constructor(ownerDocument, data) {
+      super(ownerDocument);
+      this.nodeType = 3;
+      this.nodeName = "#text";
+      this.data = data;
+    }
+    get textContent() {
+      return this.data;
+    }
+    set textContent(value) {
+      this.data = String(value);
+    }
+    get nodeValue() {
+      return this.data;
+    }
+    _shallowClone() {
+      return new Text(this.ownerDocument, this.data);
+    }
+    _serialize(raw) {
+      return raw ? this.data : escapeText(this.data);
+    }
+  }
+
+  class Comment extends Node {
+    constructor(ownerDocument, data) {
+      super(ownerDocument);
+      this.nodeType = 8;
+      this.nodeName = "#comment";
+      this.data = data;
+    }
+    get textContent() {
+      return "";
+    }
+    _shallowClone() {
+      return new Comment(this.ownerDocument, this.data);
+    }
+    _serialize() {
+      return `<!--${this.data}-->`;
+    }
+  }
+
+  const matchesSimple = (el, selector) => {
+    const re = /([#.]?)((?:\\.|[\w-])+|\*)|\[([\w-]+)(?:([~|^$*]?=)["']?([^"'\]]*)["']?)?\]|:([\w-]+)(?:\(([^)]*)\))?/g;
+    let m;
+    let matched = false;
+    while ((m = re.exec(selector))) {
+      matched = true;
+      if (m[3]) {
+        const actual = el.getAttribute(m[3]);
+        if (actual === null) return false;
+        if (m[4] === "=" && actual !== m[5]) return false;
+        if (m[4] === "~=" && !actual.split(/\s+/).includes(m[5])) return false;
+        if (m[4] === "^=" && !actual.startsWith(m[5])) return false;
+        if (m[4] === "$=" && !actual.endsWith(m[5])) return false;
+        if (m[4] === "*=" && !actual.includes(m[5])) return false;
+        continue;
+      }
+      if (m[6]) {
+        if (m[6] === "not") {
+          if (el.matches(m[7])) return false;
+        } else if (m[6] === "first-child") {
+          if (el.parentNode && el.parentNode.children[0] !== el) return false;
+        } else if (m[6] === "last-child") {
+          const kids = el.parentNode ? el.parentNode.children : [el];
+          if (kids[kids.length - 1] !== el) return false;
+        }
+        continue;
+      }
+      const name = m[2].replace(/\\(.)/g, "$1");
+      if (m[1] === "#") {
+        if (el.getAttribute("id") !== name) return false;
+      } else if (m[1] === ".") {
+        if (!el.classList.contains(name)) return false;
+      } else if (name !== "*" && el.localName.toLowerCase() !== name.toLowerCase()) {
+        return false;
+      }
+    }
+    return matched;
+  };
+
+  const matchesCompound = (el, selector) => {
+    const parts = selector.trim().split(/\s*(>)\s*|\s+/).filter((p) => p !== undefined && p !== "");
+    let idx = parts.length - 1;
+    if (!matchesSimple(el, parts[idx])) return false;
+    let node = el;
+    idx -= 1;
+    while (idx >= 0) {
+      if (parts[idx] === ">") {
+        node = node.parentElement;
+        if (!node || !matchesSimple(node, parts[idx - 1])) return false;
+        idx -= 2;
+      } else {
+        node = node.parentElement;
+        while (node && !matchesSimple(node, parts[idx])) node = node.parentElement;
+        if (!node) return false;
+        idx -= 1;
+      }
+    }
+    return true;
+  };
+
+  const splitSelectorList = (selector) => selector.split(",").map((s) => s.trim()).filter(Boolean);
+
+  class ClassList {
+    constructor(el) {
+      this.el = el;
+    }
+    _list() {
+      return (this.el.getAttribute("class") || "").split(/\s+/).filter(Boolean);
+    }
+    contains(name) {
+      return this._list().includes(name);
+    }
+    add(...names) {
+      const list = this._list();
+      names.forEach((n) => list.includes(n) || list.push(n));
+      this.el.setAttribute("class", list.join(" "));
+    }
+    remove(...names) {
+      this.el.setAttribute("class", this._list().filter((n) => !names.includes(n)).join(" "));
+    }
+    toggle(name, force) {
+      const has = this.contains(name);
+      const want = force === undefined ? !has : !!force;
+      if (want && !has) this.add(name);
+      if (!want && has) this.remove(name);
+      return want;
+    }
+    get length() {
+      return this._list().length;
+    }
+    item(i) {
+      return this._list()[i] || null;
+    }
+    [Symbol.iterator]() {
+      return this._list()[Symbol.iterator]();
+    }
+  }
+
+  const VOID_HTML = new Set(["br", "hr", "img", "input", "meta", "link"]);
+  const NON_RENDERED = new Set(["style", "script", "defs", "marker", "title", "desc", "metadata"]);
+
+  class Element extends Node {
+    constructor(ownerDocument, namespaceURI, qualifiedName) {
+      super(ownerDocument);
+      this.nodeType = 1;
+      this.namespaceURI = namespaceURI;
+      this.localName = qualifiedName;
+      this.attributes = [];
+      this.style = styleProxy(this);
+      this.classList = new ClassList(this);
+      this.dataset = new Proxy(
+        {},
+        {
+          get: (_, key) => this.getAttribute("data-" + camelToKebab(String(key))) ?? undefined,
+          set: (_, key, value) => {
+            this.setAttribute("data-" + camelToKebab(String(key)), value);
+            return true;
+          },
+        }
+      );
+    }
+    get nodeName() {
+      return this.namespaceURI === XHTML_NS ? this.localName.toUpperCase() : this.localName;
+    }
+    get tagName() {
+      return this.nodeName;
+    }
+    get children() {
+      return this.childNodes.filter((c) => c.nodeType === 1);
+    }
+    get firstElementChild() {
+      return this.children[0] || null;
+    }
+    get lastElementChild() {
+      const kids = this.children;
+      return kids[kids.length - 1] || null;
+    }
+    get childElementCount() {
+      return this.children.length;
+    }
+    get id() {
+      return this.getAttribute("id") || "";
+    }
+    set id(value) {
+      this.setAttribute("id", value);
+    }
+    get className() {
+      return this.getAttribute("class") || "";
+    }
+    set className(value) {
+      this.setAttribute("class", value);
+    }
+    _attr(name) {
+      return this.attributes.find((a) => a.name === name);
+    }
+    getAttribute(name) {
+      if (name === "style") {
+        const css = this.style.cssText;
+        return css || (this._attr("style") ? "" : null);
+      }
+      const attr = this._attr(name);
+      return attr ? attr.value : null;
+    }
+    getAttributeNS(_ns, name) {
+      return this.getAttribute(name);
+    }
+    hasAttribute(name) {
+      return this.getAttribute(name) !== null;
+    }
+    setAttribute(name, value) {
+      value = String(value);
+      if (name === "style") {
+        this.style.cssText = value;
+      }
+      const attr = this._attr(name);
+      if (attr) attr.value = value;
+      else this.attributes.push({ name, value });
+    }
+    setAttributeNS(_ns, name, value) {
+      this.setAttribute(name, value);
+    }
+    removeAttribute(name) {
+      if (name === "style") this.style.cssText = "";
+      this.attributes = this.attributes.filter((a) => a.name !== name);
+    }
+    removeAttributeNS(_ns, name) {
+      this.removeAttribute(name);
+    }
+    matches(selector) {
+      return splitSelectorList(selector).some((s) => matchesCompound(this, s));
+    }
+    closest(selector) {
+      for (let node = this; node && node.nodeType === 1; node = node.parentNode) {
+        if (node.matches(selector)) return node;
+      }
+      return null;
+    }
+    _descendants(out = []) {
+      for (const c of this.childNodes) {
+        if (c.nodeType === 1) {
+          out.push(c);
+          c._descendants(out);
+        }
+      }
+      return out;
+    }
+    querySelectorAll(selector) {
+      return this._descendants().filter((el) => el.matches(selector));
+    }
+    querySelector(selector) {
+      return this._descendants().find((el) => el.matches(selector)) || null;
+    }
+    getElementsByTagName(name) {
+      return this._descendants().filter((el) => name === "*" || el.localName === name);
+    }
+    getElementsByClassName(name) {
+      return this._descendants().filter((el) => el.classList.contains(name));
+    }
+    append(...nodes) {
+      nodes.forEach((n) =>
+        this.appendChild(typeof n === "string" ? new Text(this.ownerDocument, n) : n)
+      );
+    }
+    prepend(...nodes) {
+      const first = this.firstChild;
+      nodes.forEach((n) =>
+        this.insertBefore(typeof n === "string" ? new Text(this.ownerDocument, n) : n, first)
+      );
+    }
+    replaceWith(...nodes) {
+      const parent = this.parentNode;
+      if (!parent) return;
+      nodes.forEach((n) => parent.insertBefore(n, this));
+      parent.removeChild(this);
+    }
+    _shallowClone() {
+      const copy = new Element(this.ownerDocument, this.namespaceURI, this.localName);
+      this.attributes.forEach((a) => copy.setAttribute(a.name, a.value));
+      copy.style.cssText = this.style.cssText;
+      return copy;
+    }
+    _serialize() {
+      const attrs = this.attributes
+        .filter((a) => a.name !== "style")
+        .map((a) => ` ${a.name}="${escapeAttr(a.value)}"`)
+        .join("");
+      const css = this.style.cssText;
+      const style = css ? ` style="${escapeAttr(css)}"` : "";
+      const raw = this.localName === "style" || this.localName === "script";
+      const inner = this.childNodes.map((c) => c._serialize(raw)).join("");
+      if (!inner && this.namespaceURI === XHTML_NS && VOID_HTML.has(this.localName)) {
+        return `<${this.localName}${attrs}${style} />`;
+      }
+      return `<${this.localName}${attrs}${style}>${inner}</${this.localName}>`;
+    }
+    get outerHTML() {
+      return this._serialize();
+    }
+    get innerHTML() {
+      const raw = this.localName === "style" || this.localName === "script";
+      return this.childNodes.map((c) => c._serialize(raw)).join("");
+    }
+    set innerHTML(html) {
+      this.textContent = "";
+      parseHtmlInto(this, String(html));
+    }
+    insertAdjacentHTML(_position, html) {
+      parseHtmlInto(this, String(html));
+    }
+    get innerText() {
+      return this.textContent;
+    }
+    set innerText(value) {
+      this.textContent = value;
+    }
+    // Inherited presentation property: attribute, inline style, then rules
+    // from the enclosing SVG's <style> elements (last match wins).
+    _computed(prop) {
+      for (let node = this; node && node.nodeType === 1; node = node.parentNode) {
+        const own = node.style.getPropertyValue(prop) || node.getAttribute(prop);
+        if (own) return own;
+        let value = null;
+        for (const [selector, decls] of stylesheetRules(node)) {
+          if (decls.has(prop) && node.matches(selector)) value = decls.get(prop);
+        }
+        if (value) return value;
+      }
+      return null;
+    }
+    _fontSize() {
+      const px = parseFloat(this._computed("font-size"));
+      return isNaN(px) ? DEFAULT_FONT_SIZE : px;
+    }
+    _lines() {
+      const tspans = this.children.filter((c) => c.localName === "tspan");
+      if (tspans.length) return tspans.map((t) => t.textContent);
+      return this.textContent.split("\n");
+    }
+    getComputedTextLength() {
+      const size = this._fontSize();
+      return Math.max(0, ...this._lines().map((l) => l.length)) * size * CHAR_WIDTH;
+    }
+    getBBox() {
+      const name = this.localName;
+      if (NON_RENDERED.has(name)) return { x: 0, y: 0, width: 0, height: 0 };
+      const num = (attr) => parseFloat(this.getAttribute(attr)) || 0;
+      if (name === "text" || name === "tspan") {
+        const size = this._fontSize();
+        const lines = this._lines().filter((l, i, all) => l.length || all.length === 1);
+        const width = Math.max(0, ...lines.map((l) => l.length)) * size * CHAR_WIDTH;
+        const height = Math.max(1, lines.length) * size * LINE_HEIGHT;
+        const anchor = this._computed("text-anchor");
+        const first = this.children.find((c) => c.localName === "tspan") || this;
+        const x = first.hasAttribute("x") ? length(first.getAttribute("x"), size) : num("x");
+        const y = first.hasAttribute("y") ? length(first.getAttribute("y"), size) : num("y");
+        const baseline = y + length(first.getAttribute("dy"), size);
+        const offset = anchor === "middle" ? width / 2 : anchor === "end" ? width : 0;
+        return { x: x - offset, y: baseline - size * 0.9, width, height };
+      }
+      if (name === "rect" || name === "image" || name === "foreignObject") {
+        return { x: num("x"), y: num("y"), width: num("width"), height: num("height") };
+      }
+      if (name === "circle" || name === "ellipse") {
+        const rx = num(name === "circle" ? "r" : "rx");
+        const ry = num(name === "circle" ? "r" : "ry");
+        return { x: num("cx") - rx, y: num("cy") - ry, width: 2 * rx, height: 2 * ry };
+      }
+      if (name === "line") {
+        return boxOf([
+          [num("x1"), num("y1")],
+          [num("x2"), num("y2")],
+        ]);
+      }
+      if (name === "polygon" || name === "polyline") {
+        const nums = (this.getAttribute("points") || "").match(NUMBER_RE) || [];
+        const points = [];
+        for (let i = 0; i + 1 < nums.length; i += 2) points.push([+nums[i], +nums[i + 1]]);
+        return boxOf(points);
+      }
+      if (name === "path") {
+        return boxOf(pathPoints(this.getAttribute("d") || ""));
+      }
+      let minX = Infinity;
+      let minY = Infinity;
+      let maxX = -Infinity;
+      let maxY = -Infinity;
+      for (const child of this.children) {
+        if (typeof child.getBBox !== "function") continue;
+        const box = child.getBBox();
+        if (!box.width && !box.height) continue;
+        const [tx, ty] = parseTranslate(child.getAttribute("transform"));
+        minX = Math.min(minX, box.x + tx);
+        minY = Math.min(minY, box.y + ty);
+        maxX = Math.max(maxX, box.x + tx + box.width);
+        maxY = Math.max(maxY, box.y + ty + box.height);
+      }
+      if (minX === Infinity) {
+        if (this.namespaceURI === XHTML_NS) return this._htmlBox();
+        return { x: 0, y: 0, width: 0, height: 0 };
+      }
+      return { x: minX, y: minY, width: maxX - minX, height: maxY - minY };
+    }
+    _htmlBox() {
+      const size = this._fontSize();
+      const lines = renderedText(this).split("\n");
+      const width = Math.max(0, ...lines.map((l) => l.length)) * size * CHAR_WIDTH;
+      return { x: 0, y: 0, width, height: lines.length * size * LINE_HEIGHT };
+    }
+    getBoundingClientRect() {
+      const box = this.namespaceURI === XHTML_NS ? this._htmlBox() : this.getBBox();
+      return {
+        ...box,
+        left: box.x,
+        top: box.y,
+        right: box.x + box.width,
+        bottom: box.y + box.height,
+      };
+    }
+    getTotalLength() {
+      return 0;
+    }
+    getPointAtLength() {
+      return { x: 0, y: 0 };
+    }
+    getCTM() {
+      return null;
+    }
+    getScreenCTM() {
+      return null;
+    }
+    get offsetWidth() {
+      // Block containers take the nominal page width; inline content its text.
+      return this.children.length ? CONTAINER_WIDTH : this._htmlBox().width;
+    }
+    get offsetHeight() {
+      return this._htmlBox().height;
+    }
+    get sheet() {
+      return { cssRules: [], insertRule() {} };
+    }
+  }
+
+  const NUMBER_RE = /-?(?:\d+\.?\d*|\.\d+)(?:e[-+]?\d+)?/gi;
+
+  const boxOf = (points) => {
+    if (!points.length) return { x: 0, y: 0, width: 0, height: 0 };
+    const xs = points.map((p) => p[0]);
+    const ys = points.map((p) => p[1]);
+    const x = Math.min(...xs);
+    const y = Math.min(...ys);
+    return { x, y, width: Math.max(...xs) - x, height: Math.max(...ys) - y };
+  };
+
+  // End and control points of a path; control points overestimate curves
+  // slightly, which is fine for layout.
+  const pathPoints = (d) => {
+    const points = [];
+    let x = 0;
+    let y = 0;
+    let startX = 0;
+    let startY = 0;
+    const re = /([MLHVCSQTAZmlhvcsqtaz])([^MLHVCSQTAZmlhvcsqtaz]*)/g;
+    let m;
+    while ((m = re.exec(d))) {
+      const cmd = m[1];
+      const args = (m[2].match(NUMBER_RE) || []).map(Number);
+      const rel = cmd === cmd.toLowerCase();
+      const upper = cmd.toUpperCase();
+      const stride = { M: 2, L: 2, T: 2, H: 1, V: 1, C: 6, S: 4, Q: 4, A: 7, Z: 0 }[upper];
+      if (!stride) {
+        x = startX;
+        y = startY;
+        continue;
+      }
+      for (let i = 0; i + stride <= args.length; i += stride) {
+        const seg = args.slice(i, i + stride);
+        if (upper === "H") {
+          x = rel ? x + seg[0] : seg[0];
+        } else if (upper === "V") {
+          y = rel ? y + seg[0] : seg[0];
+        } else if (upper === "A") {
+          x = rel ? x + seg[5] : seg[5];
+          y = rel ? y + seg[6] : seg[6];
+        } else {
+          const baseX = x;
+          const baseY = y;
+          for (let j = 0; j < stride; j += 2) {
+            const px = rel ? baseX + seg[j] : seg[j];
+            const py = rel ? baseY + seg[j + 1] : seg[j + 1];
+            if (j + 2 < stride) points.push([px, py]);
+            else {
+              x = px;
+              y = py;
+            }
+          }
+        }
+        if (upper === "M" && i === 0) {
+          startX = x;
+          startY = y;
+        }
+        points.push([x, y]);
+      }
+    }
+    return points;
+  };
+
+  const renderedText = (node) =>
+    node.nodeType === 3
+      ? node.data
+      : NON_RENDERED.has(node.localName)
+        ? ""
+        : node.childNodes.map(renderedText).join("");
+
+  const length = (value, fontSize) => {
+    const n = parseFloat(value);
+    if (isNaN(n)) return 0;
+    return /em\s*$/.test(value) ? n * fontSize : n;
+  };
+
+  const ruleCache = new Map();
+  const stylesheetRules = (el) => {
+    let svg = el;
+    while (svg && svg.localName !== "svg") svg = svg.parentNode;
+    if (!svg || svg.nodeType !== 1) return [];
+    const css = svg
+      .getElementsByTagName("style")
+      .map((s) => s.textContent)
+      .join("\n");
+    if (!ruleCache.has(css)) {
+      const rules = [];
+      const re = /([^{}]+)\{([^{}]*)\}/g;
+      let m;
+      while ((m = re.exec(css))) {
+        const decls = new Map();
+        m[2].split(";").forEach((decl) => {
+          const idx = decl.indexOf(":");
+          if (idx > 0) decls.set(decl.slice(0, idx).trim(), decl.slice(idx + 1).replace("!important", "").trim());
+        });
+        if (!decls.has("text-anchor") && !decls.has("font-size")) continue;
+        splitSelectorList(m[1].replace(/^.*@[^{]*$/, "")).forEach((sel) => {
+          if (!/[:@]/.test(sel)) rules.push([sel, decls]);
+        });
+      }
+      ruleCache.clear();
+      ruleCache.set(css, rules);
+    }
+    return ruleCache.get(css);
+  };
+
+  const parseTranslate = (transform) => {
+    const m = /translate\(\s*([-\d.e]+)[\s,]*([-\d.e]+)?\s*\)/.exec(transform || "");
+    return m ? [parseFloat(m[1]) || 0, parseFloat(m[2]) || 0] : [0, 0];
+  };
+
+  // Just enough HTML parsing for the label markup Mermaid builds from strings.
+  const parseHtmlInto = (parent, html) => {
+    const doc = parent.ownerDocument;
+    const ns = parent.namespaceURI;
+    if (parent.localName === "style" || parent.localName === "script") {
+      parent.appendChild(new Text(doc, html));
+      return;
+    }
+    const stack = [parent];
+    const re = /<!--([\s\S]*?)-->|<\/\s*([\w:-]+)\s*>|<([\w:-]+)((?:\s+[^\s=/>]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s>]+))?)*)\s*(\/?)>|([^<]+|<)/g;
+    let m;
+    while ((m = re.exec(html))) {
+      const top = stack[stack.length - 1];
+      if (m[1] !== undefined) {
+        top.appendChild(new Comment(doc, m[1]));
+      } else if (m[2]) {
+        const name = m[2].toLowerCase();
+        for (let i = stack.length - 1; i > 0; i--) {
+          if (stack[i].localName.toLowerCase() === name) {
+            stack.length = i;
+            break;
+          }
+        }
+      } else if (m[3]) {
+        const name = m[3];
+        const elNs = name === "svg" ? SVG_NS : ns === SVG_NS && name !== "div" && name !== "span" ? SVG_NS : XHTML_NS;
+        const el = new Element(doc, elNs, elNs === XHTML_NS ? name.toLowerCase() : name);
+        const attrRe = /([^\s=/>]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+)))?/g;
+        let a;
+        while ((a = attrRe.exec(m[4] || ""))) {
+          el.setAttribute(a[1], decodeEntities(a[2] ?? a[3] ?? a[4] ?? ""));
+        }
+        top.appendChild(el);
+        if (!m[5] && !(elNs === XHTML_NS && VOID_HTML.has(el.localName))) stack.push(el);
+      } else if (m[6]) {
+        top.appendChild(new Text(doc, decodeEntities(m[6])));
+      }
+    }
+  };
+
+  const decodeEntities = (s) =>
+    s
+      .replace(/&lt;/g, "<")
+      .replace(/&gt;/g, ">")
+      .replace(/&quot;/g, '"')
+      .replace(/&#39;/g, "'")
+      .replace(/&nbsp;/g, " ")
+      .replace(/&#(\d+);/g, (_, n) => String.fromCharCode(+n))
+      .replace(/&amp;/g, "&");
+
+  class DocumentFragment extends Node {
+    constructor(ownerDocument) {
+      super(ownerDocument);
+      this.nodeType = 11;
+      this.nodeName = "#document-fragment";
+    }
+    _shallowClone() {
+      return new DocumentFragment(this.ownerDocument);
+    }
+    _serialize() {
+      return this.childNodes.map((c) => c._serialize()).join("");
+    }
+    querySelector(selector) {
+      return Element.prototype.querySelector.call(this, selector);
+    }
+    querySelectorAll(selector) {
+      return Element.prototype.querySelectorAll.call(this, selector);
+    }
+    _descendants(out = []) {
+      return Element.prototype._descendants.call(this, out);
+    }
+  }
+
+  class Document extends Node {
+    constructor() {
+      super(null);
+      this.nodeType = 9;
+      this.nodeName = "#document";
+      this.ownerDocument = null;
+      this.documentElement = new Element(this, XHTML_NS, "html");
+      this.head = new Element(this, XHTML_NS, "head");
+      this.body = new Element(this, XHTML_NS, "body");
+      this.documentElement.appendChild(this.head);
+      this.documentElement.appendChild(this.body);
+      this.appendChild(this.documentElement);
+      this.readyState = "complete";
+      this.fonts = { ready: Promise.resolve() };
+    }
+    createElement(name) {
+      return new Element(this, XHTML_NS, String(name).toLowerCase());
+    }
+    createElementNS(ns, name) {
+      return new Element(this, ns || XHTML_NS, name);
+    }
+    createTextNode(data) {
+      return new Text(this, String(data));
+    }
+    createComment(data) {
+      return new Comment(this, String(data));
+    }
+    createDocumentFragment() {
+      return new DocumentFragment(this);
+    }
+    getElementById(id) {
+      return this.documentElement.querySelector(`#${id}`) || null;
+    }
+    querySelector(selector) {
+      return this.documentElement.matches(selector)
+        ? this.documentElement
+        : this.documentElement.querySelector(selector);
+    }
+    querySelectorAll(selector) {
+      return this.documentElement.querySelectorAll(selector);
+    }
+    getElementsByTagName(name) {
+      return this.documentElement.getElementsByTagName(name);
+    }
+  }
+
+  const document = new Document();
+  globalThis.window = globalThis;
+  globalThis.self = globalThis;
+  globalThis.document = document;
+  globalThis.Node = Node;
+  globalThis.Element = Element;
+  globalThis.SVGElement = Element;
+  globalThis.HTMLElement = Element;
+  globalThis.DocumentFragment = DocumentFragment;
+  globalThis.navigator = { userAgent: "dossiers", language: "en" };
+  globalThis.location = { href: "about:blank", protocol: "about:", host: "", hostname: "", search: "", hash: "" };
+  globalThis.getComputedStyle = (el) => el.style;
+  globalThis.matchMedia = () => ({ matches: false, addListener() {}, removeListener() {} });
+  globalThis.requestAnimationFrame = (cb) => setTimeout(cb, 0);
+  globalThis.addEventListener = () => {};
+  globalThis.removeEventListener = () => {};
+  if (typeof globalThis.setTimeout !== "function") {
+    const timers = [];
+    globalThis.__dossiersTimers = timers;
+    globalThis.setTimeout = (cb, _ms, ...args) => {
+      timers.push(() => cb(...args));
+      return timers.length;
+    };
+    globalThis.clearTimeout = () => {};
+  }
+  // Legacy `RegExp.$1`..`$9`, still used by a bundled path parser.
+  if (!("$1" in RegExp)) {
+    const exec = RegExp.prototype.exec;
+    RegExp.prototype.exec = function (input) {
+      const match = exec.call(this, input);
+      if (match) {
+        for (let i = 1; i <= 9; i++) RegExp["$" + i] = match[i] === undefined ? "" : match[i];
+      }
+      return match;
+    };
+  }
+  if (typeof globalThis.btoa !== "function") {
+    const chars = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    globalThis.btoa = (input) => {
+      const str = String(input);
+      let out = "";
+      for (let i = 0; i < str.length; i += 3) {
+        const a = str.charCodeAt(i);
+        const b = str.charCodeAt(i + 1);
+        const c = str.charCodeAt(i + 2);
+        const n = (a << 16) | ((b || 0) << 8) | (c || 0);
+        out += chars[(n >> 18) & 63] + chars[(n >> 12) & 63];
+        out += isNaN(b) ? "=" : chars[(n >> 6) & 63];
+        out += isNaN(c) ? "=" : chars[n & 63];
+      }
+      return out;
+    };
+    globalThis.atob = (input) => {
+      const str = String(input).replace(/[^A-Za-z0-9+/]/g, "");
+      let out = "";
+      for (let i = 0; i < str.length; i += 4) {
+        const n =
+          (chars.indexOf(str[i]) << 18) |
+          (chars.indexOf(str[i + 1]) << 12) |
+          ((chars.indexOf(str[i + 2]) & 63) << 6) |
+          (chars.indexOf(str[i + 3]) & 63);
+        out += String.fromCharCode((n >> 16) & 255);
+        if (str[i + 2]) out += String.fromCharCode((n >> 8) & 255);
+        if (str[i + 3]) out += String.fromCharCode(n & 255);
+      }
+      return out;
+    };
+  }
+  if (typeof globalThis.structuredClone !== "function") {
+    const clone = (value, seen) => {
+      if (value === null || typeof value !== "object") return value;
+      if (seen.has(value)) return seen.get(value);
+      let copy;
+      if (value instanceof Date) return new Date(value.getTime());
+      if (value instanceof RegExp) return new RegExp(value.source, value.flags);
+      if (value instanceof Map) {
+        copy = new Map();
+        seen.set(value, copy);
+        value.forEach((v, k) => copy.set(clone(k, seen), clone(v, seen)));
+        return copy;
+      }
+      if (value instanceof Set) {
+        copy = new Set();
+        seen.set(value, copy);
+        value.forEach((v) => copy.add(clone(v, seen)));
+        return copy;
+      }
+      copy = Array.isArray(value) ? [] : {};
+      seen.set(value, copy);
+      for (const key of Object.keys(value)) copy[key] = clone(value[key], seen);
+      return copy;
+    };
+    globalThis.structuredClone = (value) => clone(value, new Map());
+  }
+  if (typeof globalThis.console === "undefined") {
+    const noop = () => {};
+    globalThis.console = { log: noop, warn: noop, error: noop, info: noop, debug: noop, trace: noop };
+  }
+
+  // Entry points driven from Rust: `start` kicks off one render, `poll` runs
+  // queued timers and reports completion, `result` holds the outcome.
+  let current = { done: true };
+  globalThis.dossiersMermaid = {
+    configure() {
+      globalThis.mermaid.initialize({
+        startOnLoad: false,
+        theme: "default",
+        securityLevel: "strict",
+        htmlLabels: false,
+        flowchart: { htmlLabels: false, useMaxWidth: true },
+        sequence: { useMaxWidth: true },
+      });
+    },
+    start(id, source) {
+      const state = { done: false };
+      current = state;
+      globalThis.mermaid.render(id, source).then(
+        (result) => {
+          state.svg = result.svg;
+          state.done = true;
+        },
+        (err) => {
+          state.error = String((err && err.message) || err);
+          state.done = true;
+        }
+      );
+    },
+    poll() {
+      const timers = globalThis.__dossiersTimers || [];
+      timers.splice(0).forEach((run) => run());
+      return current.done;
+    },
+    result() {
+      document.body.textContent = "";
+      return current;
+    },
+  };
+})();
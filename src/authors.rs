@@ -8,12 +8,26 @@
 //! back to a GitHub-noreply-derived avatar or a Gravatar computed from the
 //! email. Every lookup is best-effort: with no GitHub token/repo the local
 //! fallbacks still yield an avatar for any address.
+//!
+//! Git identities pass through the repository's `.mailmap` first, and the
+//! `[authors]` table in dossiers.toml then folds aliases and addresses into one
+//! canonical name, optionally pinning that person's GitHub login or avatar:
+//!
+//! ```toml
+//! [authors."Ada Lovelace"]
+//! aliases = ["ada", "A. Lovelace"]
+//! emails = ["ada@example.com", "ada@old.example.com"]
+//! github = "ada"
+//! ```
 
 use std::collections::HashMap;
 
+use serde_json::Value as JsonValue;
+
 use crate::bundle::Author;
+use crate::forge::{CommitIdentity, Forge};
 use crate::git_utils::GitAuthor;
-use crate::github::{CommitIdentity, GithubClient};
+use crate::github::GithubHost;
 
 /// The inputs needed to resolve one author: the display name plus the optional
 /// email and addition-commit SHA that drive the avatar lookup.
@@ -24,6 +38,95 @@ pub struct AuthorSeed {
     pub commit_sha: Option<String>,
 }
 
+/// One person from `[authors]`: the name they are credited under, the other
+/// spellings and addresses that mean them, and optionally the GitHub login or
+/// avatar to show for them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthorAlias {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub emails: Vec<String>,
+    pub github: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// The `[authors]` table: every configured person, keyed by canonical name.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorDirectory {
+    people: Vec<AuthorAlias>,
+}
+
+impl AuthorDirectory {
+    pub fn new(people: Vec<AuthorAlias>) -> Self {
+        Self { people }
+    }
+
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let Some(table) = value.as_object() else {
+            return Self::default();
+        };
+        let text = |entry: &JsonValue, key: &str| {
+            entry
+                .get(key)
+                .and_then(JsonValue::as_str)
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let list = |entry: &JsonValue, key: &str| -> Vec<String> {
+            entry
+                .get(key)
+                .and_then(JsonValue::as_array)
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(JsonValue::as_str)
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let people = table
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .map(|(name, entry)| AuthorAlias {
+                name: name.trim().to_string(),
+                aliases: list(entry, "aliases"),
+                emails: list(entry, "emails"),
+                github: text(entry, "github")
+                    .map(|login| login.trim_start_matches('@').to_string()),
+                avatar: text(entry, "avatar"),
+            })
+            .collect();
+        Self { people }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.people.is_empty()
+    }
+
+    /// The person an author name or email refers to. Names match the
+    /// canonical name, an alias or the GitHub login; all comparisons ignore
+    /// case. An email match wins over a name match.
+    pub fn find(&self, name: &str, email: Option<&str>) -> Option<&AuthorAlias> {
+        let same = |a: &str, b: &str| a.trim().eq_ignore_ascii_case(b.trim());
+        let by_email = email.and_then(|email| {
+            self.people
+                .iter()
+                .find(|person| person.emails.iter().any(|known| same(known, email)))
+        });
+        by_email.or_else(|| {
+            self.people.iter().find(|person| {
+                same(&person.name, name)
+                    || person.aliases.iter().any(|alias| same(alias, name))
+                    || person
+                        .github
+                        .as_deref()
+                        .is_some_and(|login| same(login, name))
+            })
+        })
+    }
+}
+
 /// A GitHub account resolved for an author: its avatar and profile URL.
 #[derive(Debug, Clone)]
 struct GitHubIdentity {
@@ -38,6 +141,10 @@ struct GitHubIdentity {
 pub struct AuthorResolver {
     by_sha: HashMap<String, CommitIdentity>,
     by_email: HashMap<String, GitHubIdentity>,
+    /// The GitHub instance profile links and noreply avatars point at.
+    host: GithubHost,
+    /// Canonical names and pinned identities from `[authors]`.
+    directory: AuthorDirectory,
 }
 
 impl AuthorResolver {
@@ -46,14 +153,46 @@ impl AuthorResolver {
         Self::default()
     }
 
-    /// Resolve every unique addition-commit SHA across `seeds` via the GitHub
+    /// Derive profile links and noreply avatars on a GitHub Enterprise Server
+    /// instead of github.com.
+    pub fn with_host(mut self, host: GithubHost) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Credit authors under the canonical names in `[authors]`.
+    pub fn with_directory(mut self, directory: AuthorDirectory) -> Self {
+        self.directory = directory;
+        self
+    }
+
+    /// A forge account, such as a pull request's author, as an author: under
+    /// its canonical name when `[authors]` knows the login, else as itself.
+    pub fn resolve_login(
+        &self,
+        login: &str,
+        avatar_url: Option<String>,
+        profile_url: String,
+    ) -> Author {
+        let fallback = Author {
+            name: login.to_string(),
+            avatar_url,
+            url: Some(profile_url),
+        };
+        match self.directory.find(login, None) {
+            Some(person) => pinned_author(person, &self.host).unwrap_or(Author {
+                name: person.name.clone(),
+                ..fallback
+            }),
+            None => fallback,
+        }
+    }
+
+    /// Resolve every unique addition-commit SHA across `seeds` via the forge's
     /// commits API (deduplicated, one call per SHA), building a sha→identity
-    /// cache plus an email→GitHub-account map that other specs' frontmatter
-    /// authors can be matched against. GitHub failures are logged and skipped.
-    pub fn from_github<'a>(
-        client: &GithubClient,
-        seeds: impl Iterator<Item = &'a AuthorSeed>,
-    ) -> Self {
+    /// cache plus an email→account map that other specs' frontmatter authors
+    /// can be matched against. Forge failures are logged and skipped.
+    pub fn from_forge<'a>(client: &dyn Forge, seeds: impl Iterator<Item = &'a AuthorSeed>) -> Self {
         let mut by_sha: HashMap<String, CommitIdentity> = HashMap::new();
         for seed in seeds {
             if let Some(sha) = seed.commit_sha.as_deref() {
@@ -61,7 +200,11 @@ impl AuthorResolver {
                     continue;
                 }
                 match client.get_commit(sha) {
-                    Ok(identity) => {
+                    Ok(mut identity) => {
+                        if identity.html_url.is_none() {
+                            identity.html_url =
+                                identity.login.as_deref().map(|l| client.profile_url(l));
+                        }
                         by_sha.insert(sha.to_string(), identity);
                     }
                     Err(err) => {
@@ -74,15 +217,22 @@ impl AuthorResolver {
             }
         }
 
+        let host = GithubHost::default();
         let mut by_email: HashMap<String, GitHubIdentity> = HashMap::new();
         for identity in by_sha.values() {
-            if let (Some(email), Some(gh)) = (identity.email.as_deref(), github_identity(identity))
+            if let (Some(email), Some(gh)) =
+                (identity.email.as_deref(), github_identity(identity, &host))
             {
                 by_email.entry(email.trim().to_lowercase()).or_insert(gh);
             }
         }
 
-        Self { by_sha, by_email }
+        Self {
+            by_sha,
+            by_email,
+            host,
+            directory: AuthorDirectory::default(),
+        }
     }
 }
 
@@ -143,9 +293,50 @@ pub fn resolve_authors(seeds: &[AuthorSeed], resolver: &AuthorResolver) -> Vec<A
 }
 
 fn resolve_one(seed: &AuthorSeed, resolver: &AuthorResolver) -> Author {
+    // 0. `[authors]` renames the author, and may pin their account or avatar.
+    let person = resolver.directory.find(&seed.name, seed.email.as_deref());
+    if let Some(author) = person.and_then(|person| pinned_author(person, &resolver.host)) {
+        return author;
+    }
+    let Some(person) = person else {
+        return resolve_identity(seed, resolver);
+    };
+    let seed = AuthorSeed {
+        name: person.name.clone(),
+        email: seed
+            .email
+            .clone()
+            .or_else(|| person.emails.first().cloned()),
+        commit_sha: seed.commit_sha.clone(),
+    };
+    resolve_identity(&seed, resolver)
+}
+
+/// A configured person's pinned avatar or GitHub account, if they have one.
+fn pinned_author(person: &AuthorAlias, host: &GithubHost) -> Option<Author> {
+    let url = person
+        .github
+        .as_deref()
+        .map(|login| host.profile_url(login));
+    let avatar_url = person
+        .avatar
+        .clone()
+        .or_else(|| person.github.as_deref().map(|login| host.avatar_url(login)))?;
+    Some(Author {
+        name: person.name.clone(),
+        avatar_url: Some(avatar_url),
+        url,
+    })
+}
+
+fn resolve_identity(seed: &AuthorSeed, resolver: &AuthorResolver) -> Author {
     // 1. The author's own addition commit resolved to a GitHub account.
     if let Some(sha) = seed.commit_sha.as_deref() {
-        if let Some(gh) = resolver.by_sha.get(sha).and_then(github_identity) {
+        if let Some(gh) = resolver
+            .by_sha
+            .get(sha)
+            .and_then(|identity| github_identity(identity, &resolver.host))
+        {
             return with_avatar(&seed.name, gh);
         }
     }
@@ -160,7 +351,7 @@ fn resolve_one(seed: &AuthorSeed, resolver: &AuthorResolver) -> Author {
             return with_avatar(&seed.name, gh.clone());
         }
         // 3. A GitHub noreply email encodes the account — derive it offline.
-        if let Some((avatar_url, profile_url)) = github_from_noreply(email) {
+        if let Some((avatar_url, profile_url)) = github_from_noreply(email, &resolver.host) {
             return Author {
                 name: seed.name.clone(),
                 avatar_url: Some(avatar_url),
@@ -190,14 +381,14 @@ fn with_avatar(name: &str, gh: GitHubIdentity) -> Author {
     }
 }
 
-fn github_identity(identity: &CommitIdentity) -> Option<GitHubIdentity> {
+fn github_identity(identity: &CommitIdentity, host: &GithubHost) -> Option<GitHubIdentity> {
     let avatar_url = identity.avatar_url.clone().filter(|s| !s.is_empty())?;
     let login = identity.login.clone().filter(|s| !s.is_empty());
     let profile_url = identity
         .html_url
         .clone()
         .filter(|s| !s.is_empty())
-        .or_else(|| login.map(|l| format!("https://github.com/{l}")))
+        .or_else(|| login.map(|l| host.profile_url(&l)))
         .unwrap_or_default();
     Some(GitHubIdentity {
         avatar_url,
@@ -223,18 +414,17 @@ pub fn split_name_email(raw: &str) -> (String, Option<String>) {
 /// GitHub serves an account's avatar at `github.com/<login>.png`, and its
 /// noreply commit email encodes the login
 /// (`<login>@users.noreply.github.com` or
-/// `<id>+<login>@users.noreply.github.com`).
-fn github_from_noreply(email: &str) -> Option<(String, String)> {
+/// `<id>+<login>@users.noreply.github.com`). An Enterprise Server does the
+/// same under its own host name.
+fn github_from_noreply(email: &str, host: &GithubHost) -> Option<(String, String)> {
     let local = email.trim().to_lowercase();
-    let user = local.strip_suffix("@users.noreply.github.com")?;
+    let domain = format!("@users.noreply.{}", host.hostname().to_lowercase());
+    let user = local.strip_suffix(domain.as_str())?;
     let login = user.rsplit('+').next().unwrap_or(user);
     if login.is_empty() {
         return None;
     }
-    Some((
-        format!("https://github.com/{login}.png?size=160"),
-        format!("https://github.com/{login}"),
-    ))
+    Some((host.avatar_url(login), host.profile_url(login)))
 }
 
 /// Gravatar image URL for an email, falling back to a generated identicon when
@@ -315,6 +505,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn enterprise_noreply_email_links_to_that_host() {
+        let host = GithubHost::new(Some("https://ghe.example.com/"), None);
+        let seeds = vec![AuthorSeed {
+            name: "Octo".into(),
+            email: Some("7+octocat@users.noreply.ghe.example.com".into()),
+            commit_sha: None,
+        }];
+        let authors = resolve_authors(&seeds, &AuthorResolver::local().with_host(host));
+        assert_eq!(
+            authors[0].avatar_url.as_deref(),
+            Some("https://ghe.example.com/octocat.png?size=160")
+        );
+        assert_eq!(
+            authors[0].url.as_deref(),
+            Some("https://ghe.example.com/octocat")
+        );
+
+        // A github.com noreply address means nothing on another host.
+        let seeds = vec![AuthorSeed {
+            email: Some("octocat@users.noreply.github.com".into()),
+            ..seeds[0].clone()
+        }];
+        let host = GithubHost::new(Some("https://ghe.example.com"), None);
+        let authors = resolve_authors(&seeds, &AuthorResolver::local().with_host(host));
+        assert!(authors[0].url.is_none());
+    }
+
+    #[test]
+    fn configured_aliases_share_one_canonical_author() {
+        let directory = AuthorDirectory::from_json_value(&serde_json::json!({
+            "Ada Lovelace": {
+                "aliases": ["ada", "A. Lovelace"],
+                "emails": ["ada@old.example.com"],
+                "github": "@ada",
+            },
+            "Grace Hopper": { "emails": ["grace@example.com"] },
+        }));
+        let resolver = AuthorResolver::local().with_directory(directory);
+        let seed = |name: &str, email: Option<&str>| AuthorSeed {
+            name: name.into(),
+            email: email.map(str::to_string),
+            commit_sha: None,
+        };
+        let authors = resolve_authors(
+            &[
+                seed("a. lovelace", None),
+                seed("Countess", Some("ADA@old.example.com")),
+                seed("Grace", Some("grace@example.com")),
+                seed("Alan", None),
+            ],
+            &resolver,
+        );
+        let names: Vec<&str> = authors.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Ada Lovelace", "Ada Lovelace", "Grace Hopper", "Alan"]
+        );
+        assert_eq!(
+            authors[0].avatar_url.as_deref(),
+            Some("https://github.com/ada.png?size=160")
+        );
+        assert_eq!(authors[1].url.as_deref(), Some("https://github.com/ada"));
+        // No pinned account: the configured name, with the address's Gravatar.
+        assert!(authors[2]
+            .avatar_url
+            .as_deref()
+            .is_some_and(|url| url.starts_with("https://www.gravatar.com/")));
+
+        let pr_author = resolver.resolve_login("ADA", None, "https://github.com/ADA".into());
+        assert_eq!(pr_author.name, "Ada Lovelace");
+        let stranger = resolver.resolve_login("bob", None, "https://github.com/bob".into());
+        assert_eq!(stranger.name, "bob");
+    }
+
     #[test]
     fn plain_email_falls_back_to_gravatar() {
         let seeds = vec![AuthorSeed {
@@ -0,0 +1,343 @@
+//! Self-hosted author avatars for static builds.
+//!
+//! Author avatars normally point at GitHub, GitLab or Gravatar, which leaves
+//! broken images on sites served inside a network without internet access.
+//! With `[avatars] self_host = true` (or `build --self-host-avatars`) each
+//! distinct avatar is downloaded once, kept in an on-disk cache, and written
+//! into the output under `assets/avatars/<hash>.<ext>`. An avatar that cannot
+//! be fetched — the build is offline, the host is unreachable, the response is
+//! not an image — is replaced by a generated initials SVG whose colour and
+//! pattern derive from the author, so every build draws the same picture.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use dossiers::bundle::Author;
+
+/// Default on-disk cache location, relative to the project root.
+const DEFAULT_CACHE_DIR: &str = "target/avatar-cache";
+
+/// Where self-hosted avatars live in the output, relative to the site root.
+const AVATAR_DIR: &str = "assets/avatars";
+
+/// Extensions recognised when looking an avatar up in the cache.
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "gif", "webp", "svg"];
+
+/// The `[avatars]` section of `dossiers.toml`.
+///
+/// ```toml
+/// [avatars]
+/// self_host = true
+/// cache_dir = "target/avatar-cache"
+/// ```
+#[derive(Debug, Clone)]
+pub struct AvatarConfig {
+    /// Whether static builds download avatars into the output.
+    pub self_host: bool,
+    pub cache_dir: Option<String>,
+    /// Whether downloaded avatars are cached on disk (default true).
+    pub disk_cache: bool,
+}
+
+impl Default for AvatarConfig {
+    fn default() -> Self {
+        Self {
+            self_host: false,
+            cache_dir: None,
+            disk_cache: true,
+        }
+    }
+}
+
+impl AvatarConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let mut config = Self::default();
+        let Some(map) = value.as_object() else {
+            return config;
+        };
+
+        for (key, entry) in map {
+            match (key.as_str(), entry) {
+                ("self_host" | "selfHost", JsonValue::Bool(enabled)) => config.self_host = *enabled,
+                ("cache_dir" | "cacheDir", JsonValue::String(dir)) => {
+                    config.cache_dir = Some(dir.trim().to_string()).filter(|d| !d.is_empty());
+                }
+                ("cache", JsonValue::Bool(enabled)) => config.disk_cache = *enabled,
+                _ => {}
+            }
+        }
+        config
+    }
+}
+
+/// Downloads, caches and generates avatars for one static build.
+pub struct AvatarStore {
+    cache_dir: Option<PathBuf>,
+    client: Option<reqwest::blocking::Client>,
+    /// Remote URL → site path of its local copy.
+    resolved: HashMap<String, String>,
+    /// File name under `assets/avatars` → contents.
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl AvatarStore {
+    /// A store for a build rooted at `project_root`. When `offline`, nothing is
+    /// downloaded: cached avatars are reused and the rest are generated.
+    pub fn new(config: &AvatarConfig, project_root: &Path, offline: bool) -> Self {
+        let cache_dir = config.disk_cache.then(|| {
+            let dir = config.cache_dir.as_deref().unwrap_or(DEFAULT_CACHE_DIR);
+            project_root.join(dir)
+        });
+        let client = (!offline)
+            .then(|| {
+                reqwest::blocking::Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .user_agent(concat!("dossiers/", env!("CARGO_PKG_VERSION")))
+                    .build()
+                    .ok()
+            })
+            .flatten();
+
+        Self {
+            cache_dir,
+            client,
+            resolved: HashMap::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Point `author`'s avatar at a local copy. Authors without an avatar are
+    /// left alone.
+    pub fn localize(&mut self, author: &mut Author) {
+        let Some(url) = author.avatar_url.clone() else {
+            return;
+        };
+        if url.starts_with(&format!("/{AVATAR_DIR}/")) {
+            return;
+        }
+        if let Some(path) = self.resolved.get(&url) {
+            author.avatar_url = Some(path.clone());
+            return;
+        }
+
+        let hash = avatar_hash(&url);
+        let file_name = match self.cached(&hash).or_else(|| self.download(&url, &hash)) {
+            Some((bytes, ext)) => {
+                let name = format!("{hash}.{ext}");
+                self.files.insert(name.clone(), bytes);
+                name
+            }
+            None => {
+                let name = format!("{hash}.svg");
+                self.files.insert(
+                    name.clone(),
+                    generated_avatar(&author.name, &url).into_bytes(),
+                );
+                name
+            }
+        };
+
+        let path = format!("/{AVATAR_DIR}/{file_name}");
+        self.resolved.insert(url, path.clone());
+        author.avatar_url = Some(path);
+    }
+
+    /// Number of avatars that will be written.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Write every localized avatar under `output_root`.
+    pub fn write(&self, output_root: &Path) -> Result<()> {
+        if self.files.is_empty() {
+            return Ok(());
+        }
+        let dir = output_root.join(AVATAR_DIR);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Creating avatar directory {}", dir.display()))?;
+        for (name, bytes) in &self.files {
+            let target = dir.join(name);
+            fs::write(&target, bytes)
+                .with_context(|| format!("Writing avatar to {}", target.display()))?;
+        }
+        Ok(())
+    }
+
+    fn cached(&self, hash: &str) -> Option<(Vec<u8>, &'static str)> {
+        let dir = self.cache_dir.as_ref()?;
+        IMAGE_EXTENSIONS.iter().find_map(|ext| {
+            fs::read(dir.join(format!("{hash}.{ext}")))
+                .ok()
+                .map(|bytes| (bytes, *ext))
+        })
+    }
+
+    fn download(&mut self, url: &str, hash: &str) -> Option<(Vec<u8>, &'static str)> {
+        let client = self.client.as_ref()?;
+        let fetched = client
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes());
+        let bytes = match fetched {
+            Ok(bytes) => bytes.to_vec(),
+            Err(err) => {
+                eprintln!("Warning: failed to download avatar {url}: {err}");
+                // An unreachable network fails every download the same way;
+                // fall back to generated avatars instead of waiting on each.
+                if err.is_connect() || err.is_timeout() {
+                    self.client = None;
+                }
+                return None;
+            }
+        };
+        let Some(ext) = image_extension(&bytes) else {
+            eprintln!("Warning: avatar {url} is not a recognised image");
+            return None;
+        };
+
+        if let Some(dir) = &self.cache_dir {
+            let path = dir.join(format!("{hash}.{ext}"));
+            let _ = dossiers::http_cache::create_cache_dir(dir);
+            if let Err(err) = fs::write(&path, &bytes) {
+                eprintln!(
+                    "Warning: failed to cache avatar at {}: {err}",
+                    path.display()
+                );
+            }
+        }
+        Some((bytes, ext))
+    }
+}
+
+/// Stable file name stem for an avatar URL.
+fn avatar_hash(url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// File extension for image `bytes`, sniffed from their signature.
+fn image_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("jpg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("gif")
+    } else if bytes.len() > 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]);
+        let head = head.trim_start();
+        (head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg")))
+            .then_some("svg")
+    }
+}
+
+/// Up to two initials for `name`: the first letters of its first and last
+/// words.
+fn initials(name: &str) -> String {
+    let words: Vec<char> = name
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_' || c == '.')
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .collect();
+    match words.as_slice() {
+        [] => String::new(),
+        [only] => only.to_uppercase().collect(),
+        [first, .., last] => first.to_uppercase().chain(last.to_uppercase()).collect(),
+    }
+}
+
+/// A deterministic 64×64 SVG avatar for `name`: its initials on a colour taken
+/// from `seed`, or a symmetric 5×5 identicon when the name has no letters.
+pub fn generated_avatar(name: &str, seed: &str) -> String {
+    let digest = Sha256::digest(seed.as_bytes());
+    let hue = u16::from_be_bytes([digest[0], digest[1]]) % 360;
+    let background = format!("hsl({hue}, 45%, 48%)");
+    let letters = initials(name);
+
+    let mut svg = String::from(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" viewBox="0 0 64 64">"#,
+    );
+    if letters.is_empty() {
+        svg.push_str(r##"<rect width="64" height="64" fill="#f0f0f0"/>"##);
+        for row in 0..5 {
+            for col in 0..3 {
+                if digest[2 + row * 3 + col] & 1 == 0 {
+                    continue;
+                }
+                for x in [col, 4 - col] {
+                    svg.push_str(&format!(
+                        r#"<rect x="{}" y="{}" width="12" height="12" fill="{background}"/>"#,
+                        2 + x * 12,
+                        2 + row * 12
+                    ));
+                }
+            }
+        }
+    } else {
+        svg.push_str(&format!(
+            r##"<rect width="64" height="64" fill="{background}"/><text x="32" y="32" dy="0.35em" text-anchor="middle" font-family="system-ui, sans-serif" font-size="26" font-weight="600" fill="#ffffff">{letters}</text>"##
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(name: &str, avatar: Option<&str>) -> Author {
+        Author {
+            name: name.into(),
+            avatar_url: avatar.map(str::to_string),
+            url: None,
+        }
+    }
+
+    #[test]
+    fn offline_builds_generate_stable_initials_avatars() {
+        let root = std::env::temp_dir().join(format!("dossiers-avatars-{}", std::process::id()));
+        let config = AvatarConfig {
+            self_host: true,
+            cache_dir: None,
+            disk_cache: false,
+        };
+        let mut store = AvatarStore::new(&config, &root, true);
+
+        let url = "https://avatars.githubusercontent.com/u/1?v=4";
+        let mut ada = author("Ada Lovelace", Some(url));
+        let mut again = author("Ada Lovelace", Some(url));
+        let mut nobody = author("Nobody", None);
+        store.localize(&mut ada);
+        store.localize(&mut again);
+        store.localize(&mut nobody);
+
+        let path = ada.avatar_url.clone().expect("localized avatar");
+        assert!(path.starts_with("/assets/avatars/") && path.ends_with(".svg"));
+        assert_eq!(again.avatar_url, ada.avatar_url);
+        assert_eq!(nobody.avatar_url, None);
+        assert_eq!(store.len(), 1);
+
+        let svg = generated_avatar("Ada Lovelace", url);
+        assert_eq!(svg, generated_avatar("Ada Lovelace", url));
+        assert!(svg.contains(">AL</text>"));
+        assert_ne!(svg, generated_avatar("Ada Lovelace", "another seed"));
+        assert!(generated_avatar("???", url).contains("<rect x="));
+
+        store.write(&root).expect("write avatars");
+        let written = root.join(path.trim_start_matches('/'));
+        assert_eq!(fs::read_to_string(&written).unwrap(), svg);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -9,28 +9,42 @@
 //!
 //! ```text
 //! manifest.json
+//! manifest.sig                (optional)
 //! project.toml                (optional)
 //! main/<dir>/<file>
 //! prs/<n>/meta.json
 //! prs/<n>/specs/<dir>/<file>
 //! ```
 //!
+//! The manifest records a SHA-256 digest of every other file, checked when a
+//! package is read. A package may also carry `manifest.sig`, an ed25519
+//! signature over the manifest bytes; since the manifest pins every digest,
+//! the signature covers the whole package.
+//!
 //! See `specs/0007-api-server` § Package format for the authoritative
 //! description.
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::{Read, Seek, Write};
-use std::path::Path;
+use std::path::{Component, Path};
 
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, Verifier};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
 pub const PACKAGE_VERSION: u32 = 1;
 
+const MANIFEST_FILE: &str = "manifest.json";
+const SIGNATURE_FILE: &str = "manifest.sig";
+
 #[derive(Debug, Clone)]
 pub struct Package {
     pub manifest: Manifest,
@@ -69,6 +83,17 @@ pub struct Manifest {
     /// `base_commit`. Delta mode only.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deleted_prs: Vec<u64>,
+    /// `sha256:<hex>` digest of every other file in the package, keyed by
+    /// in-zip path. Filled in when the package is written and checked when it
+    /// is read; absent in packages from older CLIs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub digests: BTreeMap<String, String>,
+    /// Files of upserted specs left out of a delta package because they are
+    /// identical to the receiver's copy at `base_commit`, keyed by in-zip path
+    /// with their digest. The receiver reuses the content it already holds.
+    /// Delta mode only.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unchanged: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +105,10 @@ pub struct SpecIndexEntry {
     pub format: DocFormat,
     pub title: String,
     pub status: String,
+    /// `"internal"` or `"private"` for a spec not meant for everyone; absent
+    /// for public specs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub created: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -92,6 +121,16 @@ pub struct SpecIndexEntry {
     /// index; this carries the presentation data those consumers don't need.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub authors_meta: Vec<Author>,
+    /// Logins that approved the merged pull request which last changed the
+    /// spec, as recorded by the forge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approved_by: Vec<String>,
+    /// Owners of the spec's document according to the repository's CODEOWNERS.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Teams the spec belongs to, declared or through a member author/owner.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<String>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub extra: BTreeMap<String, serde_json::Value>,
     /// Producer-resolved outbound links (the document's `links:` frontmatter).
@@ -220,6 +259,13 @@ pub struct PrChangeSet {
     /// PR creation / last-update times.
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Logins asked to review who have not yet done so.
+    pub reviewers: Vec<String>,
+    /// Each reviewer's standing verdict.
+    pub reviews: Vec<PrReview>,
+    pub labels: Vec<String>,
+    /// The commit the PR landed as, once merged.
+    pub merge_commit_sha: Option<String>,
     pub spec_changes: Vec<SpecChange>,
     pub asset_changes: Vec<AssetChange>,
     /// Resolved metadata for each target spec the PR touches (title, status,
@@ -228,12 +274,25 @@ pub struct PrChangeSet {
     pub spec_meta: Vec<PrSpecMeta>,
 }
 
+/// A reviewer's standing verdict on a PR.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrReview {
+    pub reviewer: String,
+    /// `"APPROVED"`, `"CHANGES_REQUESTED"` or `"COMMENTED"`.
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
 /// Producer-resolved metadata for one spec targeted by a PR change-set.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrSpecMeta {
     pub spec_id: String,
     pub title: String,
     pub status: String,
+    /// The PR version's visibility, as in [`SpecIndexEntry::visibility`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub authors: Vec<String>,
     /// Rich author identities parallel to `authors` (see `SpecIndexEntry`).
@@ -273,6 +332,21 @@ pub enum BundleError {
     Json(#[from] serde_json::Error),
     #[error("invalid package: {0}")]
     Invalid(String),
+    #[error("integrity check failed: {0}")]
+    Integrity(String),
+    #[error("invalid key: {0}")]
+    Key(String),
+}
+
+/// A file in the package: its in-zip path and contents.
+type Entry<'a> = (String, Cow<'a, [u8]>);
+
+/// Problems found by [`Package::verify`]. Errors are inconsistencies a
+/// consumer would reject or misapply; warnings are tolerated but suspicious.
+#[derive(Debug, Default)]
+pub struct Verification {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -292,6 +366,14 @@ struct PrMetaWire {
     created_at: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reviewers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reviews: Vec<PrReview>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    merge_commit_sha: Option<String>,
     #[serde(default)]
     removed_specs: Vec<String>,
     #[serde(default)]
@@ -306,23 +388,106 @@ struct RemovedAssetWire {
     path: String,
 }
 
+/// Contents of `manifest.sig`: a detached signature over the exact
+/// `manifest.json` bytes, with the public key that made it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignatureWire {
+    algorithm: String,
+    public_key: String,
+    signature: String,
+}
+
 impl Package {
     /// Serialise this package as a zip archive.
     pub fn write_zip<W: Write + Seek>(&self, writer: W) -> Result<W, BundleError> {
-        let mut zw = ZipWriter::new(writer);
-        let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        write_entries(self.entries(None)?, writer)
+    }
 
-        let manifest_json = serde_json::to_vec_pretty(&self.manifest)?;
-        zw.start_file("manifest.json", opts)?;
-        zw.write_all(&manifest_json)?;
+    /// Serialise this package as a zip archive signed with `key`.
+    pub fn write_zip_signed<W: Write + Seek>(
+        &self,
+        writer: W,
+        key: &SigningKey,
+    ) -> Result<W, BundleError> {
+        write_entries(self.entries(Some(key))?, writer)
+    }
 
+    /// Write this package to `dir` using the same layout as the zip archive,
+    /// so a package can be examined with ordinary tools. Entries whose path
+    /// would escape `dir` are rejected.
+    pub fn write_dir(&self, dir: &Path) -> Result<(), BundleError> {
+        let entries = self.entries(None)?;
+        if let Some((path, _)) = entries.iter().find(|(path, _)| {
+            !Path::new(path)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        }) {
+            return Err(BundleError::Invalid(format!(
+                "entry {path} escapes the output directory"
+            )));
+        }
+
+        for (path, bytes) in entries {
+            let target = dir.join(&path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, &bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Every file in the package as `(in-zip path, bytes)`, in archive order.
+    /// The manifest is written with the digest of every other entry, followed
+    /// by its signature when a key is given.
+    fn entries(&self, key: Option<&SigningKey>) -> Result<Vec<Entry<'_>>, BundleError> {
+        let mut entries = self.content_entries()?;
+        let mut manifest = self.manifest.clone();
+        manifest.digests = entries
+            .iter()
+            .map(|(path, bytes)| (path.clone(), digest(bytes)))
+            .collect();
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+
+        let signature = key
+            .map(|key| {
+                serde_json::to_vec_pretty(&SignatureWire {
+                    algorithm: "ed25519".to_string(),
+                    public_key: encode_hex(key.verifying_key().as_bytes()),
+                    signature: encode_hex(&key.sign(&manifest_bytes).to_bytes()),
+                })
+            })
+            .transpose()?;
+        let mut head = vec![(MANIFEST_FILE.to_string(), Cow::Owned(manifest_bytes))];
+        if let Some(bytes) = signature {
+            head.push((SIGNATURE_FILE.to_string(), Cow::Owned(bytes)));
+        }
+        entries.splice(0..0, head);
+        Ok(entries)
+    }
+
+    /// Digest of the package's files and manifest, ignoring when it was
+    /// packaged: two packages of the same content share a fingerprint.
+    pub fn fingerprint(&self) -> Result<String, BundleError> {
+        let mut manifest = self.manifest.clone();
+        manifest.timestamp = DateTime::UNIX_EPOCH;
+        manifest.digests = self
+            .content_entries()?
+            .iter()
+            .map(|(path, bytes)| (path.clone(), digest(bytes)))
+            .collect();
+        Ok(digest(&serde_json::to_vec(&manifest)?))
+    }
+
+    /// Every file except the manifest and its signature.
+    fn content_entries(&self) -> Result<Vec<Entry<'_>>, BundleError> {
+        let mut entries = Vec::new();
         if let Some(bytes) = &self.project_config {
-            zw.start_file("project.toml", opts)?;
-            zw.write_all(bytes)?;
+            entries.push(("project.toml".to_string(), Cow::Borrowed(bytes.as_slice())));
         }
 
         for spec in &self.mainline.specs {
-            write_spec(&mut zw, "main", spec, opts)?;
+            spec_entries(&mut entries, "main", spec);
         }
 
         for pr in &self.pr_changes {
@@ -336,6 +501,10 @@ impl Package {
                 url: pr.url.clone(),
                 created_at: pr.created_at,
                 updated_at: pr.updated_at,
+                reviewers: pr.reviewers.clone(),
+                reviews: pr.reviews.clone(),
+                labels: pr.labels.clone(),
+                merge_commit_sha: pr.merge_commit_sha.clone(),
                 spec_meta: pr.spec_meta.clone(),
                 removed_specs: pr
                     .spec_changes
@@ -359,27 +528,29 @@ impl Package {
             };
 
             let prefix = format!("prs/{}", pr.pr_number);
-            zw.start_file(format!("{prefix}/meta.json"), opts)?;
-            zw.write_all(&serde_json::to_vec_pretty(&meta)?)?;
+            entries.push((
+                format!("{prefix}/meta.json"),
+                Cow::Owned(serde_json::to_vec_pretty(&meta)?),
+            ));
 
             let pr_specs_prefix = format!("{prefix}/specs");
             for change in &pr.spec_changes {
                 if let SpecChange::Upsert(spec) = change {
-                    write_spec(&mut zw, &pr_specs_prefix, spec, opts)?;
+                    spec_entries(&mut entries, &pr_specs_prefix, spec);
                 }
             }
 
             for change in &pr.asset_changes {
                 if let AssetChange::Upsert { spec_id, asset } = change {
-                    let path = format!("{prefix}/specs/{spec_id}/{}", asset.path);
-                    zw.start_file(&path, opts)?;
-                    zw.write_all(&asset.bytes)?;
+                    entries.push((
+                        format!("{prefix}/specs/{spec_id}/{}", asset.path),
+                        Cow::Borrowed(asset.bytes.as_slice()),
+                    ));
                 }
             }
         }
 
-        let writer = zw.finish()?;
-        Ok(writer)
+        Ok(entries)
     }
 
     /// Build a `Mainline` snapshot by walking a directory of spec subdirectories.
@@ -436,8 +607,17 @@ impl Package {
         })
     }
 
-    /// Decode a package from a zip archive.
+    /// Decode a package from a zip archive, checking every file against the
+    /// manifest digests and the signature, if the package carries one.
     pub fn read_zip<R: Read + Seek>(reader: R) -> Result<Self, BundleError> {
+        Ok(Self::read_zip_with_signer(reader)?.0)
+    }
+
+    /// Like [`Package::read_zip`], also returning the key that signed the
+    /// package. Whether that key is trusted is up to the caller.
+    pub fn read_zip_with_signer<R: Read + Seek>(
+        reader: R,
+    ) -> Result<(Self, Option<VerifyingKey>), BundleError> {
         let mut archive = ZipArchive::new(reader)?;
 
         // Pull every entry into memory keyed by its in-zip path. Order is
@@ -455,7 +635,7 @@ impl Package {
             files.push((name, buf));
         }
 
-        let manifest_bytes = take_file(&mut files, "manifest.json")
+        let manifest_bytes = take_file(&mut files, MANIFEST_FILE)
             .ok_or_else(|| BundleError::Invalid("missing manifest.json".into()))?;
         let manifest: Manifest = serde_json::from_slice(&manifest_bytes)?;
         if manifest.package_version != PACKAGE_VERSION {
@@ -464,6 +644,10 @@ impl Package {
                 manifest.package_version
             )));
         }
+        let signer = take_file(&mut files, SIGNATURE_FILE)
+            .map(|bytes| check_signature(&manifest_bytes, &bytes))
+            .transpose()?;
+        check_digests(&manifest.digests, &files)?;
 
         let project_config = take_file(&mut files, "project.toml");
 
@@ -543,6 +727,10 @@ impl Package {
                 url: meta.url,
                 created_at: meta.created_at,
                 updated_at: meta.updated_at,
+                reviewers: meta.reviewers,
+                reviews: meta.reviews,
+                labels: meta.labels,
+                merge_commit_sha: meta.merge_commit_sha,
                 spec_changes,
                 asset_changes,
                 spec_meta: meta.spec_meta,
@@ -557,32 +745,304 @@ impl Package {
             )));
         }
 
-        Ok(Package {
+        let package = Package {
             manifest,
             project_config,
             mainline,
             pr_changes,
-        })
+        };
+        Ok((package, signer))
+    }
+
+    /// Check the consistency rules decoding does not enforce: the spec index
+    /// matches `main/`, `deleted_*` lists only appear in delta packages, and PR
+    /// change-sets only reference specs that exist. A delta package carries a
+    /// sparse mainline, so references to specs outside it are not checked.
+    pub fn verify(&self) -> Verification {
+        let mut report = Verification::default();
+        let manifest = &self.manifest;
+        let delta = manifest.base_commit.is_some();
+
+        let mut mainline: BTreeMap<&str, &Spec> = BTreeMap::new();
+        for spec in &self.mainline.specs {
+            if let Some(existing) = mainline.insert(spec.id.as_str(), spec) {
+                report.errors.push(format!(
+                    "spec {} appears in both main/{} and main/{}",
+                    spec.id, existing.dir_name, spec.dir_name
+                ));
+            }
+        }
+
+        if manifest.specs.is_empty() {
+            if !mainline.is_empty() {
+                report.warnings.push(
+                    "manifest has no spec index; consumers will derive metadata from source"
+                        .to_string(),
+                );
+            }
+        } else {
+            let mut indexed = BTreeSet::new();
+            for entry in &manifest.specs {
+                if !indexed.insert(entry.id.as_str()) {
+                    report
+                        .errors
+                        .push(format!("spec index lists {} more than once", entry.id));
+                }
+                let Some(spec) = mainline.get(entry.id.as_str()) else {
+                    report.errors.push(format!(
+                        "spec index entry {} has no directory under main/",
+                        entry.id
+                    ));
+                    continue;
+                };
+                if entry.dir_name != spec.dir_name {
+                    report.errors.push(format!(
+                        "spec index entry {} names directory {} but main/ has {}",
+                        entry.id, entry.dir_name, spec.dir_name
+                    ));
+                }
+                if entry.source_path != spec.source_path || entry.format != spec.format {
+                    report.errors.push(format!(
+                        "spec index entry {} names source {} ({:?}) but main/{} has {} ({:?})",
+                        entry.id,
+                        entry.source_path,
+                        entry.format,
+                        spec.dir_name,
+                        spec.source_path,
+                        spec.format
+                    ));
+                }
+                if entry.title.trim().is_empty() {
+                    report
+                        .warnings
+                        .push(format!("spec index entry {} has an empty title", entry.id));
+                }
+            }
+            for spec in mainline.values() {
+                if !indexed.contains(spec.id.as_str()) {
+                    report.errors.push(format!(
+                        "main/{} is missing from the spec index",
+                        spec.dir_name
+                    ));
+                }
+            }
+        }
+
+        if !delta {
+            if !manifest.deleted_specs.is_empty() {
+                report.errors.push(
+                    "deleted_specs is set but the package has no base_commit (not a delta)"
+                        .to_string(),
+                );
+            }
+            if !manifest.deleted_prs.is_empty() {
+                report.errors.push(
+                    "deleted_prs is set but the package has no base_commit (not a delta)"
+                        .to_string(),
+                );
+            }
+        }
+        if !manifest.unchanged.is_empty() {
+            if !delta {
+                report.errors.push(
+                    "unchanged is set but the package has no base_commit (not a delta)".to_string(),
+                );
+            }
+            let present: BTreeSet<String> = self
+                .content_entries()
+                .map(|entries| entries.into_iter().map(|(path, _)| path).collect())
+                .unwrap_or_default();
+            for path in manifest.unchanged.keys() {
+                if present.contains(path) {
+                    report.errors.push(format!(
+                        "{path} is listed as unchanged but also present in the package"
+                    ));
+                }
+            }
+        }
+        for id in &manifest.deleted_specs {
+            if mainline.contains_key(id.as_str()) {
+                report.errors.push(format!(
+                    "spec {id} is listed as deleted but present in main/"
+                ));
+            }
+        }
+        for number in &manifest.deleted_prs {
+            if self.pr_changes.iter().any(|pr| pr.pr_number == *number) {
+                report.errors.push(format!(
+                    "PR #{number} is listed as deleted but present in prs/"
+                ));
+            }
+        }
+
+        for pr in &self.pr_changes {
+            let number = pr.pr_number;
+            let upserted: BTreeSet<&str> = pr
+                .spec_changes
+                .iter()
+                .filter_map(|change| match change {
+                    SpecChange::Upsert(spec) => Some(spec.id.as_str()),
+                    SpecChange::Remove { .. } => None,
+                })
+                .collect();
+            let exists = |id: &str| delta || mainline.contains_key(id) || upserted.contains(id);
+
+            if pr.head_sha.trim().is_empty() {
+                report.errors.push(format!("PR #{number} has no head_sha"));
+            }
+            if !matches!(pr.state.as_str(), "DRAFT" | "REVIEW") {
+                report.warnings.push(format!(
+                    "PR #{number} has state '{}' (expected DRAFT or REVIEW)",
+                    pr.state
+                ));
+            }
+            for meta in &pr.spec_meta {
+                if !exists(&meta.spec_id) {
+                    report.errors.push(format!(
+                        "PR #{number} has metadata for unknown spec {}",
+                        meta.spec_id
+                    ));
+                }
+            }
+            for change in &pr.spec_changes {
+                if let SpecChange::Remove { id } = change {
+                    if !exists(id) {
+                        report
+                            .errors
+                            .push(format!("PR #{number} removes unknown spec {id}"));
+                    }
+                }
+            }
+            for change in &pr.asset_changes {
+                if let AssetChange::Remove { spec_id, path } = change {
+                    if !exists(spec_id) {
+                        report.errors.push(format!(
+                            "PR #{number} removes {path} from unknown spec {spec_id}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        report
     }
 }
 
-fn write_spec<W: Write + Seek>(
-    zw: &mut ZipWriter<W>,
-    prefix: &str,
-    spec: &Spec,
-    opts: SimpleFileOptions,
-) -> Result<(), BundleError> {
-    let src_path = format!("{prefix}/{}/{}", spec.dir_name, spec.source_path);
-    zw.start_file(src_path, opts)?;
-    zw.write_all(&spec.source)?;
-    for asset in &spec.assets {
-        let path = format!("{prefix}/{}/{}", spec.dir_name, asset.path);
+fn write_entries<W: Write + Seek>(entries: Vec<Entry<'_>>, writer: W) -> Result<W, BundleError> {
+    let mut zw = ZipWriter::new(writer);
+    let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, bytes) in entries {
         zw.start_file(path, opts)?;
-        zw.write_all(&asset.bytes)?;
+        zw.write_all(&bytes)?;
+    }
+
+    let writer = zw.finish()?;
+    Ok(writer)
+}
+
+/// The `sha256:<hex>` digest the manifest records for `bytes`.
+pub fn digest(bytes: &[u8]) -> String {
+    format!("sha256:{}", encode_hex(&Sha256::digest(bytes)))
+}
+
+/// Every file must match its recorded digest and every recorded digest must
+/// have a file. Packages without digests predate them and are accepted as is.
+fn check_digests(
+    digests: &BTreeMap<String, String>,
+    files: &[(String, Vec<u8>)],
+) -> Result<(), BundleError> {
+    if digests.is_empty() {
+        return Ok(());
+    }
+    for (name, bytes) in files {
+        match digests.get(name) {
+            None => {
+                return Err(BundleError::Integrity(format!(
+                    "{name} has no digest in the manifest"
+                )))
+            }
+            Some(expected) if *expected != digest(bytes) => {
+                return Err(BundleError::Integrity(format!(
+                    "{name} does not match its manifest digest"
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+    if let Some(name) = digests
+        .keys()
+        .find(|name| !files.iter().any(|(file, _)| file == *name))
+    {
+        return Err(BundleError::Integrity(format!(
+            "{name} is listed in the manifest but missing from the package"
+        )));
     }
     Ok(())
 }
 
+/// Check `manifest.sig` against the manifest bytes and return the signing key.
+fn check_signature(manifest: &[u8], signature: &[u8]) -> Result<VerifyingKey, BundleError> {
+    let wire: SignatureWire = serde_json::from_slice(signature)?;
+    if wire.algorithm != "ed25519" {
+        return Err(BundleError::Integrity(format!(
+            "unsupported signature algorithm {}",
+            wire.algorithm
+        )));
+    }
+    let key = parse_verifying_key(&wire.public_key)?;
+    let bytes: [u8; 64] = decode_hex(&wire.signature)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| BundleError::Integrity("malformed manifest signature".into()))?;
+    key.verify(manifest, &Signature::from_bytes(&bytes))
+        .map_err(|_| BundleError::Integrity("manifest signature does not match".into()))?;
+    Ok(key)
+}
+
+/// Parse a hex-encoded 32-byte ed25519 secret key.
+pub fn parse_signing_key(text: &str) -> Result<SigningKey, BundleError> {
+    Ok(SigningKey::from_bytes(&decode_key(text)?))
+}
+
+/// Parse a hex-encoded 32-byte ed25519 public key.
+pub fn parse_verifying_key(text: &str) -> Result<VerifyingKey, BundleError> {
+    VerifyingKey::from_bytes(&decode_key(text)?)
+        .map_err(|_| BundleError::Key("not a valid ed25519 public key".into()))
+}
+
+fn decode_key(text: &str) -> Result<[u8; 32], BundleError> {
+    decode_hex(text.trim())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| BundleError::Key("expected 64 hexadecimal characters".into()))
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn spec_entries<'a>(entries: &mut Vec<Entry<'a>>, prefix: &str, spec: &'a Spec) {
+    entries.push((
+        format!("{prefix}/{}/{}", spec.dir_name, spec.source_path),
+        Cow::Borrowed(spec.source.as_slice()),
+    ));
+    for asset in &spec.assets {
+        entries.push((
+            format!("{prefix}/{}/{}", spec.dir_name, asset.path),
+            Cow::Borrowed(asset.bytes.as_slice()),
+        ));
+    }
+}
+
 fn take_file(files: &mut Vec<(String, Vec<u8>)>, name: &str) -> Option<Vec<u8>> {
     let idx = files.iter().position(|(n, _)| n == name)?;
     Some(files.remove(idx).1)
@@ -736,6 +1196,8 @@ mod tests {
                 base_commit: None,
                 deleted_specs: Vec::new(),
                 deleted_prs: Vec::new(),
+                digests: BTreeMap::new(),
+                unchanged: BTreeMap::new(),
             },
             project_config: Some(b"# dossiers.toml\n".to_vec()),
             mainline: Mainline {
@@ -772,10 +1234,19 @@ mod tests {
                 url: "https://github.com/acme/specs/pull/42".into(),
                 created_at: DateTime::<Utc>::from_timestamp(1_700_000_100, 0),
                 updated_at: DateTime::<Utc>::from_timestamp(1_700_000_200, 0),
+                reviewers: vec!["hubot".into()],
+                reviews: vec![PrReview {
+                    reviewer: "monalisa".into(),
+                    state: "APPROVED".into(),
+                    submitted_at: DateTime::<Utc>::from_timestamp(1_700_000_150, 0),
+                }],
+                labels: vec!["rfc".into()],
+                merge_commit_sha: None,
                 spec_meta: vec![PrSpecMeta {
                     spec_id: "0001".into(),
                     title: "Authentication (rewrite)".into(),
                     status: "REVIEW".into(),
+                    visibility: None,
                     authors: vec!["octocat".into()],
                     authors_meta: vec![Author {
                         name: "octocat".into(),
@@ -849,6 +1320,10 @@ mod tests {
         assert_eq!(pr.author.as_deref(), Some("octocat"));
         assert_eq!(pr.state, "REVIEW");
         assert_eq!(pr.url, "https://github.com/acme/specs/pull/42");
+        assert_eq!(pr.reviewers, vec!["hubot".to_string()]);
+        assert_eq!(pr.reviews[0].reviewer, "monalisa");
+        assert_eq!(pr.reviews[0].state, "APPROVED");
+        assert_eq!(pr.labels, vec!["rfc".to_string()]);
         assert_eq!(
             pr.created_at,
             DateTime::<Utc>::from_timestamp(1_700_000_100, 0)
@@ -971,6 +1446,233 @@ mod tests {
         assert!(matches!(err, BundleError::Invalid(_)));
     }
 
+    /// Re-zip `bytes`, passing every entry through `edit`.
+    fn rewrite_zip(bytes: Vec<u8>, edit: impl Fn(&str, &mut Vec<u8>)) -> Cursor<Vec<u8>> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut zw = ZipWriter::new(Cursor::new(Vec::new()));
+        for idx in 0..archive.len() {
+            let mut entry = archive.by_index(idx).unwrap();
+            let name = entry.name().to_string();
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf).unwrap();
+            edit(&name, &mut buf);
+            zw.start_file(name, SimpleFileOptions::default()).unwrap();
+            zw.write_all(&buf).unwrap();
+        }
+        let mut out = zw.finish().unwrap();
+        out.set_position(0);
+        out
+    }
+
+    #[test]
+    fn digests_are_recorded_and_checked_on_read() {
+        let mut buf = Cursor::new(Vec::new());
+        sample_package().write_zip(&mut buf).unwrap();
+        let bytes = buf.into_inner();
+
+        let decoded = Package::read_zip(Cursor::new(bytes.clone())).unwrap();
+        let digests = &decoded.manifest.digests;
+        assert_eq!(digests.len(), 6, "{digests:?}");
+        assert_eq!(
+            digests["main/0002-api/api.adoc"],
+            "sha256:d56629d756cc1d1c76b99b2760e2128e86d9d9f219676bc70f56a2240738a086"
+        );
+
+        let tampered = rewrite_zip(bytes.clone(), |name, bytes| {
+            if name == "main/0002-api/api.adoc" {
+                bytes.extend_from_slice(b"injected\n");
+            }
+        });
+        let err = Package::read_zip(tampered).unwrap_err();
+        assert!(
+            matches!(&err, BundleError::Integrity(msg) if msg.contains("api.adoc")),
+            "{err}"
+        );
+
+        // Packages written before digests existed still decode.
+        let legacy = rewrite_zip(bytes, |name, bytes| {
+            if name == "manifest.json" {
+                let mut manifest: Manifest = serde_json::from_slice(bytes).unwrap();
+                manifest.digests.clear();
+                *bytes = serde_json::to_vec(&manifest).unwrap();
+            }
+        });
+        assert!(Package::read_zip(legacy).is_ok());
+    }
+
+    #[test]
+    fn fingerprint_ignores_the_packaging_time() {
+        let pkg = sample_package();
+        let mut later = sample_package();
+        later.manifest.timestamp = DateTime::<Utc>::from_timestamp(1_800_000_000, 0).unwrap();
+        assert_eq!(pkg.fingerprint().unwrap(), later.fingerprint().unwrap());
+
+        later.mainline.specs[1].source = b"= API v2\n".to_vec();
+        assert_ne!(pkg.fingerprint().unwrap(), later.fingerprint().unwrap());
+    }
+
+    #[test]
+    fn signed_packages_report_their_signer() {
+        let key = parse_signing_key(&"07".repeat(32)).unwrap();
+        let mut buf = Cursor::new(Vec::new());
+        sample_package().write_zip_signed(&mut buf, &key).unwrap();
+        let bytes = buf.into_inner();
+
+        let (_, signer) = Package::read_zip_with_signer(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(signer, Some(key.verifying_key()));
+        let public = encode_hex(key.verifying_key().as_bytes());
+        assert_eq!(parse_verifying_key(&public).unwrap(), key.verifying_key());
+
+        let mut buf = Cursor::new(Vec::new());
+        sample_package().write_zip(&mut buf).unwrap();
+        let (_, signer) = Package::read_zip_with_signer(buf).unwrap();
+        assert_eq!(signer, None);
+
+        let tampered = rewrite_zip(bytes, |name, bytes| {
+            if name == "manifest.json" {
+                let mut manifest: Manifest = serde_json::from_slice(bytes).unwrap();
+                manifest.branch = Some("release".into());
+                *bytes = serde_json::to_vec_pretty(&manifest).unwrap();
+            }
+        });
+        let err = Package::read_zip(tampered).unwrap_err();
+        assert!(matches!(err, BundleError::Integrity(_)), "{err}");
+
+        assert!(matches!(
+            parse_signing_key("not hex"),
+            Err(BundleError::Key(_))
+        ));
+    }
+
+    fn index_entry(spec: &Spec) -> SpecIndexEntry {
+        SpecIndexEntry {
+            id: spec.id.clone(),
+            dir_name: spec.dir_name.clone(),
+            source_path: spec.source_path.clone(),
+            format: spec.format,
+            title: format!("Spec {}", spec.id),
+            status: "DRAFT".into(),
+            visibility: None,
+            created: None,
+            updated: None,
+            authors: vec![],
+            authors_meta: vec![],
+            approved_by: vec![],
+            owners: vec![],
+            teams: vec![],
+            extra: BTreeMap::new(),
+            links: vec![],
+            fields: vec![],
+        }
+    }
+
+    /// `sample_package` with a spec index and without the PR's removal of a
+    /// spec that is not on mainline.
+    fn consistent_package() -> Package {
+        let mut pkg = sample_package();
+        pkg.manifest.specs = pkg.mainline.specs.iter().map(index_entry).collect();
+        pkg.pr_changes[0]
+            .spec_changes
+            .retain(|change| matches!(change, SpecChange::Upsert(_)));
+        pkg
+    }
+
+    #[test]
+    fn verify_accepts_a_consistent_package() {
+        let report = consistent_package().verify();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+        let report = sample_package().verify();
+        assert!(report.warnings[0].contains("no spec index"));
+    }
+
+    #[test]
+    fn verify_reports_index_delta_and_pr_inconsistencies() {
+        let mut pkg = consistent_package();
+        pkg.manifest.specs[1].dir_name = "0002-renamed".into();
+        pkg.manifest.specs.push(SpecIndexEntry {
+            id: "0003".into(),
+            ..index_entry(&pkg.mainline.specs[0])
+        });
+        pkg.manifest.deleted_specs = vec!["0001".into()];
+        pkg.pr_changes[0].spec_meta[0].spec_id = "0404".into();
+
+        let errors = pkg.verify().errors;
+        let expect = [
+            "names directory 0002-renamed",
+            "entry 0003 has no directory",
+            "deleted_specs is set but the package has no base_commit",
+            "spec 0001 is listed as deleted but present",
+            "metadata for unknown spec 0404",
+        ];
+        for needle in expect {
+            assert!(
+                errors.iter().any(|err| err.contains(needle)),
+                "missing '{needle}' in {errors:?}"
+            );
+        }
+        assert_eq!(errors.len(), expect.len(), "{errors:?}");
+
+        // In a delta package the mainline is sparse, so PR references to
+        // specs outside it are not errors.
+        pkg.manifest.base_commit = Some("abc000".into());
+        pkg.manifest.deleted_specs.clear();
+        let errors = pkg.verify().errors;
+        assert!(!errors.iter().any(|err| err.contains("0404")), "{errors:?}");
+    }
+
+    #[test]
+    fn verify_reports_unchanged_files_outside_a_delta() {
+        let mut pkg = consistent_package();
+        pkg.manifest.unchanged.insert(
+            "main/0001-authentication/diagram.png".into(),
+            digest(&[137, 80, 78, 71]),
+        );
+        let errors = pkg.verify().errors;
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.contains("unchanged is set but the package has no base_commit")),
+            "{errors:?}"
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.contains("diagram.png is listed as unchanged but also present")),
+            "{errors:?}"
+        );
+
+        pkg.manifest.base_commit = Some("abc000".into());
+        pkg.mainline.specs[0].assets.clear();
+        let errors = pkg.verify().errors;
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn write_dir_mirrors_the_zip_layout() {
+        let dir = temp_specs_dir("unpack");
+        sample_package().write_dir(&dir).unwrap();
+
+        let manifest: Manifest =
+            serde_json::from_slice(&fs::read(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest.commit.as_deref(), Some("abc123"));
+        assert_eq!(
+            fs::read(dir.join("main/0001-authentication/diagram.png")).unwrap(),
+            vec![137, 80, 78, 71]
+        );
+        assert!(dir.join("prs/42/meta.json").is_file());
+        assert!(dir
+            .join("prs/42/specs/0001-authentication/authentication.md")
+            .is_file());
+
+        let mut pkg = sample_package();
+        pkg.mainline.specs[0].assets[0].path = "../escape.png".into();
+        let err = pkg.write_dir(&dir).unwrap_err();
+        let _ = fs::remove_dir_all(&dir);
+        assert!(matches!(err, BundleError::Invalid(_)));
+    }
+
     #[test]
     fn extract_spec_id_requires_four_digits_and_dash() {
         assert_eq!(extract_spec_id("0001-x"), Some("0001".into()));
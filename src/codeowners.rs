@@ -0,0 +1,237 @@
+//! Spec owners from the repository's CODEOWNERS file.
+//!
+//! GitHub and GitLab look for the file at `.github/CODEOWNERS`, `CODEOWNERS`
+//! and `docs/CODEOWNERS`, in that order. Each line pairs a gitignore-style
+//! path pattern with owners (`@user`, `@org/team`, or an email address); the
+//! last pattern matching a path decides its owners, and a pattern listed
+//! without owners leaves the path unowned. GitLab's `[Section]` headers are
+//! understood too: a section's default owners apply to its ownerless lines,
+//! and owners from every section whose rules match are combined.
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+/// Where CODEOWNERS may live, relative to the repository root, in lookup order.
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug, Clone)]
+struct Rule {
+    matcher: Regex,
+    owners: Vec<String>,
+    section: usize,
+}
+
+/// A parsed CODEOWNERS file.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Read the first CODEOWNERS file found under `repo_root`. `None` when the
+    /// repository has none.
+    pub fn load(repo_root: &Path) -> Option<Self> {
+        CODEOWNERS_LOCATIONS
+            .iter()
+            .find_map(|location| fs::read_to_string(repo_root.join(location)).ok())
+            .map(|text| Self::parse(&text))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut rules = Vec::new();
+        let mut section = 0;
+        let mut section_owners: Vec<String> = Vec::new();
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('^').unwrap_or(line).strip_prefix('[') {
+                // `[Section]`, `[Section][2]` or `^[Section]`, then default owners.
+                let Some((_, rest)) = header.split_once(']') else {
+                    continue;
+                };
+                let rest = match rest.strip_prefix('[') {
+                    Some(count) => count.split_once(']').map_or("", |(_, rest)| rest),
+                    None => rest,
+                };
+                section += 1;
+                section_owners = owner_tokens(rest);
+                continue;
+            }
+
+            let (pattern, rest) = split_pattern(line);
+            let mut owners = owner_tokens(rest);
+            if owners.is_empty() {
+                owners = section_owners.clone();
+            }
+            if let Some(matcher) = pattern_regex(&pattern) {
+                rules.push(Rule {
+                    matcher,
+                    owners,
+                    section,
+                });
+            }
+        }
+
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Owners of `path`, a file path relative to the repository root.
+    pub fn owners_of(&self, path: &Path) -> Vec<String> {
+        let path = path
+            .components()
+            .filter_map(|component| component.as_os_str().to_str())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut owners: Vec<String> = Vec::new();
+        let mut sections: Vec<usize> = self.rules.iter().map(|rule| rule.section).collect();
+        sections.dedup();
+        for section in sections {
+            let decided = self
+                .rules
+                .iter()
+                .rev()
+                .filter(|rule| rule.section == section)
+                .find(|rule| rule.matcher.is_match(&path));
+            for owner in decided.into_iter().flat_map(|rule| &rule.owners) {
+                if !owners.iter().any(|seen| seen.eq_ignore_ascii_case(owner)) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+        owners
+    }
+}
+
+/// Split a rule line into its pattern, honouring `\ ` escapes, and the rest.
+fn split_pattern(line: &str) -> (String, &str) {
+    let mut pattern = String::new();
+    let mut chars = line.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    pattern.push(escaped);
+                }
+            }
+            ch if ch.is_whitespace() => return (pattern, &line[index..]),
+            ch => pattern.push(ch),
+        }
+    }
+    (pattern, "")
+}
+
+/// Owner tokens up to an inline `#` comment.
+fn owner_tokens(rest: &str) -> Vec<String> {
+    rest.split_whitespace()
+        .take_while(|token| !token.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Translate a gitignore-style pattern into a regex over `/`-separated paths.
+/// A pattern naming a directory also matches everything beneath it, but one
+/// whose last segment is a wildcard (`/docs/*`) does not match nested paths.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // A slash at the start or in the middle anchors the pattern at the root.
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return if pattern.starts_with('/') {
+            Regex::new("^.*$").ok()
+        } else {
+            None
+        };
+    }
+
+    let mut body = String::new();
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            body.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            body.push_str(".*");
+            rest = after;
+        } else {
+            let ch = rest.chars().next()?;
+            match ch {
+                '*' => body.push_str("[^/]*"),
+                '?' => body.push_str("[^/]"),
+                ch => body.push_str(&regex::escape(&ch.to_string())),
+            }
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+    let last_segment = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    let suffix = if directory_only {
+        "/.*$"
+    } else if last_segment.contains(['*', '?']) {
+        "$"
+    } else {
+        "(?:/.*)?$"
+    };
+    Regex::new(&format!("{prefix}{body}{suffix}")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_decides_owners() {
+        let owners = CodeOwners::parse(
+            r#"
+# Everything defaults to the docs team.
+*                       @acme/docs
+/specs/                 @acme/architecture   # spec reviews
+/specs/0002-*/          @grace ada@example.com
+specs/0003-legacy.md
+*.adoc                  @asciidoc-fans
+"#,
+        );
+        let of = |path: &str| owners.owners_of(Path::new(path));
+
+        assert_eq!(of("README.md"), vec!["@acme/docs"]);
+        assert_eq!(of("specs/0001-auth/README.md"), vec!["@acme/architecture"]);
+        assert_eq!(
+            of("specs/0002-billing/README.md"),
+            vec!["@grace", "ada@example.com"]
+        );
+        assert!(of("specs/0003-legacy.md").is_empty());
+        assert_eq!(of("specs/0004-search/index.adoc"), vec!["@asciidoc-fans"]);
+        assert_eq!(of("other/specs/0001.md"), vec!["@acme/docs"]);
+
+        let flat = CodeOwners::parse("/specs/* @acme/flat\n");
+        let flat_of = |path: &str| flat.owners_of(Path::new(path));
+        assert_eq!(flat_of("specs/x.md"), vec!["@acme/flat"]);
+        assert!(flat_of("specs/0001-auth/README.md").is_empty());
+    }
+
+    #[test]
+    fn gitlab_sections_combine_owners() {
+        let owners =
+            CodeOwners::parse("[Docs] @docs\nspecs/\n\n^[Security][2] @security\nspecs/0001-*/\n");
+        assert_eq!(
+            owners.owners_of(Path::new("specs/0001-auth/README.md")),
+            vec!["@docs", "@security"]
+        );
+        assert_eq!(
+            owners.owners_of(Path::new("specs/0002-billing/README.md")),
+            vec!["@docs"]
+        );
+    }
+}
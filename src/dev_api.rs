@@ -0,0 +1,723 @@
+//! A local stand-in for the Dossiers API's sync endpoints, so `dossiers push`
+//! can be exercised without the hosted service.
+//!
+//! Each project keeps, under the data directory:
+//!
+//! ```text
+//! <project>/packages/<n>.zip   every package received, in order
+//! <project>/snapshot.zip       the materialized snapshot
+//! <project>/snapshot/          the same snapshot, unpacked
+//! <project>/state.json         last synced commit per branch
+//! ```
+//!
+//! Full syncs replace the snapshot (chunked runs are buffered until their
+//! final chunk), and deltas are applied on top of it the way the hosted server
+//! does, including `unchanged` files resolved from the snapshot by digest.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::bundle::{
+    digest, encode_hex, BundleError, Package, PrChangeSet, SpecChange, SpecIndexEntry, VerifyingKey,
+};
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("{0}")]
+    Rejected(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Body of `GET .../sync/state`.
+#[derive(Debug, Default, Serialize)]
+pub struct SyncState {
+    pub branches: BTreeMap<String, String>,
+    pub pr_heads: BTreeMap<String, String>,
+    pub unchanged_content: bool,
+}
+
+/// Body of a successful `POST .../sync`.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub synced: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub pr_revisions: usize,
+}
+
+/// A chunk of a multi-request full sync: the client's run id, the chunk's
+/// position in the run, and whether it is the last. A chunk sent again (a
+/// retry or a resumed push) replaces the earlier copy at its position.
+#[derive(Debug, Clone, Copy)]
+pub struct Chunk<'a> {
+    pub run_id: &'a str,
+    pub index: Option<usize>,
+    pub last: bool,
+}
+
+pub struct DevApi {
+    data_dir: PathBuf,
+    trusted_keys: Vec<VerifyingKey>,
+    projects: Mutex<HashMap<String, ProjectStore>>,
+}
+
+#[derive(Default)]
+struct ProjectStore {
+    snapshot: Option<Package>,
+    branches: BTreeMap<String, String>,
+    received: usize,
+    runs: HashMap<String, BTreeMap<usize, Package>>,
+    /// Runs whose final chunk has been applied since the server started.
+    finalized: BTreeSet<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoredState {
+    branches: BTreeMap<String, String>,
+}
+
+impl DevApi {
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            trusted_keys: Vec::new(),
+            projects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Only accept packages signed by one of `keys`.
+    pub fn with_trusted_keys(mut self, keys: Vec<VerifyingKey>) -> Self {
+        self.trusted_keys = keys;
+        self
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn state(&self, project: &str) -> Result<SyncState, SyncError> {
+        self.with_project(project, |store| {
+            let pr_heads = store
+                .snapshot
+                .iter()
+                .flat_map(|snapshot| &snapshot.pr_changes)
+                .map(|pr| (pr.pr_number.to_string(), pr.head_sha.clone()))
+                .collect();
+            Ok(SyncState {
+                branches: store.branches.clone(),
+                pr_heads,
+                unchanged_content: true,
+            })
+        })
+    }
+
+    /// The project's current snapshot, if anything has been synced.
+    pub fn snapshot(&self, project: &str) -> Result<Option<Package>, SyncError> {
+        self.with_project(project, |store| Ok(store.snapshot.clone()))
+    }
+
+    /// Chunk indices buffered for an unfinished chunked run, so an interrupted
+    /// push can resume. `None` once the run has completed or is unknown.
+    pub fn run_chunks(&self, project: &str, run_id: &str) -> Result<Option<Vec<usize>>, SyncError> {
+        self.with_project(project, |store| {
+            Ok(store
+                .runs
+                .get(run_id)
+                .map(|run| run.keys().copied().collect()))
+        })
+    }
+
+    /// Whether a chunked run's final chunk has been applied, so a client whose
+    /// final request timed out can tell it went through.
+    pub fn run_finalized(&self, project: &str, run_id: &str) -> Result<bool, SyncError> {
+        self.with_project(project, |store| Ok(store.finalized.contains(run_id)))
+    }
+
+    /// Apply a received package: a full snapshot (possibly one chunk of
+    /// several) or, when it names a `base_commit`, a delta.
+    pub fn sync(
+        &self,
+        project: &str,
+        zip_bytes: &[u8],
+        chunk: Option<Chunk<'_>>,
+    ) -> Result<SyncSummary, SyncError> {
+        let (package, signer) = Package::read_zip_with_signer(Cursor::new(zip_bytes))?;
+        if !self.trusted_keys.is_empty() {
+            match signer {
+                Some(key) if self.trusted_keys.contains(&key) => {}
+                Some(key) => {
+                    return Err(SyncError::Rejected(format!(
+                        "package is signed by untrusted key {}",
+                        encode_hex(key.as_bytes())
+                    )))
+                }
+                None => return Err(SyncError::Rejected("package is not signed".into())),
+            }
+        }
+
+        let dir = self.project_dir(project)?;
+        self.with_project(project, |store| {
+            store.received += 1;
+            let packages = dir.join("packages");
+            fs::create_dir_all(&packages)?;
+            fs::write(packages.join(format!("{}.zip", store.received)), zip_bytes)?;
+
+            let summary = match (&package.manifest.base_commit, chunk) {
+                (Some(base), _) => {
+                    let base = base.clone();
+                    apply_delta(store, package, &base)?
+                }
+                (None, Some(chunk)) if !chunk.last => {
+                    let summary = full_summary(store.snapshot.as_ref(), &package, false);
+                    let run = store.runs.entry(chunk.run_id.to_string()).or_default();
+                    run.insert(chunk.index.unwrap_or(run.len()), package);
+                    summary
+                }
+                (None, Some(chunk)) => {
+                    // The final chunk replaces the whole snapshot, so it must
+                    // complete a run holding every earlier chunk: a repeated
+                    // final chunk or a gap would otherwise delete the specs
+                    // the missing chunks carry.
+                    let run_id = chunk.run_id;
+                    if store.finalized.contains(run_id) {
+                        return Err(SyncError::Conflict(format!(
+                            "run {run_id} was already finalized"
+                        )));
+                    }
+                    let held = store.runs.get(run_id);
+                    let index = chunk.index.unwrap_or_else(|| held.map_or(0, BTreeMap::len));
+                    let missing: Vec<String> = (0..index)
+                        .filter(|i| !held.is_some_and(|parts| parts.contains_key(i)))
+                        .map(|i| i.to_string())
+                        .collect();
+                    if !missing.is_empty() {
+                        return Err(SyncError::Conflict(format!(
+                            "run {run_id} is missing chunk(s) {}",
+                            missing.join(", ")
+                        )));
+                    }
+                    let mut parts = store.runs.remove(run_id).unwrap_or_default();
+                    store.finalized.insert(run_id.to_string());
+                    let summary = full_summary(store.snapshot.as_ref(), &package, false);
+                    parts.insert(index, package);
+                    let merged = merge_chunks(parts.into_values().collect());
+                    let deleted = full_summary(store.snapshot.as_ref(), &merged, true).deleted;
+                    replace_snapshot(store, merged);
+                    SyncSummary { deleted, ..summary }
+                }
+                (None, None) => {
+                    let summary = full_summary(store.snapshot.as_ref(), &package, true);
+                    replace_snapshot(store, package);
+                    summary
+                }
+            };
+            persist(&dir, store)?;
+            Ok(summary)
+        })
+    }
+
+    fn with_project<T>(
+        &self,
+        project: &str,
+        f: impl FnOnce(&mut ProjectStore) -> Result<T, SyncError>,
+    ) -> Result<T, SyncError> {
+        let dir = self.project_dir(project)?;
+        let mut projects = self.projects.lock().unwrap_or_else(|err| err.into_inner());
+        if !projects.contains_key(project) {
+            projects.insert(project.to_string(), load_store(&dir)?);
+        }
+        f(projects
+            .get_mut(project)
+            .expect("project store was just loaded"))
+    }
+
+    fn project_dir(&self, project: &str) -> Result<PathBuf, SyncError> {
+        let valid = !project.is_empty()
+            && project != "."
+            && project != ".."
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(SyncError::Rejected(format!(
+                "invalid project name '{project}'"
+            )));
+        }
+        Ok(self.data_dir.join(project))
+    }
+}
+
+fn load_store(dir: &Path) -> Result<ProjectStore, SyncError> {
+    let mut store = ProjectStore::default();
+    if let Ok(bytes) = fs::read(dir.join("state.json")) {
+        let state: StoredState = serde_json::from_slice(&bytes).map_err(BundleError::from)?;
+        store.branches = state.branches;
+    }
+    store.received = fs::read_dir(dir.join("packages")).map_or(0, |entries| entries.count());
+    if let Ok(bytes) = fs::read(dir.join("snapshot.zip")) {
+        store.snapshot = Some(Package::read_zip(Cursor::new(bytes))?);
+    }
+    Ok(store)
+}
+
+fn persist(dir: &Path, store: &ProjectStore) -> Result<(), SyncError> {
+    let state = StoredState {
+        branches: store.branches.clone(),
+    };
+    fs::write(
+        dir.join("state.json"),
+        serde_json::to_vec_pretty(&state).map_err(BundleError::from)?,
+    )?;
+    if let Some(snapshot) = &store.snapshot {
+        fs::write(
+            dir.join("snapshot.zip"),
+            snapshot.write_zip(Cursor::new(Vec::new()))?.into_inner(),
+        )?;
+        let unpacked = dir.join("snapshot");
+        if unpacked.exists() {
+            fs::remove_dir_all(&unpacked)?;
+        }
+        snapshot.write_dir(&unpacked)?;
+    }
+    Ok(())
+}
+
+fn record_branch(store: &mut ProjectStore, package: &Package) {
+    if let (Some(branch), Some(commit)) = (&package.manifest.branch, &package.manifest.commit) {
+        store.branches.insert(branch.clone(), commit.clone());
+    }
+}
+
+fn replace_snapshot(store: &mut ProjectStore, mut package: Package) {
+    record_branch(store, &package);
+    if let Some(snapshot) = &store.snapshot {
+        carry_approvals(&snapshot.manifest.specs, &mut package.manifest.specs);
+    }
+    store.snapshot = Some(package);
+}
+
+/// Approvals come from the forge, which a push may not have reached, and a
+/// spec last changed outside a pull request has none to report: entries
+/// that arrive without `approved_by` keep the ones held for the spec.
+fn carry_approvals(held: &[SpecIndexEntry], entries: &mut [SpecIndexEntry]) {
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.approved_by.is_empty())
+    {
+        if let Some(previous) = held.iter().find(|held| held.id == entry.id) {
+            entry.approved_by = previous.approved_by.clone();
+        }
+    }
+}
+
+/// Counts for a full sync of `package` over `snapshot`. Deletions are only
+/// known once the whole run has arrived, so chunks leave them out.
+fn full_summary(snapshot: Option<&Package>, package: &Package, with_deletes: bool) -> SyncSummary {
+    let before = mainline_ids(snapshot);
+    let after = mainline_ids(Some(package));
+    SyncSummary {
+        synced: after.len(),
+        created: after.difference(&before).count(),
+        updated: after.intersection(&before).count(),
+        deleted: if with_deletes {
+            before.difference(&after).count()
+        } else {
+            0
+        },
+        pr_revisions: pr_revisions(&package.pr_changes),
+    }
+}
+
+fn mainline_ids(package: Option<&Package>) -> BTreeSet<String> {
+    package
+        .iter()
+        .flat_map(|package| &package.mainline.specs)
+        .map(|spec| spec.id.clone())
+        .collect()
+}
+
+fn pr_revisions(prs: &[PrChangeSet]) -> usize {
+    prs.iter()
+        .flat_map(|pr| &pr.spec_changes)
+        .filter(|change| matches!(change, SpecChange::Upsert(_)))
+        .count()
+}
+
+fn merge_chunks(parts: Vec<Package>) -> Package {
+    let mut parts = parts.into_iter();
+    let mut merged = parts.next().expect("a run has at least its final chunk");
+    for part in parts {
+        merged.manifest.specs.extend(part.manifest.specs);
+        merged.mainline.specs.extend(part.mainline.specs);
+        merged.pr_changes.extend(part.pr_changes);
+    }
+    merged
+}
+
+/// Apply a delta on top of the snapshot: the snapshot must be at `base` on the
+/// delta's branch, otherwise the client is told to fall back to a full sync.
+fn apply_delta(
+    store: &mut ProjectStore,
+    delta: Package,
+    base: &str,
+) -> Result<SyncSummary, SyncError> {
+    let branch = delta
+        .manifest
+        .branch
+        .clone()
+        .ok_or_else(|| SyncError::Rejected("delta package names no branch".into()))?;
+    let (Some(snapshot), Some(synced)) = (store.snapshot.as_mut(), store.branches.get(&branch))
+    else {
+        return Err(SyncError::Conflict(format!(
+            "branch {branch} has not been synced"
+        )));
+    };
+    if synced != base {
+        return Err(SyncError::Conflict(format!(
+            "branch {branch} is at {synced}, not {base}"
+        )));
+    }
+
+    let mut upserts = delta.mainline.specs;
+    for (path, expected) in &delta.manifest.unchanged {
+        let held = path
+            .strip_prefix("main/")
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(dir, file)| {
+                let spec = snapshot.mainline.specs.iter().find(|s| s.dir_name == dir)?;
+                let asset = spec.assets.iter().find(|a| a.path == file)?;
+                let target = upserts.iter_mut().find(|s| s.dir_name == dir)?;
+                Some((target, asset))
+            })
+            .filter(|(_, asset)| digest(&asset.bytes) == *expected);
+        let Some((target, asset)) = held else {
+            return Err(SyncError::Rejected(format!(
+                "{path} is listed as unchanged but not held at {base}"
+            )));
+        };
+        target.assets.push(asset.clone());
+    }
+
+    let mut summary = SyncSummary {
+        synced: upserts.len(),
+        pr_revisions: pr_revisions(&delta.pr_changes),
+        ..SyncSummary::default()
+    };
+    let specs = &mut snapshot.mainline.specs;
+    for id in &delta.manifest.deleted_specs {
+        let before = specs.len();
+        specs.retain(|spec| &spec.id != id);
+        summary.deleted += before - specs.len();
+    }
+    for spec in upserts {
+        match specs.iter_mut().find(|existing| existing.id == spec.id) {
+            Some(existing) => {
+                *existing = spec;
+                summary.updated += 1;
+            }
+            None => {
+                specs.push(spec);
+                summary.created += 1;
+            }
+        }
+    }
+    specs.sort_by(|a, b| a.dir_name.cmp(&b.dir_name));
+
+    let mut entries = delta.manifest.specs.clone();
+    carry_approvals(&snapshot.manifest.specs, &mut entries);
+    let replaced: BTreeSet<&str> = delta
+        .manifest
+        .specs
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .chain(delta.manifest.deleted_specs.iter().map(String::as_str))
+        .collect();
+    snapshot
+        .manifest
+        .specs
+        .retain(|entry| !replaced.contains(entry.id.as_str()));
+    snapshot.manifest.specs.extend(entries);
+    snapshot.manifest.specs.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let resent: BTreeSet<u64> = delta.pr_changes.iter().map(|pr| pr.pr_number).collect();
+    snapshot.pr_changes.retain(|pr| {
+        !resent.contains(&pr.pr_number) && !delta.manifest.deleted_prs.contains(&pr.pr_number)
+    });
+    snapshot.pr_changes.extend(delta.pr_changes);
+    snapshot.pr_changes.sort_by_key(|pr| pr.pr_number);
+
+    if delta.project_config.is_some() {
+        snapshot.project_config = delta.project_config;
+    }
+    snapshot.manifest.commit = delta.manifest.commit;
+    snapshot.manifest.branch = delta.manifest.branch;
+    snapshot.manifest.timestamp = delta.manifest.timestamp;
+
+    let commit = snapshot.manifest.commit.clone().unwrap_or_default();
+    store.branches.insert(branch, commit);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::{Asset, DocFormat, Mainline, Manifest, SourceMode, Spec, PACKAGE_VERSION};
+    use chrono::{DateTime, Utc};
+
+    fn spec(id: &str, source: &str, asset: Option<&[u8]>) -> Spec {
+        Spec {
+            id: id.into(),
+            dir_name: format!("{id}-spec"),
+            format: DocFormat::Markdown,
+            source_path: "spec.md".into(),
+            source: source.as_bytes().to_vec(),
+            assets: asset
+                .map(|bytes| Asset {
+                    path: "figure.png".into(),
+                    content_type: None,
+                    bytes: bytes.to_vec(),
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn package(commit: &str, base: Option<&str>, specs: Vec<Spec>) -> Package {
+        Package {
+            manifest: Manifest {
+                package_version: PACKAGE_VERSION,
+                commit: Some(commit.into()),
+                branch: Some("main".into()),
+                timestamp: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap(),
+                source: SourceMode::Push,
+                specs: Vec::new(),
+                base_commit: base.map(str::to_string),
+                deleted_specs: Vec::new(),
+                deleted_prs: Vec::new(),
+                digests: BTreeMap::new(),
+                unchanged: BTreeMap::new(),
+            },
+            project_config: None,
+            mainline: Mainline { specs },
+            pr_changes: Vec::new(),
+        }
+    }
+
+    fn zip(package: &Package) -> Vec<u8> {
+        package
+            .write_zip(Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner()
+    }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dossiers-dev-api-{tag}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn deltas_apply_to_the_snapshot_and_reuse_unchanged_files() {
+        let dir = temp_dir("delta");
+        let api = DevApi::new(&dir);
+        let full = package(
+            "c1",
+            None,
+            vec![
+                spec("0001", "# One\n", Some(b"png")),
+                spec("0002", "# Two\n", None),
+            ],
+        );
+        let summary = api.sync("acme", &zip(&full), None).unwrap();
+        assert_eq!((summary.synced, summary.created), (2, 2));
+        assert_eq!(api.state("acme").unwrap().branches["main"], "c1");
+
+        let mut delta = package("c2", Some("c1"), vec![spec("0001", "# One v2\n", None)]);
+        delta.manifest.deleted_specs = vec!["0002".into()];
+        delta
+            .manifest
+            .unchanged
+            .insert("main/0001-spec/figure.png".into(), digest(b"png"));
+        let summary = api.sync("acme", &zip(&delta), None).unwrap();
+        assert_eq!((summary.updated, summary.deleted), (1, 1));
+
+        // The same delta again is based on a commit the server has moved past.
+        let stale = api.sync("acme", &zip(&delta), None).unwrap_err();
+        assert!(matches!(stale, SyncError::Conflict(_)), "{stale}");
+
+        // A restarted server picks up the persisted snapshot.
+        let reloaded = DevApi::new(&dir).snapshot("acme").unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(reloaded.manifest.commit.as_deref(), Some("c2"));
+        assert_eq!(reloaded.mainline.specs.len(), 1);
+        let one = &reloaded.mainline.specs[0];
+        assert_eq!(one.source, b"# One v2\n");
+        assert_eq!(one.assets[0].bytes, b"png");
+    }
+
+    #[test]
+    fn chunked_runs_replace_the_snapshot_on_the_last_chunk() {
+        let dir = temp_dir("chunks");
+        let api = DevApi::new(&dir);
+        api.sync(
+            "acme",
+            &zip(&package("c1", None, vec![spec("0009", "# Old\n", None)])),
+            None,
+        )
+        .unwrap();
+
+        let first = package("c2", None, vec![spec("0001", "# One\n", None)]);
+        let last = package("c2", None, vec![spec("0002", "# Two\n", None)]);
+        let chunk = |index, last| {
+            Some(Chunk {
+                run_id: "run-1",
+                index: Some(index),
+                last,
+            })
+        };
+        api.sync("acme", &zip(&first), chunk(0, false)).unwrap();
+        // A retried chunk replaces the first copy rather than duplicating it.
+        api.sync("acme", &zip(&first), chunk(0, false)).unwrap();
+        assert_eq!(
+            api.snapshot("acme").unwrap().unwrap().mainline.specs.len(),
+            1
+        );
+        assert_eq!(api.run_chunks("acme", "run-1").unwrap(), Some(vec![0]));
+        let summary = api.sync("acme", &zip(&last), chunk(1, true)).unwrap();
+        assert_eq!(api.run_chunks("acme", "run-1").unwrap(), None);
+        let snapshot = api.snapshot("acme").unwrap().unwrap();
+        let received = fs::read_dir(dir.join("acme/packages")).unwrap().count();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(summary.deleted, 1);
+        let ids: Vec<&str> = snapshot
+            .mainline
+            .specs
+            .iter()
+            .map(|s| s.id.as_str())
+            .collect();
+        assert_eq!(ids, ["0001", "0002"]);
+        assert_eq!(received, 4);
+    }
+
+    #[test]
+    fn final_chunks_need_every_earlier_chunk_of_a_live_run() {
+        let dir = temp_dir("final-chunks");
+        let api = DevApi::new(&dir);
+        let chunk = |run_id, index, last| {
+            Some(Chunk {
+                run_id,
+                index: Some(index),
+                last,
+            })
+        };
+        let part = |id: &str| zip(&package("c2", None, vec![spec(id, "# Spec\n", None)]));
+
+        api.sync("acme", &part("0001"), chunk("run-1", 0, false))
+            .unwrap();
+        api.sync("acme", &part("0002"), chunk("run-1", 1, true))
+            .unwrap();
+        assert!(api.run_finalized("acme", "run-1").unwrap());
+        // The final chunk posted again, as a client retrying a timed-out
+        // request would, leaves the snapshot alone.
+        let repeated = api
+            .sync("acme", &part("0002"), chunk("run-1", 1, true))
+            .unwrap_err();
+
+        api.sync("acme", &part("0003"), chunk("run-2", 0, false))
+            .unwrap();
+        let gap = api
+            .sync("acme", &part("0005"), chunk("run-2", 2, true))
+            .unwrap_err();
+        let held = api.run_chunks("acme", "run-2").unwrap();
+        let snapshot = api.snapshot("acme").unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(matches!(repeated, SyncError::Conflict(_)), "{repeated}");
+        assert!(matches!(gap, SyncError::Conflict(_)), "{gap}");
+        assert!(gap.to_string().contains("missing chunk(s) 1"), "{gap}");
+        assert_eq!(held, Some(vec![0]));
+        assert!(!api.run_finalized("acme", "run-2").unwrap());
+        let ids: Vec<&str> = snapshot
+            .mainline
+            .specs
+            .iter()
+            .map(|s| s.id.as_str())
+            .collect();
+        assert_eq!(ids, ["0001", "0002"]);
+    }
+
+    #[test]
+    fn syncs_without_approvals_keep_the_held_ones() {
+        let dir = temp_dir("approvals");
+        let api = DevApi::new(&dir);
+        let synced = |commit: &str, base: Option<&str>, approved_by: &[&str]| {
+            let mut package = package(commit, base, vec![spec("0001", "# One\n", None)]);
+            package.manifest.specs = vec![serde_json::from_value(serde_json::json!({
+                "id": "0001",
+                "dir_name": "0001-spec",
+                "source_path": "spec.md",
+                "format": "markdown",
+                "title": "One",
+                "status": "Accepted",
+                "approved_by": approved_by,
+            }))
+            .unwrap()];
+            zip(&package)
+        };
+        let approvals = || {
+            let snapshot = api.snapshot("acme").unwrap().unwrap();
+            snapshot.manifest.specs[0].approved_by.clone()
+        };
+
+        api.sync("acme", &synced("c1", None, &["grace"]), None)
+            .unwrap();
+        api.sync("acme", &synced("c2", None, &[]), None).unwrap();
+        let after_full = approvals();
+        api.sync("acme", &synced("c3", Some("c2"), &[]), None)
+            .unwrap();
+        let after_delta = approvals();
+        api.sync("acme", &synced("c4", Some("c3"), &["ada"]), None)
+            .unwrap();
+        let after_new_approval = approvals();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(after_full, ["grace"]);
+        assert_eq!(after_delta, ["grace"]);
+        assert_eq!(after_new_approval, ["ada"]);
+    }
+
+    #[test]
+    fn rejects_bad_projects_and_untrusted_packages() {
+        let dir = temp_dir("trust");
+        let key = crate::bundle::parse_signing_key(&"05".repeat(32)).unwrap();
+        let api = DevApi::new(&dir).with_trusted_keys(vec![key.verifying_key()]);
+        let pkg = package("c1", None, vec![spec("0001", "# One\n", None)]);
+
+        let unsigned = api.sync("acme", &zip(&pkg), None).unwrap_err();
+        let traversal = api.state("..").unwrap_err();
+        let signed = pkg
+            .write_zip_signed(Cursor::new(Vec::new()), &key)
+            .unwrap()
+            .into_inner();
+        let accepted = api.sync("acme", &signed, None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(matches!(unsigned, SyncError::Rejected(_)), "{unsigned}");
+        assert!(matches!(traversal, SyncError::Rejected(_)), "{traversal}");
+        assert!(accepted.is_ok());
+    }
+}
@@ -0,0 +1,474 @@
+//! Build-time rendering of diagram code blocks to inline SVG.
+//!
+//! Code blocks whose language has a registered renderer (`dot`, `plantuml`,
+//! `d2`, ...) are rendered while a spec is processed instead of being shipped
+//! as source. A renderer is either a local command, which reads the diagram
+//! source on stdin and writes SVG to stdout, or a renderer compiled into the
+//! binary (Graphviz DOT via `layout-rs`, Mermaid via an embedded JavaScript
+//! runtime). Mermaid is rendered in the browser unless it is explicitly
+//! registered here; svgbob keeps its dedicated path in `render_markdown`.
+//!
+//! Rendered SVG is cached by a hash of the renderer and the source — in memory
+//! for the life of the registry and on disk under the cache directory — so
+//! unchanged diagrams are not re-rendered on every build or reload. A renderer
+//! that fails (missing command, syntax error, panic) yields `None` and the
+//! caller falls back to showing the source.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use serde_json::Value as JsonValue;
+
+use crate::mermaid::MermaidRenderer;
+
+/// Default on-disk cache location, relative to the project root.
+const DEFAULT_CACHE_DIR: &str = "target/diagram-cache";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagramRenderer {
+    /// Program and arguments of a local command: source on stdin, SVG on
+    /// stdout.
+    Command(Vec<String>),
+    /// Graphviz DOT laid out and drawn by the pure-Rust `layout-rs` crate.
+    EmbeddedGraphviz,
+    /// The vendored Mermaid runtime evaluated in QuickJS.
+    EmbeddedMermaid,
+}
+
+impl DiagramRenderer {
+    fn from_json_value(lang: &str, value: &JsonValue) -> Option<Self> {
+        if let Some(command) = value.get("command") {
+            let argv: Vec<String> = match command {
+                JsonValue::String(line) => line.split_whitespace().map(str::to_string).collect(),
+                JsonValue::Array(items) => items
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                _ => Vec::new(),
+            };
+            return (!argv.is_empty()).then_some(Self::Command(argv));
+        }
+        match value.get("renderer").and_then(JsonValue::as_str) {
+            Some("embedded") => embedded_renderer(lang),
+            Some("graphviz") => Some(Self::EmbeddedGraphviz),
+            Some("mermaid") => Some(Self::EmbeddedMermaid),
+            _ => None,
+        }
+    }
+}
+
+/// The `[diagrams]` section of `dossiers.toml`.
+///
+/// ```toml
+/// [diagrams]
+/// cache_dir = "target/diagram-cache"
+///
+/// [diagrams.plantuml]
+/// command = "plantuml -tsvg -pipe"
+///
+/// [diagrams.dot]
+/// renderer = "embedded"
+///
+/// [diagrams.d2]
+/// enabled = false
+///
+/// # Opt in to build-time Mermaid; otherwise it renders in the browser.
+/// [diagrams.mermaid]
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiagramConfig {
+    /// Per-language overrides of the built-in renderers. `None` disables the
+    /// language so its blocks render as plain code.
+    pub renderers: BTreeMap<String, Option<DiagramRenderer>>,
+    pub cache_dir: Option<String>,
+    /// Whether rendered SVG is cached on disk (default true).
+    pub disk_cache: bool,
+}
+
+impl Default for DiagramConfig {
+    fn default() -> Self {
+        Self {
+            renderers: BTreeMap::new(),
+            cache_dir: None,
+            disk_cache: true,
+        }
+    }
+}
+
+impl DiagramConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let mut config = Self::default();
+        let Some(map) = value.as_object() else {
+            return config;
+        };
+
+        for (key, entry) in map {
+            match (key.as_str(), entry) {
+                ("cache_dir" | "cacheDir", JsonValue::String(dir)) => {
+                    config.cache_dir = Some(dir.trim().to_string()).filter(|d| !d.is_empty());
+                }
+                ("cache", JsonValue::Bool(enabled)) => config.disk_cache = *enabled,
+                (lang, JsonValue::Object(_)) => {
+                    let enabled = entry
+                        .get("enabled")
+                        .and_then(JsonValue::as_bool)
+                        .unwrap_or(true);
+                    let renderer = if enabled {
+                        DiagramRenderer::from_json_value(lang, entry)
+                            .or_else(|| builtin_renderer(lang))
+                            .or_else(|| embedded_renderer(lang))
+                    } else {
+                        None
+                    };
+                    config
+                        .renderers
+                        .insert(lang.trim().to_ascii_lowercase(), renderer);
+                }
+                _ => {}
+            }
+        }
+        config
+    }
+
+    /// Render `lang` with its embedded renderer, e.g. for
+    /// `build --prerender-mermaid`.
+    pub fn enable_embedded(&mut self, lang: &str) {
+        if let Some(renderer) = embedded_renderer(lang) {
+            self.renderers.insert(lang.to_string(), Some(renderer));
+        }
+    }
+}
+
+/// Renderers available without configuration.
+fn builtin_renderer(lang: &str) -> Option<DiagramRenderer> {
+    let command =
+        |argv: &[&str]| DiagramRenderer::Command(argv.iter().map(|s| s.to_string()).collect());
+    match lang {
+        "dot" | "graphviz" => embedded_renderer(lang),
+        "plantuml" | "puml" => Some(command(&["plantuml", "-tsvg", "-pipe"])),
+        "d2" => Some(command(&["d2", "-", "-"])),
+        _ => None,
+    }
+}
+
+/// Renderers compiled into the binary. Mermaid is not a built-in default:
+/// loading its runtime is slow and the browser renders it otherwise.
+fn embedded_renderer(lang: &str) -> Option<DiagramRenderer> {
+    match lang {
+        "dot" | "graphviz" => Some(DiagramRenderer::EmbeddedGraphviz),
+        "mermaid" => Some(DiagramRenderer::EmbeddedMermaid),
+        _ => None,
+    }
+}
+
+/// Language → renderer lookup plus the SVG cache.
+#[derive(Debug, Default)]
+pub struct DiagramRegistry {
+    renderers: HashMap<String, DiagramRenderer>,
+    cache_dir: Option<PathBuf>,
+    memo: Mutex<HashMap<String, Option<String>>>,
+    mermaid: Option<MermaidRenderer>,
+}
+
+impl DiagramRegistry {
+    /// The built-in renderers with the project's overrides applied. Relative
+    /// cache directories resolve against `project_root`.
+    pub fn from_config(config: &DiagramConfig, project_root: &Path) -> Self {
+        let mut renderers: HashMap<String, DiagramRenderer> =
+            ["dot", "graphviz", "plantuml", "puml", "d2"]
+                .into_iter()
+                .filter_map(|lang| builtin_renderer(lang).map(|r| (lang.to_string(), r)))
+                .collect();
+        for (lang, renderer) in &config.renderers {
+            match renderer {
+                Some(renderer) => {
+                    renderers.insert(lang.clone(), renderer.clone());
+                }
+                None => {
+                    renderers.remove(lang);
+                }
+            }
+        }
+
+        let cache_dir = config.disk_cache.then(|| {
+            let dir = config.cache_dir.as_deref().unwrap_or(DEFAULT_CACHE_DIR);
+            project_root.join(dir)
+        });
+
+        Self {
+            renderers,
+            cache_dir,
+            memo: Mutex::new(HashMap::new()),
+            mermaid: None,
+        }
+    }
+
+    /// Supplies the Mermaid runtime (`mermaid.min.js`) used by the embedded
+    /// Mermaid renderer. Without it, Mermaid blocks fall back to the browser.
+    pub fn with_mermaid_script(mut self, script: String) -> Self {
+        self.mermaid = Some(MermaidRenderer::new(script));
+        self
+    }
+
+    /// Whether blocks in `lang` are rendered by this registry.
+    pub fn handles(&self, lang: &str) -> bool {
+        self.renderers.contains_key(&lang.to_ascii_lowercase())
+    }
+
+    /// Render `source` to an inline `<svg>` element, or `None` when the
+    /// language has no renderer or rendering failed.
+    pub fn render(&self, lang: &str, source: &str) -> Option<String> {
+        let renderer = self.renderers.get(&lang.to_ascii_lowercase())?;
+        let key = self.cache_key(renderer, source);
+
+        if let Some(cached) = self.memo.lock().ok()?.get(&key) {
+            return cached.clone();
+        }
+
+        let cache_path = self
+            .cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{key}.svg")));
+        if let Some(svg) = cache_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+        {
+            self.remember(&key, Some(svg.clone()));
+            return Some(svg);
+        }
+
+        let rendered = match self.render_with(renderer, &key, source) {
+            Ok(svg) => Some(svg),
+            Err(err) => {
+                eprintln!("Warning: failed to render {lang} diagram: {err}");
+                None
+            }
+        };
+
+        if let (Some(svg), Some(path)) = (rendered.as_ref(), cache_path.as_ref()) {
+            if let Some(parent) = path.parent() {
+                let _ = dossiers::http_cache::create_cache_dir(parent);
+            }
+            if let Err(err) = fs::write(path, svg) {
+                eprintln!(
+                    "Warning: failed to cache diagram at {}: {err}",
+                    path.display()
+                );
+            }
+        }
+        self.remember(&key, rendered.clone());
+        rendered
+    }
+
+    fn remember(&self, key: &str, value: Option<String>) {
+        if let Ok(mut memo) = self.memo.lock() {
+            memo.insert(key.to_string(), value);
+        }
+    }
+
+    fn cache_key(&self, renderer: &DiagramRenderer, source: &str) -> String {
+        let descriptor = match renderer {
+            DiagramRenderer::Command(argv) => format!("command:{}", argv.join("\u{1f}")),
+            DiagramRenderer::EmbeddedGraphviz => "embedded:graphviz".to_string(),
+            DiagramRenderer::EmbeddedMermaid => format!(
+                "embedded:mermaid:{}",
+                self.mermaid
+                    .as_ref()
+                    .map(MermaidRenderer::fingerprint)
+                    .unwrap_or_default()
+            ),
+        };
+        let digest = md5::compute(format!("{descriptor}\0{source}").as_bytes());
+        format!("{digest:x}")
+    }
+
+    fn render_with(
+        &self,
+        renderer: &DiagramRenderer,
+        key: &str,
+        source: &str,
+    ) -> Result<String, String> {
+        let output = match renderer {
+            DiagramRenderer::Command(argv) => run_command(argv, source)?,
+            DiagramRenderer::EmbeddedGraphviz => render_graphviz(source)?,
+            DiagramRenderer::EmbeddedMermaid => {
+                let mermaid = self.mermaid.as_ref().ok_or("no Mermaid runtime loaded")?;
+                // Derived from the cache key so the same diagram always gets
+                // the same id; Mermaid scopes its styles to it.
+                mermaid.render(&format!("mermaid-{}", &key[..12]), source)?
+            }
+        };
+        extract_svg(&output).ok_or_else(|| "renderer produced no <svg> element".to_string())
+    }
+}
+
+fn run_command(argv: &[String], source: &str) -> Result<String, String> {
+    let (program, args) = argv.split_first().ok_or("empty command")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("could not run `{program}`: {err}"))?;
+    // Feed stdin from another thread while stdout is drained: a renderer that
+    // writes as it reads would otherwise fill its output pipe and stall.
+    let writer = child.stdin.take().map(|mut stdin| {
+        let source = source.to_string();
+        std::thread::spawn(move || stdin.write_all(source.as_bytes()))
+    });
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("waiting for `{program}`: {err}"))?;
+    let written = writer.map_or(Ok(Ok(())), |writer| writer.join());
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "`{program}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    match written {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => return Err(format!("writing to `{program}`: {err}")),
+        Err(_) => return Err(format!("writing to `{program}` panicked")),
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|err| format!("`{program}` wrote invalid UTF-8: {err}"))
+}
+
+fn render_graphviz(source: &str) -> Result<String, String> {
+    let source = source.to_string();
+    std::panic::catch_unwind(move || {
+        let mut parser = layout::gv::DotParser::new(&source);
+        let graph = parser.process()?;
+        let mut builder = layout::gv::GraphBuilder::new();
+        builder.visit_graph(&graph);
+        let mut visual = builder.get();
+        if visual.num_nodes() == 0 {
+            return Err("graph has no nodes".to_string());
+        }
+        let mut writer = layout::backends::svg::SVGWriter::new();
+        visual.do_it(false, false, false, &mut writer);
+        Ok(writer.finalize())
+    })
+    .map_err(|_| "graphviz layout panicked".to_string())?
+}
+
+/// The `<svg>…</svg>` element of a renderer's output, without any XML
+/// declaration or doctype that would be invalid inline.
+fn extract_svg(output: &str) -> Option<String> {
+    let start = output.find("<svg")?;
+    let end = output.rfind("</svg>")? + "</svg>".len();
+    (start < end).then(|| output[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "dossiers-diagram-{tag}-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_nanos()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn config_overrides_and_disables_builtins() {
+        let config = DiagramConfig::from_json_value(&serde_json::json!({
+            "cache_dir": "cache",
+            "plantuml": { "command": ["java", "-jar", "plantuml.jar", "-tsvg", "-pipe"] },
+            "d2": { "enabled": false },
+            "nomnoml": { "command": "nomnoml-svg" },
+        }));
+        let registry = DiagramRegistry::from_config(&config, Path::new("/project"));
+
+        assert!(registry.handles("dot"));
+        assert!(registry.handles("PlantUML"));
+        assert!(!registry.handles("d2"));
+        assert!(registry.handles("nomnoml"));
+        assert_eq!(registry.cache_dir, Some(PathBuf::from("/project/cache")));
+        assert_eq!(
+            registry.renderers.get("plantuml"),
+            Some(&DiagramRenderer::Command(vec![
+                "java".into(),
+                "-jar".into(),
+                "plantuml.jar".into(),
+                "-tsvg".into(),
+                "-pipe".into(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn embedded_graphviz_renders_inline_svg() {
+        let config = DiagramConfig {
+            disk_cache: false,
+            ..DiagramConfig::default()
+        };
+        let registry = DiagramRegistry::from_config(&config, Path::new("/unused"));
+        let svg = registry
+            .render("dot", "digraph { a -> b; }")
+            .expect("dot renders");
+        assert!(svg.starts_with("<svg"), "not inline svg: {svg}");
+        assert!(!svg.contains("<?xml"), "xml declaration kept: {svg}");
+
+        assert!(registry.render("dot", "digraph {").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_output_is_cached_on_disk_by_content() {
+        let root = temp_dir("cache");
+        let source = "<svg xmlns=\"http://www.w3.org/2000/svg\"><text>hi</text></svg>";
+        let config = |command: &str| {
+            DiagramConfig::from_json_value(&serde_json::json!({
+                "echo": { "command": command },
+            }))
+        };
+
+        let registry = DiagramRegistry::from_config(&config("cat"), &root);
+        assert_eq!(registry.render("echo", source).as_deref(), Some(source));
+
+        // A fresh registry with the same renderer reads the cached file
+        // rather than re-running the command.
+        let key = registry.cache_key(&DiagramRenderer::Command(vec!["cat".into()]), source);
+        let cache_file = root.join(DEFAULT_CACHE_DIR).join(format!("{key}.svg"));
+        assert_eq!(
+            fs::read_to_string(root.join(DEFAULT_CACHE_DIR).join(".gitignore")).ok(),
+            Some("*\n".to_string())
+        );
+        fs::write(&cache_file, "<svg>cached</svg>").expect("overwrite cache entry");
+        let cached = DiagramRegistry::from_config(&config("cat"), &root);
+        assert_eq!(
+            cached.render("echo", source).as_deref(),
+            Some("<svg>cached</svg>")
+        );
+
+        let failing = DiagramRegistry::from_config(&config("false"), &root);
+        assert!(failing.render("echo", source).is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn large_command_output_does_not_stall_on_full_pipes() {
+        // `cat` writes while it reads, so a source bigger than a pipe buffer
+        // only gets through if stdin and stdout are serviced concurrently.
+        let source = format!("<svg>{}</svg>", "x".repeat(1 << 20));
+        assert_eq!(run_command(&["cat".to_string()], &source), Ok(source));
+    }
+}
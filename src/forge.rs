@@ -0,0 +1,366 @@
+//! Code hosts ("forges") whose open pull requests become PR revisions.
+//!
+//! GitHub pull requests and GitLab merge requests are both reduced to
+//! [`PullRequest`] and [`PullFile`], so the revision pipeline only ever talks
+//! to a `dyn Forge`. Each implementation maps its REST API onto the same
+//! handful of calls: list open requests and the ones that merged a commit,
+//! list a request's changed files and reviews, download a file at a commit,
+//! and look up who authored a commit.
+
+use std::fmt;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+/// An open or merged pull (or merge) request.
+#[derive(Clone, Debug, Default)]
+pub struct PullRequest {
+    /// The number readers see: a GitHub PR number or a GitLab MR `iid`.
+    pub number: u64,
+    pub draft: bool,
+    pub head_sha: String,
+    pub head_ref: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub author: Option<String>,
+    /// Avatar of the request's author, if the forge returned one.
+    pub avatar_url: Option<String>,
+    pub title: String,
+    pub html_url: String,
+    /// Logins asked to review who have not yet done so.
+    pub requested_reviewers: Vec<String>,
+    pub labels: Vec<String>,
+    /// The commit the request landed as, once merged.
+    pub merge_commit_sha: Option<String>,
+    pub merged_at: Option<i64>,
+}
+
+/// A reviewer's verdict on a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    /// An approval or change request later dismissed by a maintainer.
+    Dismissed,
+}
+
+impl ReviewState {
+    /// GitHub's name for the state, also used on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Approved => "APPROVED",
+            Self::ChangesRequested => "CHANGES_REQUESTED",
+            Self::Commented => "COMMENTED",
+            Self::Dismissed => "DISMISSED",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_uppercase().as_str() {
+            "APPROVED" => Some(Self::Approved),
+            "CHANGES_REQUESTED" => Some(Self::ChangesRequested),
+            "COMMENTED" => Some(Self::Commented),
+            "DISMISSED" => Some(Self::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// A submitted review of a request.
+#[derive(Clone, Debug)]
+pub struct PullReview {
+    pub reviewer: String,
+    pub state: ReviewState,
+    pub submitted_at: i64,
+}
+
+/// Each reviewer's standing verdict, in the order they first reviewed: their
+/// latest approval or change request, unless dismissed since. Comments never
+/// override a verdict, and reviewers who only commented are listed as such.
+pub fn standing_reviews(reviews: &[PullReview]) -> Vec<PullReview> {
+    let mut sorted: Vec<&PullReview> = reviews.iter().collect();
+    sorted.sort_by_key(|review| review.submitted_at);
+    let mut standing: Vec<PullReview> = Vec::new();
+    for review in sorted {
+        match standing
+            .iter_mut()
+            .find(|seen| seen.reviewer == review.reviewer)
+        {
+            Some(seen) if review.state != ReviewState::Commented => *seen = review.clone(),
+            Some(_) => {}
+            None => standing.push(review.clone()),
+        }
+    }
+    standing.retain(|review| review.state != ReviewState::Dismissed);
+    standing
+}
+
+/// Who approved a request and still stands by it.
+pub fn approvers(reviews: &[PullReview]) -> Vec<String> {
+    standing_reviews(reviews)
+        .into_iter()
+        .filter(|review| review.state == ReviewState::Approved)
+        .map(|review| review.reviewer)
+        .collect()
+}
+
+/// A file changed by a pull request. `status` uses GitHub's vocabulary
+/// (`added`, `modified`, `removed`, `renamed`) whatever the forge.
+#[derive(Clone, Debug)]
+pub struct PullFile {
+    pub filename: String,
+    pub status: String,
+    /// Direct download URL for the file at the request's head, when the forge
+    /// lists one.
+    pub raw_url: Option<String>,
+    pub previous_filename: Option<String>,
+}
+
+/// The account behind a commit: the git author email recorded in the commit
+/// object, plus the forge account that email is linked to (if any), with its
+/// avatar and profile URL. Used to credit and illustrate the initial committer
+/// of a spec.
+#[derive(Clone, Debug, Default)]
+pub struct CommitIdentity {
+    pub email: Option<String>,
+    pub login: Option<String>,
+    pub avatar_url: Option<String>,
+    pub html_url: Option<String>,
+}
+
+/// Which forge a project lives on, from `forge` in dossiers.toml.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+}
+
+impl ForgeKind {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "github" => Some(Self::Github),
+            "gitlab" => Some(Self::Gitlab),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ForgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Github => "GitHub",
+            Self::Gitlab => "GitLab",
+        })
+    }
+}
+
+pub trait Forge: Send + Sync {
+    fn kind(&self) -> ForgeKind;
+
+    /// The repository as its forge names it, e.g. `acme/specs` or
+    /// `group/subgroup/specs`.
+    fn repo_slug(&self) -> String;
+
+    /// How the forge refers to a request in prose: `acme/specs#4` on GitHub,
+    /// `acme/specs!4` on GitLab.
+    fn pull_reference(&self, number: u64) -> String;
+
+    /// Web page of a request, for when the listing did not include one.
+    fn pull_url(&self, number: u64) -> String;
+
+    fn profile_url(&self, login: &str) -> String;
+
+    fn list_open_pulls(&self) -> Result<Vec<PullRequest>>;
+
+    fn list_pull_files(&self, number: u64) -> Result<Vec<PullFile>>;
+
+    /// Submitted reviews of request `number`, oldest first.
+    fn list_pull_reviews(&self, number: u64) -> Result<Vec<PullReview>>;
+
+    /// The merged requests that brought commit `sha` in, newest first.
+    fn list_commit_pulls(&self, sha: &str) -> Result<Vec<PullRequest>>;
+
+    /// Resolve a commit to the forge account behind its author.
+    fn get_commit(&self, sha: &str) -> Result<CommitIdentity>;
+
+    /// Fetch a URL with the forge's credentials, such as a file's `raw_url`.
+    fn download_bytes(&self, url: &str) -> Result<Vec<u8>>;
+
+    fn download_file_at_ref(&self, path: &str, reference: &str) -> Result<Vec<u8>>;
+
+    /// Post `body` as a comment on request `number`, or edit the comment an
+    /// earlier call left there (found by `marker` in its body), so repeated
+    /// runs keep a single comment up to date. Returns the comment's web URL.
+    fn upsert_pull_comment(&self, number: u64, marker: &str, body: &str) -> Result<String>;
+}
+
+/// A comment on a pull request, as far as finding our own again needs.
+#[derive(Clone, Debug)]
+pub(crate) struct PullComment {
+    pub id: u64,
+    /// Login of the comment's author.
+    pub author: Option<String>,
+    pub body: String,
+}
+
+/// The comment in `comments` that `author` wrote and that carries `marker`,
+/// if any. Comments by anyone else are passed over, so a reviewer quoting
+/// ours is never overwritten.
+pub(crate) fn find_marked_comment(
+    comments: &[PullComment],
+    marker: &str,
+    author: &str,
+) -> Option<u64> {
+    comments
+        .iter()
+        .filter(|comment| {
+            comment
+                .author
+                .as_deref()
+                .is_some_and(|login| login.eq_ignore_ascii_case(author))
+        })
+        .find(|comment| comment.body.contains(marker))
+        .map(|comment| comment.id)
+}
+
+/// Milliseconds since the epoch for an RFC 3339 timestamp from a forge API;
+/// now, if it cannot be parsed.
+pub(crate) fn parse_timestamp(raw: &str) -> i64 {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or_else(|_| Utc::now().timestamp_millis())
+}
+
+/// A canned-response HTTP server for exercising forge clients offline.
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// One canned response. A reply with an `etag` header answers a matching
+    /// `If-None-Match` with `304 Not Modified`.
+    #[derive(Clone)]
+    pub(crate) struct StubReply {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    impl StubReply {
+        pub(crate) fn status(status: u16, body: impl Into<String>) -> Self {
+            Self {
+                status,
+                headers: Vec::new(),
+                body: body.into(),
+            }
+        }
+
+        pub(crate) fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+            self.headers.push((name.to_ascii_lowercase(), value.into()));
+            self
+        }
+    }
+
+    impl From<String> for StubReply {
+        fn from(body: String) -> Self {
+            Self::status(200, body)
+        }
+    }
+
+    /// Serve `routes` (request path and query → reply) on a local port until
+    /// the test process exits. A route may be prefixed with a method, as in
+    /// `POST /path`, to answer only that method. A route listed more than once
+    /// answers with each reply in turn, then keeps repeating the last. Returns
+    /// the base URL and a log of every request line plus its auth and
+    /// `If-None-Match` headers; requests other than GET are logged with their
+    /// method and body.
+    pub(crate) fn serve<R: Into<StubReply>>(
+        routes: Vec<(&'static str, R)>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub");
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&log);
+        let mut routes: Vec<(&'static str, StubReply)> = routes
+            .into_iter()
+            .map(|(route, reply)| (route, reply.into()))
+            .collect();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap_or_default().to_string();
+                let target = parts.next().unwrap_or_default().to_string();
+                let keyed = format!("{method} {target}");
+                let mut auth = String::new();
+                let mut if_none_match = None;
+                let mut content_length = 0usize;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    let lower = line.to_ascii_lowercase();
+                    if lower.starts_with("authorization:") || lower.starts_with("private-token:") {
+                        auth = line.trim().to_string();
+                    }
+                    if lower.starts_with("if-none-match:") {
+                        if_none_match = line.split_once(':').map(|(_, v)| v.trim().to_string());
+                    }
+                    if let Some(len) = lower.strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap_or(0);
+                    }
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+                let mut entry = format!("{target} {auth}");
+                if let Some(etag) = &if_none_match {
+                    entry.push_str(&format!(" if-none-match={etag}"));
+                }
+                if method != "GET" {
+                    entry = format!("{method} {entry} body={}", String::from_utf8_lossy(&body));
+                }
+                seen.lock().unwrap().push(entry);
+
+                let matching: Vec<usize> = routes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (route, _))| *route == target || *route == keyed)
+                    .map(|(index, _)| index)
+                    .collect();
+                let reply = match matching.as_slice() {
+                    [] => StubReply::status(404, "{\"message\":\"404 Not Found\"}"),
+                    [only] => routes[*only].1.clone(),
+                    [first, ..] => routes.remove(*first).1,
+                };
+                let etag = reply
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name == "etag")
+                    .map(|(_, value)| value.clone());
+                let (status, body) = if etag.is_some() && etag == if_none_match {
+                    (304, String::new())
+                } else {
+                    (reply.status, reply.body)
+                };
+                let mut head = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-type: application/json\r\n\
+                     content-length: {}\r\nconnection: close\r\n",
+                    body.len()
+                );
+                for (name, value) in &reply.headers {
+                    head.push_str(&format!("{name}: {value}\r\n"));
+                }
+                let _ = write!(stream, "{head}\r\n{body}");
+            }
+        });
+        (base, log)
+    }
+}
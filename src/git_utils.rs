@@ -1,4 +1,4 @@
-use git2::{Delta, Repository, Sort};
+use git2::{Delta, DiffFindOptions, Mailmap, Oid, Repository, Signature, Sort};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -77,6 +77,353 @@ impl GitRepository {
         }
         Some(paths)
     }
+
+    /// Contents of the repository-relative `path` as committed at `rev`, or
+    /// `None` if either does not resolve.
+    pub fn read_file_at(&self, rev: &str, path: &Path) -> Option<Vec<u8>> {
+        let tree = self
+            .repo
+            .revparse_single(rev)
+            .ok()?
+            .peel_to_commit()
+            .ok()?
+            .tree()
+            .ok()?;
+        let entry = tree.get_path(path).ok()?;
+        let blob = entry.to_object(&self.repo).ok()?.peel_to_blob().ok()?;
+        Some(blob.content().to_vec())
+    }
+
+    fn commit(&self, rev: &str) -> Option<git2::Commit<'_>> {
+        self.repo.revparse_single(rev).ok()?.peel_to_commit().ok()
+    }
+
+    /// The commit a pull request is diffed against: `branch` (preferring the
+    /// remote-tracking copy), else the remote's default branch, else HEAD.
+    pub fn base_commit_sha(&self, branch: Option<&str>) -> Option<String> {
+        let mut candidates = Vec::new();
+        if let Some(branch) = branch {
+            candidates.push(format!("refs/remotes/origin/{branch}"));
+            candidates.push(format!("refs/heads/{branch}"));
+        }
+        candidates.push("refs/remotes/origin/HEAD".to_string());
+        candidates.push("HEAD".to_string());
+        candidates
+            .iter()
+            .find_map(|rev| self.commit(rev))
+            .map(|commit| commit.id().to_string())
+    }
+
+    /// Pull request heads fetched into this clone, as GitHub
+    /// (`refs/pull/N/head`), GitLab (`refs/merge-requests/N/head`) or the
+    /// common `+refs/pull/*/head:refs/remotes/origin/pr/*` refspec lay them
+    /// out. One head per number, sorted by number.
+    pub fn pull_request_heads(&self) -> Vec<LocalPullHead> {
+        let Ok(references) = self.repo.references() else {
+            return Vec::new();
+        };
+        let mailmap = self.repo.mailmap().ok();
+        let mut heads: HashMap<u64, LocalPullHead> = HashMap::new();
+        for reference in references.flatten() {
+            let Some(name) = reference.name() else {
+                continue;
+            };
+            let number = name
+                .strip_prefix("refs/pull/")
+                .or_else(|| name.strip_prefix("refs/merge-requests/"))
+                .and_then(|rest| rest.strip_suffix("/head"))
+                .or_else(|| {
+                    let rest = name.strip_prefix("refs/remotes/")?;
+                    rest.split_once("/pr/").map(|(_, number)| number)
+                })
+                .and_then(|number| number.parse::<u64>().ok());
+            let (Some(number), Ok(commit)) = (number, reference.peel_to_commit()) else {
+                continue;
+            };
+            let author = mapped_author(&commit, mailmap.as_ref());
+            heads.entry(number).or_insert_with(|| LocalPullHead {
+                number,
+                sha: commit.id().to_string(),
+                summary: commit.summary().unwrap_or_default().trim().to_string(),
+                author: GitAuthor {
+                    name: author.name().unwrap_or_default().to_string(),
+                    email: author.email().unwrap_or_default().to_string(),
+                    commit_sha: commit.id().to_string(),
+                },
+                committed_at: commit_time_to_millis(&commit),
+            });
+        }
+        let mut heads: Vec<LocalPullHead> = heads.into_values().collect();
+        heads.sort_by_key(|head| head.number);
+        heads
+    }
+
+    /// Files a pull request at `head` changes relative to where it branched
+    /// from `base` (their merge base), with renames detected. `None` when
+    /// either commit is missing locally.
+    pub fn changed_files_between(&self, base: &str, head: &str) -> Option<Vec<ChangedFile>> {
+        let head = self.commit(head)?;
+        let base = self.commit(base)?;
+        let fork_point = self
+            .repo
+            .merge_base(base.id(), head.id())
+            .and_then(|oid| self.repo.find_commit(oid))
+            .unwrap_or(base);
+        let mut diff = self
+            .repo
+            .diff_tree_to_tree(
+                Some(&fork_point.tree().ok()?),
+                Some(&head.tree().ok()?),
+                None,
+            )
+            .ok()?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+            .ok()?;
+
+        let path_of = |file: git2::DiffFile| {
+            file.path()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        };
+        let files = diff
+            .deltas()
+            .filter_map(|delta| {
+                let old = path_of(delta.old_file());
+                let new = path_of(delta.new_file());
+                let (path, status, previous_path) = match delta.status() {
+                    Delta::Added | Delta::Copied => (new?, "added", None),
+                    Delta::Deleted => (old?, "removed", None),
+                    Delta::Renamed => (new?, "renamed", old),
+                    _ => (new.or(old)?, "modified", None),
+                };
+                Some(ChangedFile {
+                    path,
+                    status,
+                    previous_path,
+                })
+            })
+            .collect();
+        Some(files)
+    }
+
+    /// The author of commit `rev`.
+    pub fn commit_author(&self, rev: &str) -> Option<GitAuthor> {
+        let commit = self.commit(rev)?;
+        let author = mapped_author(&commit, self.repo.mailmap().ok().as_ref());
+        Some(GitAuthor {
+            name: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            commit_sha: commit.id().to_string(),
+        })
+    }
+
+    /// Branches in this clone, named as on their remote (`spec/x` for
+    /// `refs/remotes/origin/spec/x`). A remote-tracking branch wins over a
+    /// local one of the same name, as the fresher copy in a CI checkout.
+    pub fn branches(&self) -> Vec<NamedRef> {
+        let mut branches: HashMap<String, NamedRef> = HashMap::new();
+        for (prefix, remote) in [("refs/heads/", false), ("refs/remotes/", true)] {
+            let Ok(references) = self.repo.references_glob(&format!("{prefix}*")) else {
+                continue;
+            };
+            for reference in references.flatten() {
+                let Some(name) = reference.name().and_then(|name| name.strip_prefix(prefix)) else {
+                    continue;
+                };
+                let name = if remote {
+                    match name.split_once('/') {
+                        Some((_, branch)) if branch != "HEAD" => branch,
+                        _ => continue,
+                    }
+                } else {
+                    name
+                };
+                let Ok(commit) = reference.peel_to_commit() else {
+                    continue;
+                };
+                branches.insert(
+                    name.to_string(),
+                    NamedRef {
+                        name: name.to_string(),
+                        sha: commit.id().to_string(),
+                        committed_at: commit_time_to_millis(&commit),
+                    },
+                );
+            }
+        }
+        let mut branches: Vec<NamedRef> = branches.into_values().collect();
+        branches.sort_by(|a, b| a.name.cmp(&b.name));
+        branches
+    }
+
+    /// Tags in this clone, oldest first. An annotated tag is dated when it was
+    /// tagged, a lightweight one by its commit.
+    pub fn tags(&self) -> Vec<NamedRef> {
+        let Ok(references) = self.repo.references_glob("refs/tags/*") else {
+            return Vec::new();
+        };
+        let mut tags: Vec<NamedRef> = references
+            .flatten()
+            .filter_map(|reference| {
+                let name = reference.name()?.strip_prefix("refs/tags/")?.to_string();
+                let commit = reference.peel_to_commit().ok()?;
+                let tagged_at = reference
+                    .peel_to_tag()
+                    .ok()
+                    .and_then(|tag| tag.tagger().map(|sig| sig.when().seconds() * 1000));
+                Some(NamedRef {
+                    name,
+                    sha: commit.id().to_string(),
+                    committed_at: tagged_at.unwrap_or_else(|| commit_time_to_millis(&commit)),
+                })
+            })
+            .collect();
+        tags.sort_by(|a, b| {
+            a.committed_at
+                .cmp(&b.committed_at)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        tags
+    }
+
+    /// Write the files under the repository-relative `subdir` as committed at
+    /// `rev` into `dest`, returning how many were written. Submodules and
+    /// symlinks are skipped.
+    pub fn export_tree(&self, rev: &str, subdir: &Path, dest: &Path) -> std::io::Result<usize> {
+        let not_found = || std::io::Error::new(std::io::ErrorKind::NotFound, rev.to_string());
+        let commit = self.commit(rev).ok_or_else(not_found)?;
+        let root = commit.tree().map_err(std::io::Error::other)?;
+        let tree = if subdir.as_os_str().is_empty() {
+            root
+        } else {
+            root.get_path(subdir)
+                .and_then(|entry| entry.to_object(&self.repo))
+                .and_then(|object| object.peel_to_tree())
+                .map_err(|_| not_found())?
+        };
+
+        let mut written = 0;
+        let mut failure = None;
+        let walked = tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.filemode() == i32::from(git2::FileMode::Link)
+                || entry.kind() != Some(git2::ObjectType::Blob)
+            {
+                return git2::TreeWalkResult::Ok;
+            }
+            let Some(name) = entry.name() else {
+                return git2::TreeWalkResult::Ok;
+            };
+            let path = dest.join(dir).join(name);
+            let result = entry
+                .to_object(&self.repo)
+                .and_then(|object| object.peel_to_blob())
+                .map_err(std::io::Error::other)
+                .and_then(|blob| {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&path, blob.content())
+                });
+            match result {
+                Ok(()) => {
+                    written += 1;
+                    git2::TreeWalkResult::Ok
+                }
+                Err(err) => {
+                    failure = Some(err);
+                    git2::TreeWalkResult::Abort
+                }
+            }
+        });
+        if let Some(err) = failure {
+            return Err(err);
+        }
+        walked.map_err(std::io::Error::other)?;
+        Ok(written)
+    }
+
+    /// The committed contents of each of `paths` along HEAD's first-parent
+    /// history, newest first, one entry per distinct version. A path's history
+    /// ends at the commit that added it; paths HEAD doesn't have map to an empty
+    /// list. Keys are repository-relative.
+    pub fn file_versions(&self, paths: &[PathBuf]) -> HashMap<PathBuf, Vec<Vec<u8>>> {
+        let mut versions: HashMap<PathBuf, Vec<Vec<u8>>> = normalize_paths(&self.workdir, paths)
+            .into_iter()
+            .map(|path| (path, Vec::new()))
+            .collect();
+        let mut pending: HashMap<PathBuf, Option<Oid>> =
+            versions.keys().map(|path| (path.clone(), None)).collect();
+
+        let Ok(mut revwalk) = self.repo.revwalk() else {
+            return versions;
+        };
+        let _ = revwalk.simplify_first_parent();
+        if revwalk.push_head().is_err() {
+            return versions;
+        }
+        for oid in revwalk.flatten() {
+            if pending.is_empty() {
+                break;
+            }
+            let Some(tree) = self.repo.find_commit(oid).ok().and_then(|c| c.tree().ok()) else {
+                continue;
+            };
+            pending.retain(|path, last| {
+                let Some(blob) = tree.get_path(path).ok().map(|entry| entry.id()) else {
+                    return false;
+                };
+                if *last != Some(blob) {
+                    *last = Some(blob);
+                    if let Ok(blob) = self.repo.find_blob(blob) {
+                        if let Some(list) = versions.get_mut(path) {
+                            list.push(blob.content().to_vec());
+                        }
+                    }
+                }
+                true
+            });
+        }
+        versions
+    }
+
+    /// Whether `sha` names a commit object present in this clone. Unlike
+    /// [`GitRepository::has_commit`] it never resolves branch names.
+    pub fn contains_commit(&self, sha: &str) -> bool {
+        Oid::from_str(sha)
+            .ok()
+            .filter(|_| sha.len() == 40)
+            .is_some_and(|oid| self.repo.find_commit(oid).is_ok())
+    }
+}
+
+/// A file changed between two commits, described with the status vocabulary
+/// forges use for pull request files (`added`, `modified`, `removed`,
+/// `renamed`).
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    pub status: &'static str,
+    pub previous_path: Option<String>,
+}
+
+/// The head commit of a locally fetched pull request.
+#[derive(Debug, Clone)]
+pub struct LocalPullHead {
+    pub number: u64,
+    pub sha: String,
+    /// First line of the head commit's message.
+    pub summary: String,
+    pub author: GitAuthor,
+    /// Head commit time, in milliseconds.
+    pub committed_at: i64,
+}
+
+/// A branch or tag and the commit it points at.
+#[derive(Debug, Clone)]
+pub struct NamedRef {
+    pub name: String,
+    pub sha: String,
+    /// When the commit was made (or an annotated tag created), in milliseconds.
+    pub committed_at: i64,
 }
 
 pub fn open_git_repository(path: &Path) -> Option<GitRepository> {
@@ -114,6 +461,8 @@ pub struct GitAuthor {
 struct PathTimes {
     addition: Option<i64>,
     last_change: Option<i64>,
+    /// SHA of the commit behind `last_change`.
+    last_change_commit: Option<String>,
     /// Author of the commit that added this path (only set on an
     /// Added/Renamed/Copied delta, never a plain modification).
     addition_author: Option<GitAuthor>,
@@ -155,6 +504,17 @@ impl GitTimestampCache {
             .max()
     }
 
+    /// The commit behind [`latest_change`], the most recent change among
+    /// `paths`.
+    pub fn latest_change_commit(&self, paths: &[PathBuf]) -> Option<&str> {
+        paths
+            .iter()
+            .filter_map(|path| self.times.get(path))
+            .filter(|times| times.last_change.is_some())
+            .max_by_key(|times| times.last_change)
+            .and_then(|times| times.last_change_commit.as_deref())
+    }
+
     /// The author of the commit that added the spec, tied to the same addition
     /// as [`latest_addition`] (the most recent addition among `paths`) so the
     /// credited author and the displayed "Created" date come from one commit.
@@ -171,10 +531,19 @@ impl GitTimestampCache {
     }
 }
 
+/// A commit's author signature, with the repository's `.mailmap` applied so an
+/// author's old names and addresses resolve to their canonical identity.
+fn mapped_author(commit: &git2::Commit, mailmap: Option<&Mailmap>) -> Signature<'static> {
+    mailmap
+        .and_then(|mailmap| commit.author_with_mailmap(mailmap).ok())
+        .unwrap_or_else(|| commit.author().to_owned())
+}
+
 /// Extract the git *author* identity from a commit (name, email, and the
-/// commit's own SHA). `None` when the signature carries neither name nor email.
-fn commit_author(commit: &git2::Commit) -> Option<GitAuthor> {
-    let sig = commit.author();
+/// commit's own SHA), after `.mailmap`. `None` when the signature carries
+/// neither name nor email.
+fn commit_author(commit: &git2::Commit, mailmap: Option<&Mailmap>) -> Option<GitAuthor> {
+    let sig = mapped_author(commit, mailmap);
     let name = sig.name().unwrap_or("").trim().to_string();
     let email = sig.email().unwrap_or("").trim().to_string();
     if name.is_empty() && email.is_empty() {
@@ -206,6 +575,7 @@ fn build_cache(repo: &GitRepository, paths: &[PathBuf]) -> GitTimestampCache {
     let mut pending_additions: HashSet<PathBuf> = rel_paths.iter().cloned().collect();
     let mut pending_changes: HashSet<PathBuf> = rel_paths.iter().cloned().collect();
 
+    let mailmap = repo.repo.mailmap().ok();
     let mut revwalk = match repo.repo.revwalk() {
         Ok(walk) => walk,
         Err(_) => return GitTimestampCache { times },
@@ -251,6 +621,7 @@ fn build_cache(repo: &GitRepository, paths: &[PathBuf]) -> GitTimestampCache {
                     if let Some(entry) = times.get_mut(path) {
                         if entry.last_change.is_none() {
                             entry.last_change = Some(time);
+                            entry.last_change_commit = Some(oid.to_string());
                             updated.last_change = true;
                         }
                     }
@@ -262,7 +633,7 @@ fn build_cache(repo: &GitRepository, paths: &[PathBuf]) -> GitTimestampCache {
                     if let Some(entry) = times.get_mut(path) {
                         if entry.addition.is_none() {
                             entry.addition = Some(time);
-                            entry.addition_author = commit_author(&commit);
+                            entry.addition_author = commit_author(&commit, mailmap.as_ref());
                             updated.addition = true;
                         }
                     }
@@ -308,6 +679,7 @@ fn build_cache_since(repo: &GitRepository, base: &str, paths: &[PathBuf]) -> Git
 
     let mut pending: HashSet<PathBuf> = rel_paths.iter().cloned().collect();
 
+    let mailmap = repo.repo.mailmap().ok();
     let mut revwalk = match repo.repo.revwalk() {
         Ok(walk) => walk,
         Err(_) => return GitTimestampCache { times },
@@ -343,12 +715,13 @@ fn build_cache_since(repo: &GitRepository, base: &str, paths: &[PathBuf]) -> Git
                 if let Some(entry) = times.get_mut(path) {
                     entry.addition = Some(time);
                     entry.last_change = Some(time);
+                    entry.last_change_commit = Some(oid.to_string());
                     // Only credit an author when the file is genuinely (re)added
                     // in this range. A plain modification of an existing spec
                     // leaves the author unset so the producer sends none and the
                     // server keeps the originally-detected initial committer.
                     if matches!(status, Delta::Added | Delta::Renamed | Delta::Copied) {
-                        entry.addition_author = commit_author(&commit);
+                        entry.addition_author = commit_author(&commit, mailmap.as_ref());
                     }
                 }
                 pending.remove(path);
@@ -434,6 +807,7 @@ mod tests {
         let bob = Signature::new("Bob", "bob@example.com", &Time::new(2_000, 0)).unwrap();
         fs::write(dir.join("spec.md"), b"# One\n\nmore\n").unwrap();
         commit_all(&repo, &bob, "edit spec");
+        let edit = repo.head().unwrap().peel_to_commit().unwrap().id();
         drop(repo);
 
         let git = open_git_repository(&dir).unwrap();
@@ -442,6 +816,10 @@ mod tests {
         let author = cache.addition_author(&paths).expect("addition author");
         assert_eq!(author.name, "Alice");
         assert_eq!(author.email, "alice@example.com");
+        assert_eq!(
+            cache.latest_change_commit(&paths),
+            Some(edit.to_string().as_str())
+        );
         let _ = fs::remove_dir_all(&dir);
     }
 
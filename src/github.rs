@@ -1,54 +1,193 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Utc};
-use reqwest::blocking::{Client, Response};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use chrono::{TimeZone, Utc};
+use reqwest::blocking::Client;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH, RETRY_AFTER, USER_AGENT,
+};
+use reqwest::{Method, StatusCode, Url};
 use serde::Deserialize;
 
+use crate::forge::{
+    find_marked_comment, parse_timestamp, CommitIdentity, Forge, ForgeKind, PullComment, PullFile,
+    PullRequest, PullReview, ReviewState,
+};
+use crate::http_cache::{is_commit_sha, HttpCache};
+
+/// Longest the client sleeps for a rate limit to lift before giving up.
+const DEFAULT_MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+/// Secondary rate limits are retried this many times before giving up.
+const SECONDARY_RATE_LIMIT_RETRIES: u32 = 3;
+/// Warn once when fewer requests than this remain in the primary quota.
+const LOW_RATE_LIMIT_WARNING: u64 = 100;
+
 #[derive(Clone, Debug)]
 pub struct GithubRepo {
     pub owner: String,
     pub name: String,
 }
 
-#[derive(Clone, Debug)]
-pub struct GithubPull {
-    pub number: u64,
-    pub draft: bool,
-    pub head_sha: String,
-    pub head_ref: String,
-    pub created_at: i64,
-    pub updated_at: i64,
-    pub author: Option<String>,
-    /// Avatar of the PR author, if GitHub returned one.
-    pub avatar_url: Option<String>,
-    pub title: String,
-    pub html_url: String,
+/// Where a GitHub instance serves its web pages and REST API: github.com, or
+/// a GitHub Enterprise Server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GithubHost {
+    web_url: String,
+    api_url: String,
 }
 
-/// The GitHub identity behind a commit: the git author email recorded in the
-/// commit object, plus the GitHub account that email is linked to (if any),
-/// with its avatar and profile URL. Used to credit and illustrate the initial
-/// committer of a spec.
-#[derive(Clone, Debug, Default)]
-pub struct CommitIdentity {
-    pub email: Option<String>,
-    pub login: Option<String>,
-    pub avatar_url: Option<String>,
-    pub html_url: Option<String>,
+impl Default for GithubHost {
+    fn default() -> Self {
+        Self {
+            web_url: "https://github.com".to_string(),
+            api_url: "https://api.github.com".to_string(),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct GithubFile {
-    pub filename: String,
-    pub status: String,
-    pub raw_url: Option<String>,
-    pub previous_filename: Option<String>,
+impl GithubHost {
+    /// A host from its web and API base URLs, each defaulting to github.com's.
+    /// An Enterprise Server's API defaults to `<web>/api/v3`.
+    pub fn new(web_url: Option<&str>, api_url: Option<&str>) -> Self {
+        let default = Self::default();
+        let web_url = web_url
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .map_or(default.web_url.clone(), str::to_string);
+        let api_url = api_url
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                if web_url == default.web_url {
+                    default.api_url
+                } else {
+                    format!("{web_url}/api/v3")
+                }
+            });
+        Self { web_url, api_url }
+    }
+
+    pub fn web_url(&self) -> &str {
+        &self.web_url
+    }
+
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// The web host name, as it appears in clone URLs and noreply emails.
+    pub fn hostname(&self) -> &str {
+        let rest = self
+            .web_url
+            .split_once("://")
+            .map_or(self.web_url.as_str(), |(_, rest)| rest);
+        let authority = rest.split('/').next().unwrap_or(rest);
+        authority.split(':').next().unwrap_or(authority)
+    }
+
+    pub fn repo_url(&self, repo_slug: &str) -> String {
+        format!("{}/{repo_slug}", self.web_url)
+    }
+
+    pub fn pull_url(&self, repo_slug: &str, number: u64) -> String {
+        format!("{}/{repo_slug}/pull/{number}", self.web_url)
+    }
+
+    pub fn profile_url(&self, login: &str) -> String {
+        format!("{}/{login}", self.web_url)
+    }
+
+    pub fn avatar_url(&self, login: &str) -> String {
+        format!("{}/{login}.png?size=160", self.web_url)
+    }
+
+    /// The repository a remote URL or `owner/name` slug points at on this host.
+    pub fn parse_repo(&self, raw: &str) -> Option<GithubRepo> {
+        let cleaned = raw.trim().trim_end_matches(".git");
+        if cleaned.is_empty() {
+            return None;
+        }
+
+        let host = self.hostname();
+        let prefixes = [
+            format!("git@{host}:"),
+            format!("{host}:"),
+            format!("ssh://git@{host}/"),
+            format!("ssh://{host}/"),
+            format!("git://{host}/"),
+        ];
+        let repo_part = if let Some(stripped) = prefixes
+            .iter()
+            .find_map(|prefix| cleaned.strip_prefix(prefix.as_str()))
+        {
+            stripped
+        } else if let Some(stripped) = parse_http_github_repo(cleaned, host) {
+            stripped
+        } else if cleaned.contains('/') && !cleaned.contains(':') {
+            cleaned
+        } else {
+            return None;
+        };
+
+        let mut segments = repo_part.trim_matches('/').split('/');
+        let owner = segments.next()?.trim();
+        let name = segments.next()?.trim();
+        if owner.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(GithubRepo {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
 }
 
 #[derive(Clone)]
 pub struct GithubClient {
     client: Client,
     repo: GithubRepo,
+    host: GithubHost,
+    cache: Option<HttpCache>,
+    max_rate_limit_wait: Duration,
+    warned_low_quota: Arc<AtomicBool>,
+}
+
+/// How a cached response may be reused.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    /// May change; revalidated with `If-None-Match` on every use.
+    Revalidate,
+    /// Names a commit SHA, so it never changes and is reused without asking.
+    Immutable,
+}
+
+/// A response with its body read, so rate-limit handling can inspect it.
+struct Reply {
+    status: StatusCode,
+    etag: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn into_body(self) -> Result<Vec<u8>> {
+        if !self.status.is_success() {
+            let text = String::from_utf8_lossy(&self.body);
+            anyhow::bail!("GitHub API error ({}): {text}", self.status);
+        }
+        Ok(self.body)
+    }
+}
+
+/// Why GitHub refused a request for rate limiting.
+enum RateLimited {
+    /// The hourly quota is spent until `reset` (seconds since the epoch).
+    Primary { reset: i64 },
+    /// Too many requests too quickly; retry after the given delay.
+    Secondary { retry_after: Duration },
 }
 
 impl GithubClient {
@@ -70,49 +209,274 @@ impl GithubClient {
             .build()
             .context("building GitHub client")?;
 
-        Ok(Self { client, repo })
+        Ok(Self {
+            client,
+            repo,
+            host: GithubHost::default(),
+            cache: None,
+            max_rate_limit_wait: DEFAULT_MAX_RATE_LIMIT_WAIT,
+            warned_low_quota: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Keep responses on disk: lists are revalidated with their ETag and
+    /// lookups by commit SHA are reused outright.
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// How long to sleep for a rate limit to lift before failing instead.
+    pub fn with_max_rate_limit_wait(mut self, wait: Duration) -> Self {
+        self.max_rate_limit_wait = wait;
+        self
+    }
+
+    /// Talk to a GitHub Enterprise Server instead of github.com.
+    pub fn with_host(mut self, host: GithubHost) -> Self {
+        self.host = host;
+        self
     }
 
     pub fn repo(&self) -> &GithubRepo {
         &self.repo
     }
 
-    pub fn list_open_pulls(&self) -> Result<Vec<GithubPull>> {
+    pub fn host(&self) -> &GithubHost {
+        &self.host
+    }
+
+    fn api_url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url> {
+        let url = format!(
+            "{}/repos/{}/{}/{}",
+            self.host.api_url,
+            self.repo.owner,
+            self.repo.name,
+            path.trim_start_matches('/')
+        );
+        let parsed = if query.is_empty() {
+            // `parse_with_params` would leave a bare `?` behind.
+            Url::parse(&url)
+        } else {
+            Url::parse_with_params(&url, query)
+        };
+        parsed.with_context(|| format!("invalid GitHub URL {url}"))
+    }
+
+    /// The login the client's token belongs to.
+    fn authenticated_login(&self) -> Result<String> {
+        let url = format!("{}/user", self.host.api_url);
+        let url = Url::parse(&url).with_context(|| format!("invalid GitHub URL {url}"))?;
+        let body = self
+            .request(&url, None)
+            .and_then(Reply::into_body)
+            .context("requesting the authenticated user")?;
+        let user: UserRef = parse_json(&body)?;
+        Ok(user.login)
+    }
+
+    /// GET `url` through the cache.
+    fn fetch(&self, url: Url, freshness: Freshness) -> Result<Vec<u8>> {
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(url.as_str()));
+        if freshness == Freshness::Immutable {
+            if let Some(cached) = cached {
+                return Ok(cached.body);
+            }
+        }
+
+        let etag = cached.as_ref().and_then(|cached| cached.etag.as_deref());
+        let reply = self.request(&url, etag)?;
+        if reply.status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                return Ok(cached.body);
+            }
+        }
+        let etag = reply.etag.clone();
+        let body = reply.into_body()?;
+        if let Some(cache) = &self.cache {
+            match freshness {
+                Freshness::Immutable => cache.put(url.as_str(), None, &body),
+                Freshness::Revalidate if etag.is_some() => {
+                    cache.put(url.as_str(), etag.as_deref(), &body)
+                }
+                Freshness::Revalidate => {}
+            }
+        }
+        Ok(body)
+    }
+
+    /// GET `url` uncached, waiting out rate limits up to
+    /// `max_rate_limit_wait` and failing with an explanation beyond it.
+    fn request(&self, url: &Url, etag: Option<&str>) -> Result<Reply> {
+        self.send(Method::GET, url, etag, None)
+    }
+
+    /// Send a request with an optional JSON body, handling rate limits as
+    /// [`GithubClient::request`] does.
+    fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        etag: Option<&str>,
+        json: Option<&serde_json::Value>,
+    ) -> Result<Reply> {
+        let mut secondary_retries = 0;
+        loop {
+            let mut request = self.client.request(method.clone(), url.clone());
+            if let Some(etag) = etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(json) = json {
+                request = request.json(json);
+            }
+            let response = request
+                .send()
+                .with_context(|| format!("requesting {url}"))?;
+            let headers = response.headers().clone();
+            let reply = Reply {
+                status: response.status(),
+                etag: header_str(&headers, ETAG).map(str::to_string),
+                body: response
+                    .bytes()
+                    .with_context(|| format!("reading response from {url}"))?
+                    .to_vec(),
+            };
+            self.warn_on_low_quota(&headers);
+
+            let wait = match rate_limited(&reply, &headers) {
+                None => return Ok(reply),
+                Some(RateLimited::Primary { reset }) => {
+                    let reset_at = Utc.timestamp_opt(reset, 0).single().map_or_else(
+                        || "an unknown time".to_string(),
+                        |at| at.format("%H:%M:%S UTC").to_string(),
+                    );
+                    let wait = Duration::from_secs(
+                        u64::try_from(reset - Utc::now().timestamp()).unwrap_or(0) + 1,
+                    );
+                    if wait > self.max_rate_limit_wait {
+                        anyhow::bail!(
+                            "GitHub API rate limit exhausted; it resets at {reset_at}. \
+                             Retry then, or use a token with a larger quota."
+                        );
+                    }
+                    eprintln!(
+                        "GitHub API rate limit exhausted; waiting {}s for it to reset at {reset_at}.",
+                        wait.as_secs()
+                    );
+                    wait
+                }
+                Some(RateLimited::Secondary { retry_after }) => {
+                    secondary_retries += 1;
+                    if secondary_retries > SECONDARY_RATE_LIMIT_RETRIES
+                        || retry_after > self.max_rate_limit_wait
+                    {
+                        anyhow::bail!(
+                            "GitHub secondary rate limit hit requesting {url}; \
+                             retry in {}s with fewer concurrent jobs.",
+                            retry_after.as_secs()
+                        );
+                    }
+                    eprintln!(
+                        "GitHub secondary rate limit hit; retrying in {}s.",
+                        retry_after.as_secs()
+                    );
+                    retry_after
+                }
+            };
+            std::thread::sleep(wait);
+        }
+    }
+
+    fn warn_on_low_quota(&self, headers: &HeaderMap) {
+        let remaining = header_str(headers, "x-ratelimit-remaining").and_then(|v| v.parse().ok());
+        if remaining.is_some_and(|remaining: u64| remaining < LOW_RATE_LIMIT_WARNING)
+            && !self.warned_low_quota.swap(true, Ordering::Relaxed)
+        {
+            eprintln!(
+                "Warning: only {} GitHub API request(s) left until the rate limit resets.",
+                remaining.unwrap_or_default()
+            );
+        }
+    }
+}
+
+fn header_str<K: reqwest::header::AsHeaderName>(headers: &HeaderMap, name: K) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Classify a refused request following GitHub's guidance: a `Retry-After`
+/// header means wait that long, an empty quota means wait for its reset, and
+/// any other 429 (or a 403 that says so) is a secondary limit worth a minute.
+fn rate_limited(reply: &Reply, headers: &HeaderMap) -> Option<RateLimited> {
+    if !matches!(
+        reply.status,
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) {
+        return None;
+    }
+    if let Some(seconds) = header_str(headers, RETRY_AFTER).and_then(|v| v.trim().parse().ok()) {
+        return Some(RateLimited::Secondary {
+            retry_after: Duration::from_secs(seconds),
+        });
+    }
+    if header_str(headers, "x-ratelimit-remaining") == Some("0") {
+        let reset = header_str(headers, "x-ratelimit-reset")
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or_else(|| Utc::now().timestamp() + 60);
+        return Some(RateLimited::Primary { reset });
+    }
+    let secondary = reply.status == StatusCode::TOO_MANY_REQUESTS
+        || String::from_utf8_lossy(&reply.body)
+            .to_ascii_lowercase()
+            .contains("secondary rate limit");
+    secondary.then_some(RateLimited::Secondary {
+        retry_after: Duration::from_secs(60),
+    })
+}
+
+impl Forge for GithubClient {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Github
+    }
+
+    fn repo_slug(&self) -> String {
+        format!("{}/{}", self.repo.owner, self.repo.name)
+    }
+
+    fn pull_reference(&self, number: u64) -> String {
+        format!("{}#{number}", self.repo_slug())
+    }
+
+    fn pull_url(&self, number: u64) -> String {
+        self.host.pull_url(&self.repo_slug(), number)
+    }
+
+    fn profile_url(&self, login: &str) -> String {
+        self.host.profile_url(login)
+    }
+
+    fn list_open_pulls(&self) -> Result<Vec<PullRequest>> {
         let mut pulls = Vec::new();
         let mut page = 1u32;
 
         loop {
-            let url = self.api_url("pulls");
-            let response = self
-                .client
-                .get(url)
-                .query(&[
+            let url = self.api_url(
+                "pulls",
+                &[
                     ("state", "open"),
                     ("per_page", "50"),
                     ("page", &page.to_string()),
-                ])
-                .send()
+                ],
+            )?;
+            let body = self
+                .fetch(url, Freshness::Revalidate)
                 .context("requesting open pull requests")?;
-            let page_pulls: Vec<PullResponse> = parse_json(response)?;
+            let page_pulls: Vec<PullResponse> = parse_json(&body)?;
             let count = page_pulls.len();
-            pulls.extend(page_pulls.into_iter().map(|pull| {
-                let (author, avatar_url) = match pull.user {
-                    Some(u) => (Some(u.login), u.avatar_url),
-                    None => (None, None),
-                };
-                GithubPull {
-                    number: pull.number,
-                    draft: pull.draft,
-                    head_sha: pull.head.sha,
-                    head_ref: pull.head.git_ref,
-                    created_at: parse_timestamp(&pull.created_at),
-                    updated_at: parse_timestamp(&pull.updated_at),
-                    author,
-                    avatar_url,
-                    title: pull.title,
-                    html_url: pull.html_url,
-                }
-            }));
+            pulls.extend(page_pulls.into_iter().map(PullResponse::into_pull));
 
             if count < 50 {
                 break;
@@ -123,21 +487,21 @@ impl GithubClient {
         Ok(pulls)
     }
 
-    pub fn list_pull_files(&self, pull_number: u64) -> Result<Vec<GithubFile>> {
+    fn list_pull_files(&self, pull_number: u64) -> Result<Vec<PullFile>> {
         let mut files = Vec::new();
         let mut page = 1u32;
 
         loop {
-            let url = self.api_url(&format!("pulls/{pull_number}/files"));
-            let response = self
-                .client
-                .get(url)
-                .query(&[("per_page", "100"), ("page", &page.to_string())])
-                .send()
+            let url = self.api_url(
+                &format!("pulls/{pull_number}/files"),
+                &[("per_page", "100"), ("page", &page.to_string())],
+            )?;
+            let body = self
+                .fetch(url, Freshness::Revalidate)
                 .with_context(|| format!("requesting files for PR #{pull_number}"))?;
-            let page_files: Vec<FileResponse> = parse_json(response)?;
+            let page_files: Vec<FileResponse> = parse_json(&body)?;
             let count = page_files.len();
-            files.extend(page_files.into_iter().map(|file| GithubFile {
+            files.extend(page_files.into_iter().map(|file| PullFile {
                 filename: file.filename,
                 status: file.status,
                 raw_url: file.raw_url,
@@ -153,18 +517,72 @@ impl GithubClient {
         Ok(files)
     }
 
+    fn list_pull_reviews(&self, pull_number: u64) -> Result<Vec<PullReview>> {
+        let mut reviews = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let url = self.api_url(
+                &format!("pulls/{pull_number}/reviews"),
+                &[("per_page", "100"), ("page", &page.to_string())],
+            )?;
+            let body = self
+                .fetch(url, Freshness::Revalidate)
+                .with_context(|| format!("requesting reviews for PR #{pull_number}"))?;
+            let page_reviews: Vec<ReviewResponse> = parse_json(&body)?;
+            let count = page_reviews.len();
+            // Pending reviews have not been submitted and carry no date.
+            reviews.extend(page_reviews.into_iter().filter_map(|review| {
+                Some(PullReview {
+                    reviewer: review.user?.login,
+                    state: ReviewState::parse(&review.state)?,
+                    submitted_at: parse_timestamp(&review.submitted_at?),
+                })
+            }));
+
+            if count < 100 {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(reviews)
+    }
+
+    /// One page of the most recently updated closed PRs, keeping those that
+    /// were merged rather than abandoned.
+    /// The pull requests GitHub associates with the commit: the one it was
+    /// merged as, or the ones that carried it.
+    fn list_commit_pulls(&self, sha: &str) -> Result<Vec<PullRequest>> {
+        let url = self.api_url(&format!("commits/{sha}/pulls"), &[("per_page", "100")])?;
+        let body = self
+            .fetch(url, Freshness::Revalidate)
+            .with_context(|| format!("requesting pull requests of commit {sha}"))?;
+        let pulls: Vec<PullResponse> = parse_json(&body)?;
+        let mut merged: Vec<PullRequest> = pulls
+            .into_iter()
+            .map(PullResponse::into_pull)
+            .filter(|pull| pull.merged_at.is_some())
+            .collect();
+        merged.sort_by_key(|pull| std::cmp::Reverse(pull.merged_at));
+        Ok(merged)
+    }
+
     /// Resolve a commit to the GitHub account behind its author. Returns the
     /// git author email (always, when present in the commit object) plus the
     /// linked GitHub `login`/`avatar_url`/`html_url` when GitHub could map that
     /// email to an account (the `author` field is null otherwise).
-    pub fn get_commit(&self, sha: &str) -> Result<CommitIdentity> {
-        let url = self.api_url(&format!("commits/{sha}"));
-        let response = self
-            .client
-            .get(url)
-            .send()
+    fn get_commit(&self, sha: &str) -> Result<CommitIdentity> {
+        let url = self.api_url(&format!("commits/{sha}"), &[])?;
+        let freshness = if is_commit_sha(sha) {
+            Freshness::Immutable
+        } else {
+            Freshness::Revalidate
+        };
+        let body = self
+            .fetch(url, freshness)
             .with_context(|| format!("requesting commit {sha}"))?;
-        let parsed: CommitResponse = parse_json(response)?;
+        let parsed: CommitResponse = parse_json(&body)?;
         Ok(CommitIdentity {
             email: parsed.commit.author.and_then(|a| a.email),
             login: parsed.author.as_ref().map(|u| u.login.clone()),
@@ -173,90 +591,110 @@ impl GithubClient {
         })
     }
 
-    pub fn download_bytes(&self, url: &str) -> Result<Vec<u8>> {
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .with_context(|| format!("downloading {url}"))?
-            .error_for_status()
-            .with_context(|| format!("downloading {url}"))?;
-        let bytes = response
-            .bytes()
-            .with_context(|| format!("reading bytes from {url}"))?;
-        Ok(bytes.to_vec())
-    }
-
-    pub fn download_file_at_ref(&self, path: &str, reference: &str) -> Result<Vec<u8>> {
-        let url = self.api_url(&format!("contents/{path}"));
-        let response = self
-            .client
-            .get(url)
-            .query(&[("ref", reference)])
-            .send()
-            .with_context(|| format!("requesting contents for {path} at {reference}"))?
-            .error_for_status()
-            .with_context(|| format!("requesting contents for {path} at {reference}"))?;
+    /// A PR file's `raw_url` embeds the head commit SHA, so it is cached for
+    /// good.
+    fn download_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let parsed = Url::parse(url).with_context(|| format!("invalid download URL {url}"))?;
+        let freshness = if parsed
+            .path_segments()
+            .is_some_and(|mut segments| segments.any(is_commit_sha))
+        {
+            Freshness::Immutable
+        } else {
+            Freshness::Revalidate
+        };
+        self.fetch(parsed, freshness)
+            .with_context(|| format!("downloading {url}"))
+    }
 
-        let content: ContentResponse = response.json().with_context(|| {
+    fn download_file_at_ref(&self, path: &str, reference: &str) -> Result<Vec<u8>> {
+        let url = self.api_url(&format!("contents/{path}"), &[("ref", reference)])?;
+        // The file itself is cached rather than its metadata: a private
+        // repository's download URL carries a short-lived token.
+        let key = format!("{url}#raw");
+        let cache = self.cache.as_ref().filter(|_| is_commit_sha(reference));
+        if let Some(cached) = cache.and_then(|cache| cache.get(&key)) {
+            return Ok(cached.body);
+        }
+
+        let body = self
+            .request(&url, None)
+            .and_then(Reply::into_body)
+            .with_context(|| format!("requesting contents for {path} at {reference}"))?;
+        let content: ContentResponse = serde_json::from_slice(&body).with_context(|| {
             format!("parsing content metadata for {path} at reference {reference}")
         })?;
 
         let Some(download_url) = content.download_url else {
             anyhow::bail!("no download url for {path} at {reference}")
         };
-
-        self.download_bytes(&download_url)
+        let download_url = Url::parse(&download_url)
+            .with_context(|| format!("invalid download URL {download_url}"))?;
+        let bytes = self
+            .request(&download_url, None)
+            .and_then(Reply::into_body)
+            .with_context(|| format!("downloading {download_url}"))?;
+        if let Some(cache) = cache {
+            cache.put(&key, None, &bytes);
+        }
+        Ok(bytes)
     }
 
-    fn api_url(&self, path: &str) -> String {
-        format!(
-            "https://api.github.com/repos/{}/{}/{}",
-            self.repo.owner,
-            self.repo.name,
-            path.trim_start_matches('/')
-        )
+    /// Comments live on the PR's issue; they are listed fresh each time, as
+    /// the one to edit may have been posted moments ago by another job. Only
+    /// the token owner's comments are candidates for editing.
+    fn upsert_pull_comment(&self, number: u64, marker: &str, body: &str) -> Result<String> {
+        let login = self.authenticated_login()?;
+        let mut comments = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let url = self.api_url(
+                &format!("issues/{number}/comments"),
+                &[("per_page", "100"), ("page", &page.to_string())],
+            )?;
+            let body = self
+                .request(&url, None)
+                .and_then(Reply::into_body)
+                .with_context(|| format!("requesting comments on PR #{number}"))?;
+            let page_comments: Vec<CommentResponse> = parse_json(&body)?;
+            let count = page_comments.len();
+            comments.extend(page_comments.into_iter().map(|comment| PullComment {
+                id: comment.id,
+                author: comment.user.map(|user| user.login),
+                body: comment.body,
+            }));
+            if count < 100 {
+                break;
+            }
+            page += 1;
+        }
+
+        let payload = serde_json::json!({ "body": body });
+        let (method, url) = match find_marked_comment(&comments, marker, &login) {
+            Some(id) => (
+                Method::PATCH,
+                self.api_url(&format!("issues/comments/{id}"), &[])?,
+            ),
+            None => (
+                Method::POST,
+                self.api_url(&format!("issues/{number}/comments"), &[])?,
+            ),
+        };
+        let reply = self
+            .send(method, &url, None, Some(&payload))
+            .and_then(Reply::into_body)
+            .with_context(|| format!("commenting on PR #{number}"))?;
+        let comment: CommentResponse = parse_json(&reply)?;
+        Ok(comment.html_url)
     }
 }
 
+/// The github.com repository a remote URL or `owner/name` slug points at.
 pub fn parse_github_repo(raw: &str) -> Option<GithubRepo> {
-    let cleaned = raw.trim().trim_end_matches(".git");
-    if cleaned.is_empty() {
-        return None;
-    }
-
-    let repo_part = if let Some(stripped) = cleaned.strip_prefix("git@github.com:") {
-        stripped
-    } else if let Some(stripped) = cleaned.strip_prefix("github.com:") {
-        stripped
-    } else if let Some(stripped) = cleaned.strip_prefix("ssh://git@github.com/") {
-        stripped
-    } else if let Some(stripped) = cleaned.strip_prefix("ssh://github.com/") {
-        stripped
-    } else if let Some(stripped) = cleaned.strip_prefix("git://github.com/") {
-        stripped
-    } else if let Some(stripped) = parse_http_github_repo(cleaned) {
-        stripped
-    } else if cleaned.contains('/') && !cleaned.contains(':') {
-        cleaned
-    } else {
-        return None;
-    };
-
-    let mut segments = repo_part.trim_matches('/').split('/');
-    let owner = segments.next()?.trim();
-    let name = segments.next()?.trim();
-    if owner.is_empty() || name.is_empty() {
-        return None;
-    }
-
-    Some(GithubRepo {
-        owner: owner.to_string(),
-        name: name.to_string(),
-    })
+    GithubHost::default().parse_repo(raw)
 }
 
-fn parse_http_github_repo(cleaned: &str) -> Option<&str> {
+fn parse_http_github_repo<'a>(cleaned: &'a str, expected_host: &str) -> Option<&'a str> {
     let rest = cleaned
         .strip_prefix("https://")
         .or_else(|| cleaned.strip_prefix("http://"))?;
@@ -264,7 +702,7 @@ fn parse_http_github_repo(cleaned: &str) -> Option<&str> {
     let (authority, path) = rest.split_at(slash);
     let host_port = authority.rsplit('@').next().unwrap_or(authority);
     let host = host_port.split(':').next().unwrap_or(host_port);
-    if host != "github.com" {
+    if !host.eq_ignore_ascii_case(expected_host) {
         return None;
     }
     let path = &path[1..];
@@ -274,15 +712,8 @@ fn parse_http_github_repo(cleaned: &str) -> Option<&str> {
     Some(path)
 }
 
-fn parse_json<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T> {
-    let status = response.status();
-    if !status.is_success() {
-        let text = response.text().unwrap_or_default();
-        anyhow::bail!("GitHub API error ({status}): {text}");
-    }
-    response
-        .json::<T>()
-        .context("parsing GitHub API response body")
+fn parse_json<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T> {
+    serde_json::from_slice(body).context("parsing GitHub API response body")
 }
 
 #[derive(Debug, Deserialize)]
@@ -298,6 +729,59 @@ struct PullResponse {
     title: String,
     #[serde(default)]
     html_url: String,
+    #[serde(default)]
+    requested_reviewers: Vec<UserRef>,
+    #[serde(default)]
+    labels: Vec<LabelRef>,
+    #[serde(default)]
+    merge_commit_sha: Option<String>,
+    #[serde(default)]
+    merged_at: Option<String>,
+}
+
+impl PullResponse {
+    fn into_pull(self) -> PullRequest {
+        let (author, avatar_url) = match self.user {
+            Some(u) => (Some(u.login), u.avatar_url),
+            None => (None, None),
+        };
+        // Until a PR merges, `merge_commit_sha` names a test merge commit.
+        let merged_at = self.merged_at.as_deref().map(parse_timestamp);
+        PullRequest {
+            number: self.number,
+            draft: self.draft,
+            head_sha: self.head.sha,
+            head_ref: self.head.git_ref,
+            created_at: parse_timestamp(&self.created_at),
+            updated_at: parse_timestamp(&self.updated_at),
+            author,
+            avatar_url,
+            title: self.title,
+            html_url: self.html_url,
+            requested_reviewers: self
+                .requested_reviewers
+                .into_iter()
+                .map(|user| user.login)
+                .collect(),
+            labels: self.labels.into_iter().map(|label| label.name).collect(),
+            merge_commit_sha: self.merge_commit_sha.filter(|_| merged_at.is_some()),
+            merged_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelRef {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewResponse {
+    #[serde(default)]
+    user: Option<UserRef>,
+    state: String,
+    #[serde(default)]
+    submitted_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -315,6 +799,17 @@ struct FileResponse {
     previous_filename: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CommentResponse {
+    id: u64,
+    #[serde(default)]
+    user: Option<UserRef>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    html_url: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ContentResponse {
     download_url: Option<String>,
@@ -358,8 +853,307 @@ struct CommitUser {
     html_url: String,
 }
 
-fn parse_timestamp(raw: &str) -> i64 {
-    DateTime::parse_from_rfc3339(raw)
-        .map(|dt| dt.timestamp_millis())
-        .unwrap_or_else(|_| Utc::now().timestamp_millis())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enterprise_hosts_parse_their_own_remotes() {
+        let github = GithubHost::default();
+        assert_eq!(github.api_url(), "https://api.github.com");
+        assert_eq!(
+            github.pull_url("acme/specs", 4),
+            "https://github.com/acme/specs/pull/4"
+        );
+
+        let ghe = GithubHost::new(Some("https://ghe.example.com/"), None);
+        assert_eq!(ghe.api_url(), "https://ghe.example.com/api/v3");
+        assert_eq!(ghe.hostname(), "ghe.example.com");
+        let custom = GithubHost::new(
+            Some("https://ghe.example.com"),
+            Some("https://api.ghe.test"),
+        );
+        assert_eq!(custom.api_url(), "https://api.ghe.test");
+
+        for remote in [
+            "git@ghe.example.com:acme/specs.git",
+            "ssh://git@ghe.example.com/acme/specs",
+            "https://ghe.example.com/acme/specs.git",
+            "acme/specs",
+        ] {
+            let repo = ghe.parse_repo(remote).expect(remote);
+            assert_eq!((repo.owner.as_str(), repo.name.as_str()), ("acme", "specs"));
+        }
+        assert!(ghe.parse_repo("git@github.com:acme/specs.git").is_none());
+        assert!(parse_github_repo("https://ghe.example.com/acme/specs").is_none());
+        assert!(parse_github_repo("git@github.com:acme/specs.git").is_some());
+    }
+
+    #[test]
+    fn pull_requests_map_onto_the_forge_model() {
+        let (base, log) = crate::forge::stub::serve(vec![
+            (
+                "/repos/acme/specs/pulls?state=open&per_page=50&page=1",
+                serde_json::json!([{
+                    "number": 4,
+                    "draft": false,
+                    "head": { "sha": "abc123", "ref": "rfc-auth" },
+                    "created_at": "2024-03-01T10:00:00Z",
+                    "updated_at": "2024-03-02T10:00:00Z",
+                    "user": { "login": "ada", "avatar_url": "https://a.test/ada.png" },
+                    "title": "Auth RFC",
+                    "html_url": "https://github.com/acme/specs/pull/4"
+                }])
+                .to_string(),
+            ),
+            (
+                "/repos/acme/specs/pulls/4/files?per_page=100&page=1",
+                serde_json::json!([{
+                    "filename": "specs/new.md",
+                    "status": "renamed",
+                    "raw_url": "https://raw.test/new.md",
+                    "previous_filename": "specs/old.md"
+                }])
+                .to_string(),
+            ),
+        ]);
+        let repo = GithubRepo {
+            owner: "acme".to_string(),
+            name: "specs".to_string(),
+        };
+        let client = GithubClient::new(repo, "secret")
+            .unwrap()
+            .with_host(GithubHost::new(Some("https://ghe.test"), Some(&base)));
+        let forge: &dyn Forge = &client;
+
+        assert_eq!(forge.pull_reference(4), "acme/specs#4");
+        assert_eq!(forge.pull_url(4), "https://ghe.test/acme/specs/pull/4");
+        let pulls = forge.list_open_pulls().unwrap();
+        assert_eq!(pulls.len(), 1);
+        assert_eq!(
+            (pulls[0].number, pulls[0].head_ref.as_str()),
+            (4, "rfc-auth")
+        );
+        let files = forge.list_pull_files(4).unwrap();
+        assert_eq!(files[0].previous_filename.as_deref(), Some("specs/old.md"));
+        assert!(log
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|line| line.ends_with("Bearer secret")));
+    }
+
+    #[test]
+    fn merged_pulls_carry_reviews_labels_and_merge_commits() {
+        let pull = |number: u64, merged_at: Option<&str>| {
+            serde_json::json!({
+                "number": number,
+                "draft": false,
+                "head": { "sha": "abc123", "ref": "rfc-auth" },
+                "created_at": "2024-03-01T10:00:00Z",
+                "updated_at": "2024-03-05T10:00:00Z",
+                "requested_reviewers": [{ "login": "linus" }],
+                "labels": [{ "name": "rfc" }],
+                "merge_commit_sha": "feedface",
+                "merged_at": merged_at
+            })
+        };
+        let review = |login: &str, state: &str, at: Option<&str>| serde_json::json!({ "user": { "login": login }, "state": state, "submitted_at": at });
+        let (base, _log) = crate::forge::stub::serve(vec![
+            (
+                "/repos/acme/specs/commits/feedface/pulls?per_page=100",
+                serde_json::json!([
+                    pull(3, Some("2024-03-02T10:00:00Z")),
+                    pull(5, None),
+                    pull(4, Some("2024-03-04T10:00:00Z")),
+                ])
+                .to_string(),
+            ),
+            (
+                "/repos/acme/specs/pulls/4/reviews?per_page=100&page=1",
+                serde_json::json!([
+                    review("ada", "CHANGES_REQUESTED", Some("2024-03-02T10:00:00Z")),
+                    review("grace", "APPROVED", Some("2024-03-02T11:00:00Z")),
+                    review("ada", "APPROVED", Some("2024-03-03T10:00:00Z")),
+                    review("ada", "COMMENTED", Some("2024-03-03T11:00:00Z")),
+                    review("grace", "DISMISSED", Some("2024-03-03T12:00:00Z")),
+                    review("linus", "COMMENTED", Some("2024-03-03T13:00:00Z")),
+                    review("hopper", "PENDING", None),
+                ])
+                .to_string(),
+            ),
+        ]);
+        let client = stub_client(&base);
+        let forge: &dyn Forge = &client;
+
+        let merged = forge.list_commit_pulls("feedface").unwrap();
+        let numbers: Vec<u64> = merged.iter().map(|pull| pull.number).collect();
+        assert_eq!(numbers, [4, 3]);
+        assert_eq!(merged[0].merge_commit_sha.as_deref(), Some("feedface"));
+        assert_eq!(merged[0].labels, ["rfc"]);
+        assert_eq!(merged[0].requested_reviewers, ["linus"]);
+
+        let reviews = forge.list_pull_reviews(4).unwrap();
+        assert_eq!(reviews.len(), 6);
+        let standing: Vec<(String, ReviewState)> = crate::forge::standing_reviews(&reviews)
+            .into_iter()
+            .map(|review| (review.reviewer, review.state))
+            .collect();
+        assert_eq!(
+            standing,
+            [
+                ("ada".to_string(), ReviewState::Approved),
+                ("linus".to_string(), ReviewState::Commented),
+            ]
+        );
+        assert_eq!(crate::forge::approvers(&reviews), ["ada"]);
+    }
+
+    fn stub_client(base: &str) -> GithubClient {
+        let repo = GithubRepo {
+            owner: "acme".to_string(),
+            name: "specs".to_string(),
+        };
+        GithubClient::new(repo, "secret")
+            .unwrap()
+            .with_host(GithubHost::new(Some("https://ghe.test"), Some(base)))
+    }
+
+    #[test]
+    fn cached_lists_are_revalidated_and_commits_reused() {
+        use crate::forge::stub::StubReply;
+
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let (base, log) = crate::forge::stub::serve(vec![
+            (
+                "/repos/acme/specs/pulls?state=open&per_page=50&page=1",
+                StubReply::from("[]".to_string()).header("etag", "\"v1\""),
+            ),
+            (
+                "/repos/acme/specs/commits/0123456789abcdef0123456789abcdef01234567",
+                StubReply::from(
+                    serde_json::json!({
+                        "commit": { "author": { "email": "ada@example.com" } },
+                        "author": { "login": "ada", "avatar_url": "https://a.test/ada.png" }
+                    })
+                    .to_string(),
+                ),
+            ),
+        ]);
+        let dir = std::env::temp_dir().join(format!(
+            "dossiers-github-cache-{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let cache = HttpCache::new(&dir);
+
+        for _ in 0..2 {
+            let client = stub_client(&base).with_cache(cache.clone());
+            assert!(client.list_open_pulls().unwrap().is_empty());
+            let identity = client.get_commit(sha).unwrap();
+            assert_eq!(identity.login.as_deref(), Some("ada"));
+        }
+
+        let log = log.lock().unwrap();
+        let pulls: Vec<&String> = log.iter().filter(|l| l.contains("/pulls")).collect();
+        assert_eq!(pulls.len(), 2);
+        assert!(!pulls[0].contains("if-none-match"));
+        assert!(pulls[1].ends_with("if-none-match=\"v1\""));
+        assert_eq!(log.iter().filter(|l| l.contains("/commits/")).count(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn pull_comments_are_posted_once_then_edited() {
+        use crate::forge::stub::StubReply;
+
+        let marker = "<!-- dossiers:pr-summary -->";
+        let comment = |id: u64, login: &str, body: &str| serde_json::json!({ "id": id, "user": { "login": login }, "body": body, "html_url": "" });
+        // A reviewer quoting the summary carries the marker too, but is not
+        // ours to edit.
+        let quoted = comment(3, "ada", &format!("> {marker}\n> old\n\nWhy?"));
+        let (base, log) = crate::forge::stub::serve(vec![
+            (
+                "/user",
+                StubReply::from(r#"{"login":"dossiers-bot"}"#.to_string()),
+            ),
+            (
+                "/repos/acme/specs/issues/4/comments?per_page=100&page=1",
+                StubReply::from(
+                    serde_json::json!([comment(1, "ada", "LGTM"), quoted]).to_string(),
+                ),
+            ),
+            (
+                "/repos/acme/specs/issues/4/comments?per_page=100&page=1",
+                StubReply::from(
+                    serde_json::json!([
+                        comment(1, "ada", "LGTM"),
+                        quoted,
+                        comment(9, "Dossiers-Bot", &format!("{marker}\nold")),
+                    ])
+                    .to_string(),
+                ),
+            ),
+            (
+                "POST /repos/acme/specs/issues/4/comments",
+                StubReply::status(
+                    201,
+                    r#"{"id":9,"body":"","html_url":"https://ghe.test/acme/specs/pull/4#issuecomment-9"}"#,
+                ),
+            ),
+            (
+                "PATCH /repos/acme/specs/issues/comments/9",
+                StubReply::from(
+                    r#"{"id":9,"body":"","html_url":"https://ghe.test/acme/specs/pull/4#issuecomment-9"}"#
+                        .to_string(),
+                ),
+            ),
+        ]);
+        let client = stub_client(&base);
+
+        for text in ["first", "second"] {
+            let url = client
+                .upsert_pull_comment(4, marker, &format!("{marker}\n{text}"))
+                .unwrap();
+            assert!(url.ends_with("#issuecomment-9"));
+        }
+        let log = log.lock().unwrap();
+        let writes: Vec<&String> = log.iter().filter(|line| !line.starts_with('/')).collect();
+        assert_eq!(writes.len(), 2);
+        assert!(writes[0].starts_with("POST /repos/acme/specs/issues/4/comments"));
+        assert!(writes[0].contains("first"));
+        assert!(writes[1].starts_with("PATCH /repos/acme/specs/issues/comments/9"));
+        assert!(writes[1].contains("second"));
+    }
+
+    #[test]
+    fn rate_limits_are_waited_out_or_reported() {
+        use crate::forge::stub::StubReply;
+
+        let reset = (Utc::now().timestamp() + 3600).to_string();
+        let (base, _) = crate::forge::stub::serve(vec![
+            (
+                "/repos/acme/specs/pulls?state=open&per_page=50&page=1",
+                StubReply::status(
+                    403,
+                    r#"{"message":"You have exceeded a secondary rate limit"}"#,
+                )
+                .header("retry-after", "0"),
+            ),
+            (
+                "/repos/acme/specs/pulls?state=open&per_page=50&page=1",
+                StubReply::from("[]".to_string()),
+            ),
+            (
+                "/repos/acme/specs/pulls/4/files?per_page=100&page=1",
+                StubReply::status(403, r#"{"message":"API rate limit exceeded"}"#)
+                    .header("x-ratelimit-remaining", "0")
+                    .header("x-ratelimit-reset", reset),
+            ),
+        ]);
+        let client = stub_client(&base).with_max_rate_limit_wait(Duration::from_secs(5));
+
+        assert!(client.list_open_pulls().unwrap().is_empty());
+        let err = format!("{:#}", client.list_pull_files(4).unwrap_err());
+        assert!(err.contains("rate limit exhausted; it resets at"), "{err}");
+    }
 }
@@ -7,16 +7,28 @@
 //! colors with no re-render, and degrades to plain (uncolored) code when CSS or
 //! JS is unavailable.
 //!
-//! The `SyntaxSet` and generated CSS are built once, lazily. Diagram languages
-//! (mermaid, svgbob) are deliberately *not* highlightable — they are rendered
-//! separately (svgbob to SVG, mermaid client-side), so their code blocks must
-//! not pass through here (see `render_markdown`).
+//! The bundled `SyntaxSet` and default CSS are built once, lazily. A project's
+//! `[highlight]` section swaps in other themes (bundled or `.tmTheme` files)
+//! and extra `.sublime-syntax` grammars; a [`Highlighter`] built from it owns
+//! the extended set and its own stylesheet. Diagram languages (mermaid,
+//! svgbob) are deliberately *not* highlightable — they are rendered separately
+//! (svgbob to SVG, mermaid client-side), so their code blocks must not pass
+//! through here (see `render_markdown`).
+//!
+//! Fence attributes after the language token control per-block line features:
+//! ```` ```rust {1,3-5} showLineNumbers ```` marks lines 1 and 3–5 and numbers
+//! every line (see [`FenceOptions`]).
+
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
 
 use lazy_static::lazy_static;
 use regex::Regex;
-use syntect::highlighting::ThemeSet;
+use serde_json::Value as JsonValue;
+use syntect::highlighting::{Theme, ThemeSet};
 use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
-use syntect::parsing::SyntaxSet;
+use syntect::parsing::{SyntaxDefinition, SyntaxSet, SyntaxSetBuilder};
 use syntect::util::LinesWithEndings;
 
 /// Prefix on every generated class, so highlight styles can never collide with
@@ -29,7 +41,198 @@ const LIGHT_THEME: &str = "InspiredGitHub";
 
 lazy_static! {
     static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
-    static ref HIGHLIGHT_CSS: String = build_css();
+    static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+    static ref HIGHLIGHT_CSS: String = build_css(
+        THEME_SET.themes.get(DARK_THEME),
+        THEME_SET.themes.get(LIGHT_THEME)
+    );
+}
+
+/// The `[highlight]` section of `dossiers.toml`.
+///
+/// ```toml
+/// [highlight]
+/// dark_theme = "base16-eighties.dark"   # a bundled theme name...
+/// light_theme = "themes/Paper.tmTheme"  # ...or a .tmTheme path
+/// syntaxes = ["grammars"]               # .sublime-syntax files or folders
+/// line_numbers = true                   # number every block by default
+/// ```
+///
+/// `theme` sets both variants at once. Paths are relative to the project root.
+#[derive(Debug, Clone, Default)]
+pub struct HighlightConfig {
+    pub dark_theme: Option<String>,
+    pub light_theme: Option<String>,
+    pub syntaxes: Vec<String>,
+    pub line_numbers: bool,
+}
+
+impl HighlightConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let string = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| value.get(*key).and_then(JsonValue::as_str))
+                .map(|s| s.trim().to_string())
+                .find(|s| !s.is_empty())
+        };
+        let theme = string(&["theme"]);
+        let syntaxes = match value.get("syntaxes") {
+            Some(JsonValue::String(path)) => vec![path.trim().to_string()],
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            dark_theme: string(&["dark_theme", "darkTheme"]).or_else(|| theme.clone()),
+            light_theme: string(&["light_theme", "lightTheme"]).or(theme),
+            syntaxes,
+            line_numbers: value
+                .get("line_numbers")
+                .or_else(|| value.get("lineNumbers"))
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Highlighting as configured for a project. The default uses syntect's
+/// bundled syntaxes and the built-in theme pair.
+#[derive(Debug, Default)]
+pub struct Highlighter {
+    /// The bundled set extended with project grammars; `None` for the bundled
+    /// set alone.
+    syntaxes: Option<SyntaxSet>,
+    /// Stylesheet for configured themes; `None` for the default pair.
+    css: Option<String>,
+    line_numbers: bool,
+}
+
+impl Highlighter {
+    /// Load the configured themes and grammars. Anything that fails to load is
+    /// reported in the returned problems and left at its default, so a bad
+    /// theme path never stops a build.
+    pub fn from_config(config: &HighlightConfig, project_root: &Path) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+
+        let mut load = |spec: Option<&String>, default: &str| -> Option<Theme> {
+            let spec = spec?;
+            match load_theme(spec, project_root) {
+                Ok(theme) => Some(theme),
+                Err(err) => {
+                    problems.push(format!("highlight theme '{spec}': {err}; using {default}"));
+                    None
+                }
+            }
+        };
+        let dark = load(config.dark_theme.as_ref(), DARK_THEME);
+        let light = load(config.light_theme.as_ref(), LIGHT_THEME);
+        let css = (dark.is_some() || light.is_some()).then(|| {
+            build_css(
+                dark.as_ref().or_else(|| THEME_SET.themes.get(DARK_THEME)),
+                light.as_ref().or_else(|| THEME_SET.themes.get(LIGHT_THEME)),
+            )
+        });
+
+        let syntaxes = if config.syntaxes.is_empty() {
+            None
+        } else {
+            let mut builder = SYNTAX_SET.clone().into_builder();
+            for entry in &config.syntaxes {
+                if let Err(err) = add_syntaxes(&mut builder, &project_root.join(entry)) {
+                    problems.push(format!("highlight syntaxes '{entry}': {err}"));
+                }
+            }
+            Some(builder.build())
+        };
+
+        let highlighter = Self {
+            syntaxes,
+            css,
+            line_numbers: config.line_numbers,
+        };
+        (highlighter, problems)
+    }
+
+    fn syntax_set(&self) -> &SyntaxSet {
+        self.syntaxes.as_ref().unwrap_or(&SYNTAX_SET)
+    }
+
+    /// Whether a fence's language token can be highlighted: non-empty, not a
+    /// diagram language, and backed by a known syntax.
+    pub fn is_highlightable(&self, token: &str) -> bool {
+        !token.is_empty()
+            && !is_diagram_lang(token)
+            && self.syntax_set().find_syntax_by_token(token).is_some()
+    }
+
+    /// Render `code` as class-annotated `<span>` HTML for the given language
+    /// token.
+    ///
+    /// Returns `None` when the token has no known syntax; callers should then
+    /// fall back to a plain escaped `<pre><code>` block. The returned string is
+    /// the inner content only — callers wrap it in `<pre><code>`.
+    pub fn highlight(&self, token: &str, code: &str) -> Option<String> {
+        let syntaxes = self.syntax_set();
+        let syntax = syntaxes.find_syntax_by_token(token)?;
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+        for line in LinesWithEndings::from(code) {
+            generator
+                .parse_html_for_line_which_includes_newline(line)
+                .ok()?;
+        }
+        Some(generator.finalize())
+    }
+
+    /// The highlight stylesheet, inlined into pages that contain a highlighted
+    /// code block.
+    pub fn css(&self) -> &str {
+        self.css.as_deref().unwrap_or(&HIGHLIGHT_CSS)
+    }
+
+    /// Whether blocks without a line-number attribute are numbered.
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers
+    }
+}
+
+/// A bundled theme by name, or a `.tmTheme` file relative to `project_root`.
+fn load_theme(spec: &str, project_root: &Path) -> Result<Theme, String> {
+    if let Some(theme) = THEME_SET.themes.get(spec) {
+        return Ok(theme.clone());
+    }
+    let path = project_root.join(spec);
+    if !path.is_file() {
+        let mut bundled: Vec<&str> = THEME_SET.themes.keys().map(String::as_str).collect();
+        bundled.sort_unstable();
+        return Err(format!(
+            "not a bundled theme ({}) or a .tmTheme file",
+            bundled.join(", ")
+        ));
+    }
+    ThemeSet::get_theme(&path).map_err(|err| err.to_string())
+}
+
+/// Add one `.sublime-syntax` file, or every one under a folder.
+fn add_syntaxes(builder: &mut SyntaxSetBuilder, path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        return builder
+            .add_from_folder(path, true)
+            .map_err(|err| err.to_string());
+    }
+    let source = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let fallback_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned());
+    let syntax = SyntaxDefinition::load_from_str(&source, true, fallback_name.as_deref())
+        .map_err(|err| err.to_string())?;
+    builder.add(syntax);
+    Ok(())
 }
 
 /// Language tokens rendered separately from raw source; never highlighted.
@@ -37,46 +240,123 @@ fn is_diagram_lang(token: &str) -> bool {
     matches!(token.to_ascii_lowercase().as_str(), "mermaid" | "svgbob")
 }
 
-/// Whether a fence's language token can be highlighted: non-empty, not a
-/// diagram language, and backed by a known syntax.
-pub fn is_highlightable(token: &str) -> bool {
-    !token.is_empty() && !is_diagram_lang(token) && SYNTAX_SET.find_syntax_by_token(token).is_some()
+/// Per-block line options from a fence's info string, after the language
+/// token: `{1,3-5}` marks lines, `showLineNumbers` (or `linenos`) numbers them,
+/// optionally from a start line (`showLineNumbers=10`), and `nolinenos` turns
+/// off a project-wide default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FenceOptions {
+    pub line_numbers: Option<bool>,
+    pub start_line: Option<usize>,
+    pub marked_lines: Vec<RangeInclusive<usize>>,
 }
 
-/// Render `code` as class-annotated `<span>` HTML for the given language token.
-///
-/// Returns `None` when the token has no known syntax; callers should then fall
-/// back to a plain escaped `<pre><code>` block. The returned string is the inner
-/// content only — callers wrap it in `<pre><code>`.
-pub fn highlight(token: &str, code: &str) -> Option<String> {
-    let syntax = SYNTAX_SET.find_syntax_by_token(token)?;
-    let mut generator =
-        ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, CLASS_STYLE);
-    for line in LinesWithEndings::from(code) {
-        generator
-            .parse_html_for_line_which_includes_newline(line)
-            .ok()?;
-    }
-    Some(generator.finalize())
+impl FenceOptions {
+    pub fn parse(info: &str) -> Self {
+        lazy_static! {
+            static ref BRACES: Regex = Regex::new(r"\{([^}]*)\}").unwrap();
+        }
+        let mut options = Self::default();
+
+        for group in BRACES.captures_iter(info) {
+            for item in group[1].split([',', ' ']).map(str::trim) {
+                let range = match item.split_once('-') {
+                    Some((start, end)) => start.trim().parse().ok().zip(end.trim().parse().ok()),
+                    None => item.parse().ok().map(|line| (line, line)),
+                };
+                if let Some((start, end)) = range.filter(|(start, end)| start <= end) {
+                    options.marked_lines.push(start..=end);
+                }
+            }
+        }
+
+        let rest = BRACES.replace_all(info, " ");
+        for word in rest
+            .split(|ch: char| ch.is_whitespace() || ch == ',')
+            .skip(1)
+        {
+            let (key, value) = word.split_once('=').unwrap_or((word, ""));
+            match key {
+                "showLineNumbers" | "linenos" | "line-numbers" => {
+                    options.line_numbers = Some(true);
+                    options.start_line = value.trim_matches('"').parse().ok();
+                }
+                "nolinenos" => options.line_numbers = Some(false),
+                _ => {}
+            }
+        }
+        options
+    }
+
+    fn is_marked(&self, line: usize) -> bool {
+        self.marked_lines.iter().any(|range| range.contains(&line))
+    }
 }
 
-/// The generated highlight stylesheet, inlined into pages that contain a
-/// highlighted code block.
-pub fn highlight_css() -> &'static str {
-    &HIGHLIGHT_CSS
+/// Wrap each line of highlighted (or escaped) code in a
+/// `<span class="hl-line" data-line="N">`, marking `options.marked_lines`.
+/// Scope spans that cross a line break are closed before it and reopened
+/// after, so every line is well-formed on its own.
+pub fn wrap_lines(html: &str, options: &FenceOptions) -> String {
+    lazy_static! {
+        static ref TAG: Regex = Regex::new(r"<span[^>]*>|</span>|\n").unwrap();
+    }
+    let start = options.start_line.unwrap_or(1);
+    let mut out = String::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut line = 0;
+    let begin_line = |out: &mut String, open: &[&str], line: usize| {
+        let number = start + line;
+        let class = if options.is_marked(line + 1) {
+            "hl-line hl-line--marked"
+        } else {
+            "hl-line"
+        };
+        out.push_str(&format!("<span class=\"{class}\" data-line=\"{number}\">"));
+        open.iter().for_each(|tag| out.push_str(tag));
+    };
+
+    begin_line(&mut out, &open, line);
+    let mut last = 0;
+    for token in TAG.find_iter(html) {
+        out.push_str(&html[last..token.start()]);
+        last = token.end();
+        match token.as_str() {
+            "\n" => {
+                out.push_str(&"</span>".repeat(open.len() + 1));
+                out.push('\n');
+                line += 1;
+                if last < html.len() {
+                    begin_line(&mut out, &open, line);
+                }
+            }
+            "</span>" => {
+                open.pop();
+                out.push_str("</span>");
+            }
+            tag => {
+                open.push(tag);
+                out.push_str(tag);
+            }
+        }
+    }
+    out.push_str(&html[last..]);
+    if !html.ends_with('\n') {
+        out.push_str(&"</span>".repeat(open.len() + 1));
+    }
+    out
 }
 
 /// Build the combined light + dark stylesheet, each theme scoped to the matching
 /// `data-theme` value so the site's theme toggle switches colors without a
 /// re-render. Missing themes are skipped rather than panicking.
-fn build_css() -> String {
-    let themes = ThemeSet::load_defaults();
+fn build_css(dark: Option<&Theme>, light: Option<&Theme>) -> String {
     let mut css = String::new();
-    for (key, scope) in [
-        (DARK_THEME, ":root[data-theme='dark'] .doc-content"),
-        (LIGHT_THEME, ":root[data-theme='light'] .doc-content"),
+    for (theme, scope) in [
+        (dark, ":root[data-theme='dark'] .doc-content"),
+        (light, ":root[data-theme='light'] .doc-content"),
     ] {
-        if let Some(theme) = themes.themes.get(key) {
+        if let Some(theme) = theme {
             if let Ok(rules) = css_for_theme_with_class_style(theme, CLASS_STYLE) {
                 css.push_str(&scope_css(&rules, scope));
             }
@@ -124,6 +404,14 @@ fn scope_css(sheet: &str, scope: &str) -> String {
 mod tests {
     use super::*;
 
+    fn is_highlightable(token: &str) -> bool {
+        Highlighter::default().is_highlightable(token)
+    }
+
+    fn highlight(token: &str, code: &str) -> Option<String> {
+        Highlighter::default().highlight(token, code)
+    }
+
     #[test]
     fn known_language_is_highlightable_by_name_and_extension() {
         assert!(is_highlightable("rust"));
@@ -165,7 +453,8 @@ mod tests {
 
     #[test]
     fn css_scopes_both_themes_and_is_valid() {
-        let css = highlight_css();
+        let highlighter = Highlighter::default();
+        let css = highlighter.css();
         assert!(css.contains(":root[data-theme='dark'] .doc-content .hl-"));
         assert!(css.contains(":root[data-theme='light'] .doc-content .hl-"));
         // No stray comment survived scoping, and braces are balanced.
@@ -176,4 +465,91 @@ mod tests {
             "unbalanced braces in generated css"
         );
     }
+
+    #[test]
+    fn config_reads_themes_syntaxes_and_line_numbers() {
+        let config = HighlightConfig::from_json_value(&serde_json::json!({
+            "theme": "base16-eighties.dark",
+            "lightTheme": "InspiredGitHub",
+            "syntaxes": "grammars",
+            "line_numbers": true,
+        }));
+        assert_eq!(config.dark_theme.as_deref(), Some("base16-eighties.dark"));
+        assert_eq!(config.light_theme.as_deref(), Some("InspiredGitHub"));
+        assert_eq!(config.syntaxes, vec!["grammars".to_string()]);
+        assert!(config.line_numbers);
+    }
+
+    #[test]
+    fn configured_theme_changes_css_and_bad_theme_is_reported() {
+        let root = Path::new(".");
+        let config = HighlightConfig {
+            dark_theme: Some("Solarized (dark)".to_string()),
+            light_theme: Some("no-such-theme".to_string()),
+            ..HighlightConfig::default()
+        };
+        let (highlighter, problems) = Highlighter::from_config(&config, root);
+        assert_ne!(highlighter.css(), Highlighter::default().css());
+        assert!(highlighter
+            .css()
+            .contains(":root[data-theme='light'] .doc-content .hl-"));
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("no-such-theme"), "{problems:?}");
+    }
+
+    #[test]
+    fn custom_grammar_is_loaded_from_the_project() {
+        let dir = std::env::temp_dir().join(format!("dossiers-hl-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("widget.sublime-syntax"),
+            "%YAML 1.2\n---\nname: Widget\nfile_extensions: [widget]\nscope: source.widget\n\
+             contexts:\n  main:\n    - match: '\\bwidget\\b'\n      scope: keyword.control.widget\n",
+        )
+        .unwrap();
+        let config = HighlightConfig {
+            syntaxes: vec!["widget.sublime-syntax".to_string(), "missing".to_string()],
+            ..HighlightConfig::default()
+        };
+        let (highlighter, problems) = Highlighter::from_config(&config, &dir);
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!is_highlightable("widget"));
+        assert!(highlighter.is_highlightable("widget"));
+        let html = highlighter.highlight("widget", "widget on\n").unwrap();
+        assert!(html.contains("hl-keyword"), "{html}");
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("missing"), "{problems:?}");
+    }
+
+    #[test]
+    fn fence_options_parse_marked_lines_and_numbering() {
+        let options = FenceOptions::parse("rust {1,3-5} showLineNumbers=10");
+        assert_eq!(options.marked_lines, vec![1..=1, 3..=5]);
+        assert_eq!(options.line_numbers, Some(true));
+        assert_eq!(options.start_line, Some(10));
+
+        assert_eq!(FenceOptions::parse("rust").line_numbers, None);
+        assert_eq!(
+            FenceOptions::parse("rust nolinenos").line_numbers,
+            Some(false)
+        );
+        // Reversed ranges and junk are ignored.
+        assert!(FenceOptions::parse("rust {5-3,x}").marked_lines.is_empty());
+    }
+
+    #[test]
+    fn wrap_lines_balances_spans_across_line_breaks() {
+        let options = FenceOptions {
+            marked_lines: vec![2..=2],
+            ..FenceOptions::default()
+        };
+        let html = wrap_lines("<span class=\"a\">one\ntwo</span>\nthree\n", &options);
+        assert_eq!(
+            html,
+            "<span class=\"hl-line\" data-line=\"1\"><span class=\"a\">one</span></span>\n\
+             <span class=\"hl-line hl-line--marked\" data-line=\"2\"><span class=\"a\">two</span></span>\n\
+             <span class=\"hl-line\" data-line=\"3\">three</span>\n"
+        );
+    }
 }
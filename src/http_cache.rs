@@ -0,0 +1,116 @@
+//! On-disk cache of forge API responses, keyed by URL.
+//!
+//! Each entry is the response body plus the `ETag` it was served with, so the
+//! next request can be sent with `If-None-Match` and answered by a bodiless
+//! `304 Not Modified` (which GitHub does not count against the rate limit).
+//! Lookups that name a commit SHA never change and are served from the cache
+//! without a request at all.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default cache location, relative to the repository root.
+pub const DEFAULT_CACHE_DIR: &str = "target/github-cache";
+
+#[derive(Clone, Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+/// A cached response body and the validator it was served with.
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let (body_path, etag_path) = self.paths(key);
+        let body = fs::read(body_path).ok()?;
+        let etag = fs::read_to_string(etag_path)
+            .ok()
+            .filter(|etag| !etag.is_empty());
+        Some(CachedResponse { etag, body })
+    }
+
+    /// Store a response. Failures only cost a future request, so they are
+    /// reported and otherwise ignored.
+    pub fn put(&self, key: &str, etag: Option<&str>, body: &[u8]) {
+        let (body_path, etag_path) = self.paths(key);
+        let written = create_cache_dir(&self.dir)
+            .and_then(|()| fs::write(&body_path, body))
+            .and_then(|()| match etag {
+                Some(etag) => fs::write(&etag_path, etag),
+                None => match fs::remove_file(&etag_path) {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+                    _ => Ok(()),
+                },
+            });
+        if let Err(err) = written {
+            eprintln!(
+                "Warning: failed to cache API response at {}: {err}",
+                body_path.display()
+            );
+        }
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let digest = format!("{:x}", md5::compute(key.as_bytes()));
+        (
+            self.dir.join(format!("{digest}.body")),
+            self.dir.join(format!("{digest}.etag")),
+        )
+    }
+}
+
+/// Create a cache directory holding a `.gitignore` that ignores everything
+/// in it, so a cache under the repository is never committed by accident.
+pub fn create_cache_dir(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        fs::write(ignore, "*\n")?;
+    }
+    Ok(())
+}
+
+/// Whether `raw` is a full 40-character commit SHA, the only kind of
+/// reference whose content can be cached forever.
+pub fn is_commit_sha(raw: &str) -> bool {
+    raw.len() == 40 && raw.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_directories_ignore_their_own_contents() {
+        let dir = std::env::temp_dir().join(format!("dossiers-http-cache-{}", std::process::id()));
+        let cache = HttpCache::new(dir.join("target/github-cache"));
+        cache.put(
+            "https://api.github.com/repos/o/r/pulls",
+            Some("\"abc\""),
+            b"[]",
+        );
+        let ignore = fs::read_to_string(cache.dir().join(".gitignore"));
+        let cached = cache.get("https://api.github.com/repos/o/r/pulls");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(ignore.unwrap(), "*\n");
+        let cached = cached.expect("cached response");
+        assert_eq!(
+            (cached.etag.as_deref(), cached.body),
+            (Some("\"abc\""), b"[]".to_vec())
+        );
+    }
+}
@@ -1,4 +1,10 @@
 pub mod authors;
 pub mod bundle;
+pub mod codeowners;
+pub mod dev_api;
+pub mod forge;
 pub mod git_utils;
 pub mod github;
+pub mod gitlab;
+pub mod http_cache;
+pub mod local_forge;
@@ -0,0 +1,285 @@
+//! Pull request revisions read from git objects rather than a forge's API.
+//!
+//! CI clones often already hold the heads of open pull requests, fetched as
+//! `refs/pull/*/head` or as the PR branches themselves. [`LocalGitForge`]
+//! wraps a real forge and answers every file question from the local
+//! repository whenever it holds a request's head commit, so the forge is only
+//! asked for the list of open requests (titles, drafts, authors). In offline
+//! mode it never contacts the forge at all: the open requests are the fetched
+//! heads, and the forge only supplies web links.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+
+use crate::forge::{CommitIdentity, Forge, ForgeKind, PullFile, PullRequest, PullReview};
+use crate::git_utils::GitRepository;
+
+pub struct LocalGitForge {
+    repo: Mutex<GitRepository>,
+    /// Commit pull requests are diffed against (via their merge base).
+    base: String,
+    forge: Box<dyn Forge>,
+    offline: bool,
+    /// Head commit of each listed request.
+    heads: Mutex<HashMap<u64, String>>,
+}
+
+impl LocalGitForge {
+    /// Serve files from `repo` whenever it holds a request's head commit,
+    /// diffing against `base`, and ask `forge` for everything else.
+    pub fn new(repo: GitRepository, base: String, forge: Box<dyn Forge>) -> Self {
+        Self {
+            repo: Mutex::new(repo),
+            base,
+            forge,
+            offline: false,
+            heads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Never contact the forge; open requests are the locally fetched heads.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    fn repo(&self) -> std::sync::MutexGuard<'_, GitRepository> {
+        self.repo
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn remember_heads(&self, pulls: &[PullRequest]) {
+        let mut heads = self.heads.lock().unwrap_or_else(|p| p.into_inner());
+        heads.extend(
+            pulls
+                .iter()
+                .map(|pull| (pull.number, pull.head_sha.clone())),
+        );
+    }
+}
+
+impl Forge for LocalGitForge {
+    fn kind(&self) -> ForgeKind {
+        self.forge.kind()
+    }
+
+    fn repo_slug(&self) -> String {
+        self.forge.repo_slug()
+    }
+
+    fn pull_reference(&self, number: u64) -> String {
+        self.forge.pull_reference(number)
+    }
+
+    fn pull_url(&self, number: u64) -> String {
+        self.forge.pull_url(number)
+    }
+
+    fn profile_url(&self, login: &str) -> String {
+        self.forge.profile_url(login)
+    }
+
+    fn list_open_pulls(&self) -> Result<Vec<PullRequest>> {
+        let pulls = if self.offline {
+            self.repo()
+                .pull_request_heads()
+                .into_iter()
+                .map(|head| PullRequest {
+                    number: head.number,
+                    draft: false,
+                    head_sha: head.sha,
+                    head_ref: String::new(),
+                    created_at: head.committed_at,
+                    updated_at: head.committed_at,
+                    author: None,
+                    avatar_url: None,
+                    title: head.summary,
+                    html_url: self.forge.pull_url(head.number),
+                    ..PullRequest::default()
+                })
+                .collect()
+        } else {
+            self.forge.list_open_pulls()?
+        };
+        self.remember_heads(&pulls);
+        Ok(pulls)
+    }
+
+    fn list_pull_files(&self, number: u64) -> Result<Vec<PullFile>> {
+        let head = self
+            .heads
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .get(&number)
+            .cloned();
+        let local = head
+            .as_deref()
+            .and_then(|head| self.repo().changed_files_between(&self.base, head));
+        match local {
+            Some(files) => Ok(files
+                .into_iter()
+                .map(|file| PullFile {
+                    filename: file.path,
+                    status: file.status.to_string(),
+                    raw_url: None,
+                    previous_filename: file.previous_path,
+                })
+                .collect()),
+            None if self.offline => Err(anyhow!(
+                "head of PR #{number} is not in the local repository"
+            )),
+            None => self.forge.list_pull_files(number),
+        }
+    }
+
+    /// Reviews live only on the forge; offline, no request has any.
+    fn list_pull_reviews(&self, number: u64) -> Result<Vec<PullReview>> {
+        if self.offline {
+            return Ok(Vec::new());
+        }
+        self.forge.list_pull_reviews(number)
+    }
+
+    fn list_commit_pulls(&self, sha: &str) -> Result<Vec<PullRequest>> {
+        if self.offline {
+            return Ok(Vec::new());
+        }
+        self.forge.list_commit_pulls(sha)
+    }
+
+    fn get_commit(&self, sha: &str) -> Result<CommitIdentity> {
+        if !self.offline {
+            return self.forge.get_commit(sha);
+        }
+        let author = self
+            .repo()
+            .commit_author(sha)
+            .ok_or_else(|| anyhow!("commit {sha} is not in the local repository"))?;
+        Ok(CommitIdentity {
+            email: Some(author.email).filter(|email| !email.is_empty()),
+            ..CommitIdentity::default()
+        })
+    }
+
+    fn download_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        if self.offline {
+            anyhow::bail!("not downloading {url} in offline mode");
+        }
+        self.forge.download_bytes(url)
+    }
+
+    fn download_file_at_ref(&self, path: &str, reference: &str) -> Result<Vec<u8>> {
+        if let Some(bytes) = self.repo().read_file_at(reference, Path::new(path)) {
+            return Ok(bytes);
+        }
+        if self.offline {
+            anyhow::bail!("{path} at {reference} is not in the local repository");
+        }
+        self.forge.download_file_at_ref(path, reference)
+    }
+
+    fn upsert_pull_comment(&self, number: u64, marker: &str, body: &str) -> Result<String> {
+        if self.offline {
+            anyhow::bail!(
+                "not commenting on {} in offline mode",
+                self.pull_reference(number)
+            );
+        }
+        self.forge.upsert_pull_comment(number, marker, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_utils::open_git_repository;
+    use crate::github::{GithubClient, GithubRepo};
+    use git2::{IndexAddOption, Repository, Signature, Time};
+    use std::fs;
+
+    fn commit_all(repo: &Repository, message: &str, update_ref: Option<&str>) -> git2::Oid {
+        let sig = Signature::new("Ada", "ada@example.com", &Time::new(1_000, 0)).unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.update_all(["*"].iter(), None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(update_ref, &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn offline_pulls_come_from_fetched_heads() {
+        let dir = std::env::temp_dir().join(format!(
+            "dossiers-local-forge-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(dir.join("specs")).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        fs::write(dir.join("specs/0001-a.md"), "# A\n").unwrap();
+        fs::write(dir.join("specs/0002-b.md"), "# B\n\nUnchanged body text.\n").unwrap();
+        let base = commit_all(&repo, "initial", Some("HEAD"));
+
+        // The PR edits one spec, renames another and adds a third, without
+        // moving the checked-out branch.
+        fs::write(dir.join("specs/0001-a.md"), "# A, revised\n").unwrap();
+        fs::rename(dir.join("specs/0002-b.md"), dir.join("specs/0002-bee.md")).unwrap();
+        fs::write(dir.join("specs/0003-c.md"), "# C\n").unwrap();
+        let head = commit_all(&repo, "Propose C\n\nDetails.", None);
+        repo.reference("refs/pull/7/head", head, false, "fetch")
+            .unwrap();
+
+        let links = GithubClient::new(
+            GithubRepo {
+                owner: "acme".to_string(),
+                name: "specs".to_string(),
+            },
+            "",
+        )
+        .unwrap();
+        let forge = LocalGitForge::new(
+            open_git_repository(&dir).unwrap(),
+            base.to_string(),
+            Box::new(links),
+        )
+        .offline();
+
+        let pulls = forge.list_open_pulls().unwrap();
+        assert_eq!(pulls.len(), 1);
+        assert_eq!((pulls[0].number, pulls[0].title.as_str()), (7, "Propose C"));
+        assert_eq!(pulls[0].html_url, "https://github.com/acme/specs/pull/7");
+
+        let mut files: Vec<(String, String)> = forge
+            .list_pull_files(7)
+            .unwrap()
+            .into_iter()
+            .map(|file| (file.filename, file.status))
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                ("specs/0001-a.md".to_string(), "modified".to_string()),
+                ("specs/0002-bee.md".to_string(), "renamed".to_string()),
+                ("specs/0003-c.md".to_string(), "added".to_string()),
+            ]
+        );
+        let body = forge
+            .download_file_at_ref("specs/0003-c.md", &head.to_string())
+            .unwrap();
+        assert_eq!(body, b"# C\n");
+        assert!(forge.download_bytes("https://example.test/x").is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
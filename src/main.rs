@@ -1,14 +1,23 @@
 use std::any::Any;
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::fmt::Write;
 use std::fs::{self, File};
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+mod avatars;
+mod diagram;
 mod highlight;
+mod mermaid;
 mod metadata;
+mod revisions;
+mod snippet;
+mod teams;
+mod visibility;
+mod workflow;
 
 use actix_files::Files;
 use actix_web::{rt::task, web, App, HttpResponse, HttpServer, Responder};
@@ -20,17 +29,29 @@ use asciidoc_parser::{
         IsBlock as _, MediaBlock, MediaType, RawDelimitedBlock, SectionBlock, SimpleBlock,
         SimpleBlockStyle,
     },
-    document::Document as AsciidocDocument,
+    document::{Document as AsciidocDocument, InterpretedValue},
     Parser as AsciidocParser,
 };
+use avatars::AvatarStore;
 use chrono::{Local, NaiveDate, TimeZone, Utc};
 use clap::{Parser as ClapParser, Subcommand};
-use dossiers::git_utils::{open_git_repository, GitTimestampCache};
-use dossiers::github::{parse_github_repo, GithubClient, GithubFile, GithubPull};
+use diagram::DiagramRegistry;
+use dossiers::bundle::SigningKey;
+use dossiers::codeowners::CodeOwners;
+use dossiers::dev_api::{Chunk, DevApi, SyncError};
+use dossiers::forge::{
+    approvers, standing_reviews, Forge, ForgeKind, PullFile, PullRequest, ReviewState,
+};
+use dossiers::git_utils::{open_git_repository, GitRepository, GitTimestampCache};
+use dossiers::github::{GithubClient, GithubHost};
+use dossiers::gitlab::GitlabClient;
+use dossiers::local_forge::LocalGitForge;
+use highlight::{FenceOptions, Highlighter};
 use lazy_static::lazy_static;
 use maud::{html, Markup, PreEscaped};
 use metadata::{
     ExtraMetadataField, MetadataReader, MetadataValue, MetadataValueType, ProjectConfiguration,
+    PushTarget,
 };
 use pulldown_cmark::{
     html as md_html, CodeBlockKind, Event, Options as MdOptions, Parser, Tag, TagEnd,
@@ -38,9 +59,13 @@ use pulldown_cmark::{
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Number, Value};
+use snippet::SnippetRef;
+use teams::TeamDirectory;
 use thiserror::Error;
 use unicode_normalization::char::is_combining_mark;
 use unicode_normalization::UnicodeNormalization;
+use visibility::Visibility;
+use workflow::{Transition, WorkflowConfig};
 
 const EMBEDDED_CSS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/global.css"));
 const EMBEDDED_FAVICON: &[u8] =
@@ -91,6 +116,8 @@ struct GeneratedSpec {
     extra: HashMap<String, Value>,
     source: String,
     format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    visibility: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -124,6 +151,22 @@ struct SpecDocument {
     listed: bool,
     revision_of: Option<String>,
     pr_number: Option<u64>,
+    /// The branch or tag this version was read from; PR previews carry
+    /// `pr_number` instead.
+    git_ref: Option<RevisionSource>,
+    /// Effective visibility, after `[visibility]` status rules.
+    visibility: Visibility,
+    /// Logins that approved the merged pull request which last changed this
+    /// spec.
+    approved_by: Vec<String>,
+    /// The commit that last changed the spec, according to git; the pull
+    /// request that merged it supplies `approved_by`.
+    last_commit: Option<String>,
+    /// Owners of the spec's document according to CODEOWNERS.
+    owners: Vec<String>,
+    /// Teams the spec names in its frontmatter; see [`AppState::spec_teams`]
+    /// for every team it belongs to.
+    teams: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +176,14 @@ struct StatusSummary {
     count: usize,
 }
 
+#[derive(Debug, Clone)]
+struct TeamSummary {
+    name: String,
+    slug: String,
+    description: Option<String>,
+    count: usize,
+}
+
 #[derive(Debug)]
 struct PendingSpec {
     id: String,
@@ -151,6 +202,9 @@ struct PendingSpec {
     meta_updated: Option<i64>,
     git_paths: Vec<PathBuf>,
     doc_path: PathBuf,
+    visibility: Option<String>,
+    owners: Vec<String>,
+    teams: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -421,16 +475,101 @@ struct AppState {
     site_name: String,
     site_description: String,
     github_repo: Option<String>,
+    github: GithubHost,
+    /// Each previewed pull request's reference (`acme/specs#4`, or
+    /// `group/specs!4` on GitLab) and web page.
+    pull_links: HashMap<u64, (String, String)>,
+    /// Reviewers, labels and merge commit of each previewed pull request.
+    pull_reviews: HashMap<u64, PullReviewInfo>,
     extra_fields: Vec<ExtraMetadataField>,
     generated_at: i64,
     assets: Assets,
     renderer: DocRenderer,
     create_config: Option<CreateDocConfig>,
+    /// Specs left out of the site for its audience; links to them are
+    /// rendered as plain text.
+    hidden_spec_ids: HashSet<String>,
+    teams: TeamDirectory,
+    workflow: WorkflowConfig,
+}
+
+/// Who was asked to review a pull request and how they responded, plus its
+/// labels and, once merged, the commit it landed as.
+#[derive(Clone, Debug, Default)]
+struct PullReviewInfo {
+    /// Requested reviewers who have not reviewed yet.
+    reviewers: Vec<String>,
+    reviews: Vec<dossiers::bundle::PrReview>,
+    labels: Vec<String>,
+    merge_commit_sha: Option<String>,
+}
+
+impl PullReviewInfo {
+    /// Read from the forge; a failure to list reviews is reported and leaves
+    /// them out.
+    fn fetch(client: &dyn Forge, pull: &PullRequest) -> Self {
+        let reviews = match client.list_pull_reviews(pull.number) {
+            Ok(reviews) => standing_reviews(&reviews)
+                .into_iter()
+                .map(|review| dossiers::bundle::PrReview {
+                    reviewer: review.reviewer,
+                    state: review.state.as_str().to_string(),
+                    submitted_at: millis_to_utc(review.submitted_at),
+                })
+                .collect(),
+            Err(err) => {
+                eprintln!(
+                    "Warning: unable to list reviews of PR #{}: {err}",
+                    pull.number
+                );
+                Vec::new()
+            }
+        };
+        Self {
+            reviewers: pull.requested_reviewers.clone(),
+            reviews,
+            labels: pull.labels.clone(),
+            merge_commit_sha: pull.merge_commit_sha.clone(),
+        }
+    }
+
+    fn from_change_set(pr: &dossiers::bundle::PrChangeSet) -> Self {
+        Self {
+            reviewers: pr.reviewers.clone(),
+            reviews: pr.reviews.clone(),
+            labels: pr.labels.clone(),
+            merge_commit_sha: pr.merge_commit_sha.clone(),
+        }
+    }
+
+    /// One entry per reviewer: `(login, verdict)`, with reviewers still to
+    /// respond listed as pending.
+    fn verdicts(&self) -> Vec<(&str, &'static str)> {
+        self.reviews
+            .iter()
+            .map(|review| {
+                let verdict = match ReviewState::parse(&review.state) {
+                    Some(ReviewState::Approved) => "approved",
+                    Some(ReviewState::ChangesRequested) => "changes requested",
+                    _ => "commented",
+                };
+                (review.reviewer.as_str(), verdict)
+            })
+            .chain(
+                self.reviewers
+                    .iter()
+                    .filter(|login| !self.reviews.iter().any(|r| &r.reviewer == *login))
+                    .map(|login| (login.as_str(), "pending")),
+            )
+            .collect()
+    }
 }
 
 #[derive(Clone)]
 struct CreateDocConfig {
     github_repo: String,
+    /// Web URL of the repository, on github.com or an Enterprise Server.
+    repo_url: String,
     default_branch: String,
     subdirectory: Option<String>,
     next_id: u64,
@@ -439,6 +578,62 @@ struct CreateDocConfig {
 }
 
 impl AppState {
+    /// Every team `spec` belongs to: those it names and those with a member
+    /// among its authors, their emails, or its owners.
+    fn spec_teams(&self, spec: &SpecDocument) -> Vec<String> {
+        self.teams.teams_of(
+            &spec.teams,
+            &spec.authors,
+            &author_emails(&spec.author_seeds),
+            &spec.owners,
+        )
+    }
+
+    /// Configured teams, then any others specs name, each with its number of
+    /// listed specs.
+    fn team_summaries(&self) -> Vec<TeamSummary> {
+        let mut summaries: Vec<TeamSummary> = self
+            .teams
+            .teams
+            .iter()
+            .map(|team| TeamSummary {
+                name: team.name.clone(),
+                slug: slugify_team(&team.name),
+                description: team.description.clone(),
+                count: 0,
+            })
+            .collect();
+        for spec in self.specs.iter().filter(|spec| spec.listed) {
+            for name in self.spec_teams(spec) {
+                let slug = slugify_team(&name);
+                match summaries.iter_mut().find(|summary| summary.slug == slug) {
+                    Some(summary) => summary.count += 1,
+                    None => summaries.push(TeamSummary {
+                        name,
+                        slug,
+                        description: None,
+                        count: 1,
+                    }),
+                }
+            }
+        }
+        summaries
+    }
+
+    /// Listed specs of the team whose page is at `slug`.
+    fn team_specs(&self, slug: &str) -> Vec<&SpecDocument> {
+        self.specs
+            .iter()
+            .filter(|spec| {
+                spec.listed
+                    && self
+                        .spec_teams(spec)
+                        .iter()
+                        .any(|name| slugify_team(name) == slug)
+            })
+            .collect()
+    }
+
     /// Recompute `create_config.next_id` from the current `spec_ids` set.
     /// Must be called after any late additions (e.g. PR specs) so that
     /// the "Create new" button proposes a non-colliding ID.
@@ -453,18 +648,123 @@ impl AppState {
                 .unwrap_or(1);
         }
     }
+
+    /// Drop every spec (and PR revision) more restricted than `audience`,
+    /// along with its static mount, so a published site holds only what its
+    /// readers may see. Returns how many specs were left out.
+    fn restrict_to_audience(
+        &mut self,
+        static_mounts: &mut Vec<StaticMount>,
+        audience: Visibility,
+    ) -> usize {
+        let hidden: HashSet<String> = self
+            .specs
+            .iter()
+            .filter(|spec| spec.visibility > audience)
+            .map(|spec| spec.id.clone())
+            .collect();
+        // A revision of a hidden spec goes with it.
+        let hidden: HashSet<String> = self
+            .specs
+            .iter()
+            .filter(|spec| {
+                hidden.contains(&spec.id)
+                    || spec
+                        .revision_of
+                        .as_ref()
+                        .is_some_and(|base| hidden.contains(base))
+            })
+            .map(|spec| spec.id.clone())
+            .collect();
+        if hidden.is_empty() {
+            return 0;
+        }
+
+        self.specs.retain(|spec| !hidden.contains(&spec.id));
+        self.specs_by_id.retain(|id, _| !hidden.contains(id));
+        self.spec_ids.retain(|id| !hidden.contains(id));
+        self.revisions.retain(|base, _| !hidden.contains(base));
+        for revisions in self.revisions.values_mut() {
+            revisions.retain(|rev| !hidden.contains(&rev.href));
+        }
+        self.revisions.retain(|_, revisions| !revisions.is_empty());
+        static_mounts
+            .retain(|(prefix, _)| !hidden.contains(prefix.strip_prefix('/').unwrap_or(prefix)));
+
+        let count = hidden.len();
+        self.hidden_spec_ids.extend(hidden);
+        count
+    }
 }
 
 type StaticMount = (String, PathBuf);
 
 #[derive(Clone)]
 struct RevisionLink {
-    pr_number: u64,
+    source: RevisionSource,
     status: String,
     updated: Option<i64>,
     href: String,
 }
 
+/// Where a revision of a spec comes from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum RevisionSource {
+    Pull(u64),
+    /// A branch matching `[revisions] branches`.
+    Branch(String),
+    /// A release tag matching `[revisions] tags`.
+    Tag(String),
+}
+
+impl RevisionSource {
+    /// Page id of this revision of `spec_id`: `<id>/pr/<n>`,
+    /// `<id>/branch/<name>`, or `v/<tag>/<id>` for a frozen release.
+    fn revision_id(&self, spec_id: &str) -> String {
+        match self {
+            Self::Pull(number) => format!("{spec_id}/pr/{number}"),
+            Self::Branch(name) => format!("{spec_id}/branch/{}", ref_slug(name)),
+            Self::Tag(name) => format!("v/{}/{spec_id}", ref_slug(name)),
+        }
+    }
+}
+
+impl std::fmt::Display for RevisionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pull(number) => write!(f, "PR #{number}"),
+            Self::Branch(name) => write!(f, "Branch {name}"),
+            Self::Tag(name) => write!(f, "Release {name}"),
+        }
+    }
+}
+
+/// A ref name as a single URL path segment: `spec/retention` becomes
+/// `spec-retention`.
+fn ref_slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Order a spec's revisions: PRs by number, then branches by name, then
+/// releases in the order they were tagged.
+fn sort_revisions(revisions: &mut [RevisionLink]) {
+    revisions.sort_by(|a, b| match (&a.source, &b.source) {
+        (RevisionSource::Tag(_), RevisionSource::Tag(_)) => a
+            .updated
+            .cmp(&b.updated)
+            .then_with(|| a.source.cmp(&b.source)),
+        _ => a.source.cmp(&b.source),
+    });
+}
+
 struct LoadResult {
     specs: Vec<SpecDocument>,
     static_mounts: Vec<StaticMount>,
@@ -476,10 +776,16 @@ struct ReloadableAppState {
     project_root: PathBuf,
     config_path: Option<PathBuf>,
     assets: Assets,
+    /// A fixed state served as-is instead of reloading from disk; set when
+    /// serving a package, whose contents cannot change underneath us.
+    snapshot: Option<AppState>,
 }
 
 impl ReloadableAppState {
     fn load(&self) -> Result<AppState> {
+        if let Some(state) = &self.snapshot {
+            return Ok(state.clone());
+        }
         let project_config =
             load_project_configuration(&self.project_root, self.config_path.as_deref());
         let site_name = resolve_site_name(&self.project_root, &project_config);
@@ -568,10 +874,8 @@ fn parse_doc_metadata(source: &str, format: &DocFormat, fallback_title: &str) ->
         };
         let value = raw_value.trim();
         match key.to_lowercase().as_str() {
-            "status" => {
-                if !value.is_empty() {
-                    status = value.to_string();
-                }
+            "status" if !value.is_empty() => {
+                status = value.to_string();
             }
             "created" => {
                 created = parse_date(value);
@@ -770,6 +1074,11 @@ fn display_extra_value(value: &Value) -> String {
     }
 }
 
+/// The emails behind a spec's authors, for matching `[teams]` members.
+fn author_emails(seeds: &[dossiers::authors::AuthorSeed]) -> Vec<String> {
+    seeds.iter().filter_map(|seed| seed.email.clone()).collect()
+}
+
 /// Resolve a document's outbound `links:` into render-ready wire rows, dropping
 /// entries missing a label or href.
 fn resolve_meta_links(links: &[Link]) -> Vec<dossiers::bundle::MetaLink> {
@@ -844,7 +1153,7 @@ fn url_escape_component(raw: &str) -> String {
     encoded
 }
 
-fn load_specs_from_json(path: &Path, _config: &ProjectConfiguration) -> Result<LoadResult> {
+fn load_specs_from_json(path: &Path, config: &ProjectConfiguration) -> Result<LoadResult> {
     let raw_specs: Vec<GeneratedSpec> = serde_json::from_reader(
         File::open(path).with_context(|| format!("Opening {}", path.display()))?,
     )
@@ -852,7 +1161,7 @@ fn load_specs_from_json(path: &Path, _config: &ProjectConfiguration) -> Result<L
 
     let mut specs = Vec::with_capacity(raw_specs.len());
     for spec in raw_specs {
-        let parsed = spec_from_generated(spec)?;
+        let parsed = spec_from_generated(spec, config)?;
         specs.push(parsed);
     }
 
@@ -939,6 +1248,9 @@ fn load_specs_from_directory(
     let mut seen_ids: HashSet<String> = HashSet::new();
     let metadata_reader = MetadataReader::new(project_config.clone());
     let git_repo = open_git_repository(dir);
+    let codeowners = git_repo
+        .as_ref()
+        .and_then(|repo| CodeOwners::load(repo.workdir()));
     let mut all_git_paths: HashSet<PathBuf> = HashSet::new();
 
     for spec_id in ordered_ids {
@@ -993,6 +1305,18 @@ fn load_specs_from_directory(
         if let Some(paths) = git_paths.as_ref() {
             all_git_paths.extend(paths.iter().cloned());
         }
+        let owners = match (&codeowners, &git_repo) {
+            (Some(codeowners), Some(repo)) => doc_path
+                .canonicalize()
+                .ok()
+                .and_then(|path| {
+                    path.strip_prefix(repo.workdir())
+                        .map(|p| codeowners.owners_of(p))
+                        .ok()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
 
         pending_specs.push(PendingSpec {
             id: spec_id.clone(),
@@ -1009,6 +1333,9 @@ fn load_specs_from_directory(
             meta_updated: meta.updated.as_deref().and_then(parse_date),
             git_paths: git_paths.unwrap_or_default(),
             doc_path: doc_path.clone(),
+            visibility: meta.visibility,
+            owners,
+            teams: meta.teams,
         });
 
         static_mounts.push((format!("/{}", spec_id), static_root));
@@ -1028,6 +1355,7 @@ fn load_specs_from_directory(
         None
     };
 
+    let author_resolver = metadata_reader.author_resolver();
     for pending in pending_specs {
         let (git_addition, git_change) = git_cache
             .as_ref()
@@ -1059,6 +1387,7 @@ fn load_specs_from_directory(
 
         let git_managed = git_repo.is_some() && (git_addition.is_some() || git_change.is_some());
         let status = metadata_reader.resolve_status(pending.status.clone(), git_managed);
+        let visibility = metadata_reader.resolve_visibility(pending.visibility.as_deref(), &status);
 
         // Credit the spec: frontmatter authors override, else the git author of
         // the commit that first added it. Resolve avatars locally (Gravatar /
@@ -1070,14 +1399,14 @@ fn load_specs_from_directory(
             .cloned();
         let author_seeds =
             dossiers::authors::build_seeds(&pending.raw_authors, git_author.as_ref());
-        let authors_meta = dossiers::authors::resolve_authors(
-            &author_seeds,
-            &dossiers::authors::AuthorResolver::local(),
-        );
+        let authors_meta = dossiers::authors::resolve_authors(&author_seeds, &author_resolver);
         let authors: Vec<String> = if author_seeds.is_empty() {
             pending.authors
         } else {
-            author_seeds.iter().map(|seed| seed.name.clone()).collect()
+            authors_meta
+                .iter()
+                .map(|author| author.name.clone())
+                .collect()
         };
 
         specs.push(SpecDocument {
@@ -1098,6 +1427,15 @@ fn load_specs_from_directory(
             listed: true,
             revision_of: None,
             pr_number: None,
+            git_ref: None,
+            approved_by: Vec::new(),
+            last_commit: git_cache
+                .as_ref()
+                .and_then(|cache| cache.latest_change_commit(&pending.git_paths))
+                .map(str::to_string),
+            visibility,
+            owners: pending.owners,
+            teams: pending.teams,
         });
     }
 
@@ -1216,6 +1554,7 @@ fn spec_document_to_generated_spec(spec: SpecDocument) -> GeneratedSpec {
             DocFormat::Markdown => "markdown".to_string(),
             DocFormat::Asciidoc => "asciidoc".to_string(),
         },
+        visibility: spec.visibility.to_wire(),
     }
 }
 
@@ -1253,13 +1592,15 @@ struct Cli {
 impl Cli {
     fn project_dir(&self) -> Option<PathBuf> {
         let path = match &self.command {
-            CliCommand::Serve { path }
-            | CliCommand::Prepare { path }
+            CliCommand::Prepare { path }
             | CliCommand::Check { path }
-            | CliCommand::List { path } => path.as_ref()?,
-            CliCommand::Build { path, .. }
+            | CliCommand::List { path, .. } => path.as_ref()?,
+            CliCommand::Serve { path, .. }
+            | CliCommand::Build { path, .. }
             | CliCommand::Push { path, .. }
+            | CliCommand::PrComment { path, .. }
             | CliCommand::Bundle { path, .. } => path.as_ref()?,
+            CliCommand::DevApi { .. } => return None,
         };
         Some(absolutize_project_dir(path.clone()))
     }
@@ -1272,6 +1613,10 @@ enum CliCommand {
         /// Spec source path (defaults to the current directory)
         #[arg(value_name = "PATH")]
         path: Option<PathBuf>,
+
+        /// Serve the specs and PR previews in a package zip instead
+        #[arg(long = "from-bundle", value_name = "FILE", conflicts_with = "path")]
+        from_bundle: Option<PathBuf>,
     },
 
     /// Render a normalised JSON snapshot of all specs to output.json
@@ -1299,6 +1644,32 @@ enum CliCommand {
         /// Generate URLs ending with a trailing slash
         #[arg(long = "trailing-slashes")]
         trailing_slashes: bool,
+
+        /// Render Mermaid diagrams to inline SVG instead of in the browser;
+        /// diagrams that fail still fall back to the client-side runtime
+        #[arg(long = "prerender-mermaid")]
+        prerender_mermaid: bool,
+
+        /// Render the specs and PR previews in a package zip instead, without
+        /// the original repository
+        #[arg(long = "from-bundle", value_name = "FILE", conflicts_with = "path")]
+        from_bundle: Option<PathBuf>,
+
+        /// Publish only specs visible to this audience: public, internal or
+        /// private [default: [visibility].audience or internal; a bundle is
+        /// built as packaged]
+        #[arg(long = "audience", value_name = "LEVEL")]
+        audience: Option<Visibility>,
+
+        /// Build PR revisions only from locally fetched PR heads, without
+        /// calling the GitHub or GitLab API
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// Download author avatars into the output instead of linking to
+        /// remote hosts [default: [avatars].self_host]
+        #[arg(long = "self-host-avatars")]
+        self_host_avatars: bool,
     },
 
     /// Lint specs for metadata, asset, and cross-reference issues
@@ -1313,6 +1684,11 @@ enum CliCommand {
         /// Spec source path (defaults to the current directory)
         #[arg(value_name = "PATH")]
         path: Option<PathBuf>,
+
+        /// Only list specs CODEOWNERS assigns to this owner (`@org/team`,
+        /// `@user` or an email; the `@` is optional)
+        #[arg(long = "owner", value_name = "OWNER")]
+        owner: Option<String>,
     },
 
     /// Package specs and push them to a Dossiers API server
@@ -1333,6 +1709,11 @@ enum CliCommand {
         #[arg(long = "token", value_name = "TOKEN")]
         token: Option<String>,
 
+        /// Push to a named [push.targets.<name>] destination, with its own URL,
+        /// project, token and spec filters [default: $DOSSIERS_TARGET]
+        #[arg(long = "target", value_name = "NAME")]
+        target: Option<String>,
+
         /// Branch label to include in the package metadata
         #[arg(long = "branch", value_name = "NAME")]
         branch: Option<String>,
@@ -1345,15 +1726,40 @@ enum CliCommand {
         #[arg(long = "no-prs")]
         no_prs: bool,
 
+        /// Build PR revisions only from locally fetched PR heads, without
+        /// calling the GitHub or GitLab API
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// Push only specs visible to this audience: public, internal or
+        /// private [default: the target's audience, else [visibility].audience
+        /// or internal]
+        #[arg(long = "audience", value_name = "LEVEL")]
+        audience: Option<Visibility>,
+
         /// Abort if the server does not respond within this many seconds; 0
         /// disables the limit [default: $DOSSIERS_TIMEOUT or 300]
         #[arg(long = "timeout", value_name = "SECONDS")]
         timeout: Option<u64>,
 
+        /// Retry a failed request this many times, backing off exponentially
+        /// [default: $DOSSIERS_RETRIES or 4]
+        #[arg(long = "retries", value_name = "COUNT")]
+        retries: Option<u32>,
+
         /// Assemble and print the package without transmitting it
         #[arg(long = "dry-run")]
         dry_run: bool,
 
+        /// Print what the push would change on the server (specs, PR revisions,
+        /// assets and bytes to send) and stop without sending anything
+        #[arg(long = "plan", conflicts_with = "dry_run")]
+        plan: bool,
+
+        /// Print the plan and ask for confirmation before sending
+        #[arg(long = "confirm", conflicts_with_all = ["dry_run", "plan"])]
+        confirm: bool,
+
         /// Push a full snapshot, skipping the incremental fast-path. Use when a
         /// change outside the git diff (e.g. edited project config, or a new
         /// server capability) means every spec must be re-sent.
@@ -1361,8 +1767,66 @@ enum CliCommand {
         full: bool,
     },
 
-    /// Assemble a package zip without pushing it (useful for debugging)
+    /// Post or update a comment on a pull request summarising the specs it
+    /// changes, with preview links and `check` findings for those specs
+    PrComment {
+        /// Spec source path (defaults to the current directory); should be a
+        /// checkout of the pull request
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// Pull or merge request number [default: from $GITHUB_REF or
+        /// $CI_MERGE_REQUEST_IID]
+        #[arg(long = "pr", value_name = "NUMBER")]
+        pr: Option<u64>,
+
+        /// Base URL of the published site, for preview links [default:
+        /// site_url in dossiers.toml]
+        #[arg(long = "site-url", value_name = "URL")]
+        site_url: Option<String>,
+
+        /// Print the comment instead of posting it
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+    },
+
+    /// Run a local stand-in for the API server's sync endpoints, for testing
+    /// `push` offline
+    DevApi {
+        /// Directory to store received packages and project snapshots in
+        #[arg(
+            long = "data-dir",
+            value_name = "DIR",
+            default_value = ".dossiers-dev-api"
+        )]
+        data_dir: PathBuf,
+
+        /// Port to listen on
+        #[arg(
+            short = 'p',
+            long = "port",
+            value_name = "PORT",
+            default_value_t = 4010
+        )]
+        port: u16,
+
+        /// Require this bearer token [default: accept any]
+        #[arg(long = "token", value_name = "TOKEN")]
+        token: Option<String>,
+
+        /// Only accept packages signed by this hex ed25519 public key
+        /// (repeatable)
+        #[arg(long = "public-key", value_name = "KEY")]
+        public_keys: Vec<String>,
+    },
+
+    /// Assemble a package zip without pushing it (useful for debugging), or
+    /// inspect, unpack, or verify an existing one
+    #[command(args_conflicts_with_subcommands = true)]
     Bundle {
+        #[command(subcommand)]
+        action: Option<BundleAction>,
+
         /// Spec source path (defaults to the current directory)
         #[arg(value_name = "PATH")]
         path: Option<PathBuf>,
@@ -1383,6 +1847,59 @@ enum CliCommand {
         /// Commit SHA to include in the package metadata
         #[arg(long = "commit", value_name = "SHA")]
         commit: Option<String>,
+
+        /// Package only specs visible to this audience: public, internal or
+        /// private [default: [visibility].audience or internal]
+        #[arg(long = "audience", value_name = "LEVEL")]
+        audience: Option<Visibility>,
+
+        /// Build PR revisions only from locally fetched PR heads, without
+        /// calling the GitHub or GitLab API
+        #[arg(long = "offline")]
+        offline: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BundleAction {
+    /// Print a package's manifest, spec index, PR change-sets, and sizes
+    Inspect {
+        /// Package zip to read
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Extract a package into a directory with the same layout as the zip
+    Unpack {
+        /// Package zip to read
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Directory to write [default: FILE without its extension]
+        #[arg(short = 'o', long = "output", value_name = "DIR")]
+        output: Option<PathBuf>,
+    },
+
+    /// Check a package's schema, digests, signature, and internal consistency
+    Verify {
+        /// Package zip to read
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Require a signature by this hex ed25519 public key (repeatable)
+        #[arg(long = "public-key", value_name = "KEY")]
+        public_keys: Vec<String>,
+    },
+
+    /// Generate an ed25519 key pair for signing packages
+    Keygen {
+        /// Write the secret key to this file instead of printing it
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Replace an existing key file
+        #[arg(long = "force")]
+        force: bool,
     },
 }
 
@@ -1402,36 +1919,92 @@ async fn main() -> Result<()> {
 
 async fn run_command(command: CliCommand, config_path: Option<PathBuf>) -> Result<()> {
     match command {
-        CliCommand::Serve { path } => run_server(resolve_input(path)?, config_path).await,
+        CliCommand::Serve {
+            from_bundle: Some(bundle),
+            ..
+        } => run_server_from_bundle(bundle, config_path).await,
+        CliCommand::Serve { path, .. } => run_server(resolve_input(path)?, config_path).await,
         CliCommand::Prepare { path } => {
             run_prepare(resolve_input(path)?, config_path)?;
             Ok(())
         }
+        CliCommand::Build {
+            output_dir,
+            trailing_slashes,
+            prerender_mermaid,
+            from_bundle: Some(bundle),
+            audience,
+            self_host_avatars,
+            ..
+        } => {
+            task::spawn_blocking(move || {
+                run_build_from_bundle(
+                    bundle,
+                    output_dir,
+                    config_path,
+                    trailing_slashes,
+                    prerender_mermaid,
+                    audience,
+                    self_host_avatars,
+                )
+            })
+            .await
+            .map_err(|err| anyhow!("build task failed: {err}"))??;
+            Ok(())
+        }
         CliCommand::Build {
             path,
             output_dir,
             trailing_slashes,
+            prerender_mermaid,
+            from_bundle: None,
+            audience,
+            offline,
+            self_host_avatars,
         } => {
             let input_path = resolve_input(path)?;
             task::spawn_blocking(move || {
-                run_build(input_path, output_dir, config_path, trailing_slashes)
+                run_build(
+                    input_path,
+                    output_dir,
+                    config_path,
+                    trailing_slashes,
+                    prerender_mermaid,
+                    audience,
+                    offline,
+                    self_host_avatars,
+                )
             })
             .await
             .map_err(|err| anyhow!("build task failed: {err}"))??;
             Ok(())
         }
         CliCommand::Check { path } => run_check(resolve_input(path)?, config_path),
-        CliCommand::List { path } => run_list(resolve_input(path)?, config_path),
+        CliCommand::DevApi {
+            data_dir,
+            port,
+            token,
+            public_keys,
+        } => run_dev_api(data_dir, port, token, &public_keys).await,
+        CliCommand::List { path, owner } => {
+            run_list(resolve_input(path)?, config_path, owner.as_deref())
+        }
         CliCommand::Push {
             path,
             api_url,
             project,
             token,
+            target,
             branch,
             commit,
             no_prs,
+            offline,
+            audience,
             timeout,
+            retries,
             dry_run,
+            plan,
+            confirm,
             full,
         } => {
             let input_path = resolve_input(path)?;
@@ -1442,26 +2015,65 @@ async fn run_command(command: CliCommand, config_path: Option<PathBuf>) -> Resul
                     api_url,
                     project,
                     token,
+                    target,
                     branch,
                     commit,
                     no_prs,
+                    offline,
+                    audience,
                     timeout,
+                    retries,
                     dry_run,
+                    plan,
+                    confirm,
                     full,
                 )
             })
             .await
             .map_err(|err| anyhow!("push task failed: {err}"))?
         }
+        CliCommand::PrComment {
+            path,
+            pr,
+            site_url,
+            dry_run,
+        } => {
+            let input_path = resolve_input(path)?;
+            task::spawn_blocking(move || {
+                run_pr_comment(input_path, config_path, pr, site_url, dry_run)
+            })
+            .await
+            .map_err(|err| anyhow!("pr-comment task failed: {err}"))?
+        }
+        CliCommand::Bundle {
+            action: Some(action),
+            ..
+        } => match action {
+            BundleAction::Inspect { file } => run_bundle_inspect(&file),
+            BundleAction::Unpack { file, output } => run_bundle_unpack(&file, output),
+            BundleAction::Verify { file, public_keys } => run_bundle_verify(&file, &public_keys),
+            BundleAction::Keygen { output, force } => run_bundle_keygen(output, force),
+        },
         CliCommand::Bundle {
+            action: None,
             path,
             output,
             branch,
             commit,
+            audience,
+            offline,
         } => {
             let input_path = resolve_input(path)?;
             task::spawn_blocking(move || {
-                run_bundle(input_path, config_path, output, branch, commit)
+                run_bundle(
+                    input_path,
+                    config_path,
+                    output,
+                    branch,
+                    commit,
+                    audience,
+                    offline,
+                )
             })
             .await
             .map_err(|err| anyhow!("bundle task failed: {err}"))?
@@ -1586,11 +2198,17 @@ fn run_check(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
         validate_project_config_for_check(&project_root, config_path.as_deref());
 
     let resolved_input = resolve_spec_input_path(&input_path, &project_config);
-    let (specs, discovery_report) = discover_specs_for_check(&resolved_input);
-    let metadata_report = validate_metadata_for_specs(&specs, &project_config);
-    let lint_data = collect_spec_lint_data(&specs);
-    let asset_report = validate_asset_references(&specs, &lint_data);
-    let cross_report = validate_cross_references(&specs, &lint_data);
+    let SpecCheckReports {
+        specs,
+        discovery: discovery_report,
+        metadata: metadata_report,
+        assets: asset_report,
+        cross: cross_report,
+        snippets: snippet_report,
+        visibility: visibility_report,
+        ownership: ownership_report,
+        workflow: workflow_report,
+    } = check_specs(&resolved_input, &project_root, &project_config);
     let doc_success = format!(
         "Found {} specification{}",
         specs.len(),
@@ -1629,6 +2247,32 @@ fn run_check(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
     total_errors += errors;
     total_warnings += warnings;
 
+    println!();
+    println!("Checking code snippets...");
+    let (errors, warnings) = print_category_report(&snippet_report, "All code snippets are valid");
+    total_errors += errors;
+    total_warnings += warnings;
+
+    println!();
+    println!("Checking visibility...");
+    let (errors, warnings) =
+        print_category_report(&visibility_report, "No spec links to a less visible one");
+    total_errors += errors;
+    total_warnings += warnings;
+
+    println!();
+    println!("Checking ownership...");
+    let (errors, warnings) = print_category_report(&ownership_report, "Every spec has an owner");
+    total_errors += errors;
+    total_warnings += warnings;
+
+    println!();
+    println!("Checking status workflow...");
+    let (errors, warnings) =
+        print_category_report(&workflow_report, "All status changes follow the workflow");
+    total_errors += errors;
+    total_warnings += warnings;
+
     println!(
         "Summary: {} errors, {} warnings",
         total_errors, total_warnings
@@ -1641,11 +2285,72 @@ fn run_check(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-fn run_list(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
+/// Everything `check` reports about the specs themselves, one report per
+/// category.
+struct SpecCheckReports {
+    specs: Vec<LintSpec>,
+    discovery: CategoryReport,
+    metadata: CategoryReport,
+    assets: CategoryReport,
+    cross: CategoryReport,
+    snippets: CategoryReport,
+    visibility: CategoryReport,
+    ownership: CategoryReport,
+    workflow: CategoryReport,
+}
+
+impl SpecCheckReports {
+    fn categories(&self) -> [&CategoryReport; 8] {
+        [
+            &self.discovery,
+            &self.metadata,
+            &self.assets,
+            &self.cross,
+            &self.snippets,
+            &self.visibility,
+            &self.ownership,
+            &self.workflow,
+        ]
+    }
+}
+
+fn check_specs(
+    spec_root: &Path,
+    project_root: &Path,
+    project_config: &ProjectConfiguration,
+) -> SpecCheckReports {
+    let (specs, discovery) = discover_specs_for_check(spec_root);
+    let metadata = validate_metadata_for_specs(&specs, project_config);
+    let lint_data = collect_spec_lint_data(&specs);
+    let assets = validate_asset_references(&specs, &lint_data);
+    let cross = validate_cross_references(&specs, &lint_data);
+    let snippets = validate_snippet_references(&specs, &snippet_root(project_root));
+    let visibility = validate_visibility(&specs, &lint_data, project_config);
+    let ownership = validate_ownership(&specs, spec_root);
+    let workflow = validate_workflow(&specs, spec_root, project_config);
+    SpecCheckReports {
+        specs,
+        discovery,
+        metadata,
+        assets,
+        cross,
+        snippets,
+        visibility,
+        ownership,
+        workflow,
+    }
+}
+
+fn run_list(input_path: PathBuf, config_path: Option<PathBuf>, owner: Option<&str>) -> Result<()> {
     let project_root = project_root_from(config_path.as_deref(), &input_path);
     let project_config = load_project_configuration(&project_root, config_path.as_deref());
     let resolved_input = resolve_spec_input_path(&input_path, &project_config);
     let mut load_result = load_specs(&resolved_input, &project_config)?;
+    if let Some(owner) = owner {
+        load_result
+            .specs
+            .retain(|spec| spec.owners.iter().any(|o| same_owner(o, owner)));
+    }
     load_result
         .specs
         .sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.title.cmp(&b.title)));
@@ -1654,18 +2359,63 @@ fn run_list(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
     let use_color = supports_color();
 
     if load_result.specs.is_empty() {
-        println!("No specifications found");
+        match owner {
+            Some(owner) => println!("No specifications owned by {owner}"),
+            None => println!("No specifications found"),
+        }
         return Ok(());
     }
 
-    for spec in load_result.specs {
-        print_list_entry(&spec, &display_prefix, use_color);
+    for spec in &load_result.specs {
+        print_list_entry(spec, &display_prefix, use_color);
         println!();
     }
 
+    if owner.is_none() {
+        if let Some(summary) = ownership_summary(&load_result.specs) {
+            println!("{summary}");
+        }
+    }
+
     Ok(())
 }
 
+/// Whether two owners (or team members) name the same account, ignoring case
+/// and a leading `@`.
+fn same_owner(a: &str, b: &str) -> bool {
+    a.trim_start_matches('@')
+        .eq_ignore_ascii_case(b.trim_start_matches('@'))
+}
+
+/// Specs per owner, most first, plus the unowned count — e.g.
+/// `Owners: @acme/docs (3), @grace (1); 2 unowned`. `None` when no spec has an
+/// owner.
+fn ownership_summary(specs: &[SpecDocument]) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for owner in specs.iter().flat_map(|spec| &spec.owners) {
+        *counts.entry(owner.as_str()).or_default() += 1;
+    }
+    if counts.is_empty() {
+        return None;
+    }
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut summary = format!(
+        "Owners: {}",
+        ranked
+            .iter()
+            .map(|(owner, count)| format!("{owner} ({count})"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let unowned = specs.iter().filter(|spec| spec.owners.is_empty()).count();
+    if unowned > 0 {
+        let _ = write!(summary, "; {unowned} unowned");
+    }
+    Some(summary)
+}
+
 fn print_list_entry(spec: &SpecDocument, display_prefix: &str, use_color: bool) {
     let bold_start = if use_color { "\u{001b}[1m" } else { "" };
     let bold_end = if use_color { "\u{001b}[22m" } else { "" };
@@ -1682,6 +2432,9 @@ fn print_list_entry(spec: &SpecDocument, display_prefix: &str, use_color: bool)
     let created = format_spec_date(spec.created, false).unwrap_or_else(|| "n/a".into());
     let updated = format_spec_date(spec.updated, false).unwrap_or_else(|| "n/a".into());
     println!("  {status}, created: {created}, updated: {updated}");
+    if !spec.owners.is_empty() {
+        println!("  owners: {}", spec.owners.join(", "));
+    }
 }
 
 fn color_status(status: &str, use_color: bool) -> String {
@@ -1742,43 +2495,284 @@ async fn run_server(input_path: PathBuf, config_path: Option<PathBuf>) -> Result
         project_root: project_root.clone(),
         config_path: config_path.clone(),
         assets,
+        snapshot: None,
     };
 
-    println!("Serving specs on http://localhost:8080");
-    HttpServer::new(move || {
-        let mut app = App::new()
-            .app_data(web::Data::new(reloadable_state.clone()))
-            .route("/", web::get().to(index_page))
-            .route("/favicon.svg", web::get().to(favicon))
-            .route("/assets/mermaid.min.js", web::get().to(mermaid_script))
-            .route("/author/{slug}/", web::get().to(author_redirect))
-            .route("/author/{slug}", web::get().to(author_page))
-            .route("/status", web::get().to(status_index_page))
-            .route("/status/", web::get().to(status_index_page))
-            .route("/status/{slug}/", web::get().to(status_redirect))
-            .route("/status/{slug}", web::get().to(status_page))
-            .route("/{spec_id:\\d+}", web::get().to(spec_page))
-            .route("/{spec_id:\\d+}/", web::get().to(spec_redirect));
-
-        for (mount, path) in &static_mounts {
-            app = app.service(Files::new(&mount[..], path.clone()).prefer_utf8(true));
-        }
+    serve_site(reloadable_state, static_mounts).await
+}
 
-        app
+async fn run_server_from_bundle(bundle: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
+    let workspace = bundle_workspace();
+    let assets = Assets::embedded();
+    let (state, static_mounts) = task::spawn_blocking({
+        let (bundle, workspace, config_path, assets) = (
+            bundle.clone(),
+            workspace.clone(),
+            config_path.clone(),
+            assets.clone(),
+        );
+        move || load_bundle_state(&bundle, &workspace, config_path.as_deref(), assets, false)
     })
-    .bind(("127.0.0.1", 8080))?
-    .run()
-    .await?;
+    .await
+    .map_err(|err| anyhow!("loading bundle failed: {err}"))??;
+    println!("Loaded {} from {}", state.site_name, bundle.display());
 
-    Ok(())
+    let reloadable_state = ReloadableAppState {
+        input_path: bundle,
+        project_root: workspace.clone(),
+        config_path,
+        assets,
+        snapshot: Some(state),
+    };
+    let served = serve_site(reloadable_state, static_mounts).await;
+    let _ = fs::remove_dir_all(&workspace);
+    served
 }
 
-fn run_prepare(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
-    let project_root = project_root_from(config_path.as_deref(), &input_path);
-    let project_config = load_project_configuration(&project_root, config_path.as_deref());
-    let (specs, _) = load_and_sort_specs(&input_path, &project_config)?;
+struct DevApiState {
+    api: DevApi,
+    token: Option<String>,
+}
 
-    let prepared: Vec<GeneratedSpec> = specs
+async fn run_dev_api(
+    data_dir: PathBuf,
+    port: u16,
+    token: Option<String>,
+    public_keys: &[String],
+) -> Result<()> {
+    let trusted = public_keys
+        .iter()
+        .map(|key| {
+            dossiers::bundle::parse_verifying_key(key)
+                .with_context(|| format!("parsing public key {key}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let state = DevApiState {
+        api: DevApi::new(data_dir).with_trusted_keys(trusted),
+        token,
+    };
+    let data_dir = state.api.data_dir().display().to_string();
+    let (server, addr) = bind_dev_api(state, port)?;
+    println!("Dev API listening on http://{addr} (storing packages in {data_dir})");
+    println!("Push to it with: dossiers push --api-url http://{addr} --project <name> --token dev");
+    server.await?;
+    Ok(())
+}
+
+fn bind_dev_api(
+    state: DevApiState,
+    port: u16,
+) -> std::io::Result<(actix_web::dev::Server, std::net::SocketAddr)> {
+    let state = web::Data::new(state);
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .app_data(web::PayloadConfig::new(256 * 1024 * 1024))
+            .route(
+                "/api/v1/projects/{project}/sync/state",
+                web::get().to(dev_api_state),
+            )
+            .route(
+                "/api/v1/projects/{project}/sync",
+                web::post().to(dev_api_sync),
+            )
+            .route(
+                "/api/v1/projects/{project}/sync/runs/{run_id}",
+                web::get().to(dev_api_run),
+            )
+            .route(
+                "/api/v1/projects/{project}/snapshot",
+                web::get().to(dev_api_snapshot),
+            )
+    })
+    .bind(("127.0.0.1", port))?;
+    let addr = server.addrs()[0];
+    Ok((server.run(), addr))
+}
+
+#[derive(Deserialize)]
+struct DevApiSyncQuery {
+    run_id: Option<String>,
+    chunk: Option<usize>,
+    #[serde(rename = "final", default)]
+    last: bool,
+}
+
+fn dev_api_authorized(state: &DevApiState, request: &actix_web::HttpRequest) -> bool {
+    let presented = request
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match (&state.token, presented) {
+        (Some(expected), Some(presented)) => expected == presented,
+        (None, presented) => presented.is_some(),
+        (Some(_), None) => false,
+    }
+}
+
+fn dev_api_error(err: SyncError) -> HttpResponse {
+    match &err {
+        SyncError::Conflict(_) => HttpResponse::Conflict().body(err.to_string()),
+        SyncError::Rejected(_) | SyncError::Bundle(_) => {
+            HttpResponse::BadRequest().body(err.to_string())
+        }
+        SyncError::Io(_) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn dev_api_state(
+    path: web::Path<String>,
+    request: actix_web::HttpRequest,
+    state: web::Data<DevApiState>,
+) -> impl Responder {
+    if !dev_api_authorized(&state, &request) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let project = path.into_inner();
+    match web::block(move || state.api.state(&project)).await {
+        Ok(Ok(sync_state)) => HttpResponse::Ok().json(sync_state),
+        Ok(Err(err)) => dev_api_error(err),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn dev_api_sync(
+    path: web::Path<String>,
+    query: web::Query<DevApiSyncQuery>,
+    request: actix_web::HttpRequest,
+    body: web::Bytes,
+    state: web::Data<DevApiState>,
+) -> impl Responder {
+    if !dev_api_authorized(&state, &request) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let project = path.into_inner();
+    let query = query.into_inner();
+    let result = web::block(move || {
+        let chunk = query.run_id.as_deref().map(|run_id| Chunk {
+            run_id,
+            index: query.chunk,
+            last: query.last,
+        });
+        let result = state.api.sync(&project, &body, chunk);
+        match &result {
+            Ok(summary) => println!(
+                "{project}: synced {} spec(s), {} created, {} updated, {} deleted",
+                summary.synced, summary.created, summary.updated, summary.deleted
+            ),
+            Err(err) => eprintln!("{project}: rejected package: {err}"),
+        }
+        result
+    })
+    .await;
+    match result {
+        Ok(Ok(summary)) => HttpResponse::Ok().json(summary),
+        Ok(Err(err)) => dev_api_error(err),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn dev_api_run(
+    path: web::Path<(String, String)>,
+    request: actix_web::HttpRequest,
+    state: web::Data<DevApiState>,
+) -> impl Responder {
+    if !dev_api_authorized(&state, &request) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let (project, run_id) = path.into_inner();
+    let run = web::block(move || {
+        let chunks = state.api.run_chunks(&project, &run_id)?;
+        let finalized = chunks.is_none() && state.api.run_finalized(&project, &run_id)?;
+        Ok::<_, SyncError>((chunks, finalized))
+    });
+    match run.await {
+        Ok(Ok((Some(chunks), _))) => {
+            HttpResponse::Ok().json(serde_json::json!({ "chunks": chunks }))
+        }
+        Ok(Ok((None, true))) => {
+            HttpResponse::Ok().json(serde_json::json!({ "chunks": [], "finalized": true }))
+        }
+        Ok(Ok((None, false))) => HttpResponse::NotFound().body("unknown run"),
+        Ok(Err(err)) => dev_api_error(err),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn dev_api_snapshot(
+    path: web::Path<String>,
+    request: actix_web::HttpRequest,
+    state: web::Data<DevApiState>,
+) -> impl Responder {
+    if !dev_api_authorized(&state, &request) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let project = path.into_inner();
+    let result = web::block(move || {
+        state.api.snapshot(&project).and_then(|snapshot| {
+            snapshot
+                .map(|package| {
+                    package
+                        .write_zip(std::io::Cursor::new(Vec::new()))
+                        .map(|buf| buf.into_inner())
+                })
+                .transpose()
+                .map_err(SyncError::from)
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok(Some(zip_bytes))) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .body(zip_bytes),
+        Ok(Ok(None)) => HttpResponse::NotFound().body("nothing has been synced yet"),
+        Ok(Err(err)) => dev_api_error(err),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn serve_site(
+    reloadable_state: ReloadableAppState,
+    static_mounts: Vec<StaticMount>,
+) -> Result<()> {
+    println!("Serving specs on http://localhost:8080");
+    HttpServer::new(move || {
+        let mut app = App::new()
+            .app_data(web::Data::new(reloadable_state.clone()))
+            .route("/", web::get().to(index_page))
+            .route("/favicon.svg", web::get().to(favicon))
+            .route("/assets/mermaid.min.js", web::get().to(mermaid_script))
+            .route("/author/{slug}/", web::get().to(author_redirect))
+            .route("/author/{slug}", web::get().to(author_page))
+            .route("/status", web::get().to(status_index_page))
+            .route("/status/", web::get().to(status_index_page))
+            .route("/status/{slug}/", web::get().to(status_redirect))
+            .route("/status/{slug}", web::get().to(status_page))
+            .route("/team/{slug}/", web::get().to(team_redirect))
+            .route("/team/{slug}", web::get().to(team_page))
+            .route("/{spec_id:\\d+}", web::get().to(spec_page))
+            .route("/{spec_id:\\d+}/", web::get().to(spec_redirect));
+
+        for (mount, path) in &static_mounts {
+            app = app.service(Files::new(&mount[..], path.clone()).prefer_utf8(true));
+        }
+
+        app
+    })
+    .bind(("127.0.0.1", 8080))?
+    .run()
+    .await?;
+
+    Ok(())
+}
+
+fn run_prepare(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()> {
+    let project_root = project_root_from(config_path.as_deref(), &input_path);
+    let project_config = load_project_configuration(&project_root, config_path.as_deref());
+    let (specs, _) = load_and_sort_specs(&input_path, &project_config)?;
+
+    let prepared: Vec<GeneratedSpec> = specs
         .into_iter()
         .map(spec_document_to_generated_spec)
         .collect();
@@ -1796,14 +2790,24 @@ fn run_prepare(input_path: PathBuf, config_path: Option<PathBuf>) -> Result<()>
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_build(
     input_path: PathBuf,
     output_dir: PathBuf,
     config_path: Option<PathBuf>,
     trailing_slashes: bool,
+    prerender_mermaid: bool,
+    audience: Option<Visibility>,
+    offline: bool,
+    self_host_avatars: bool,
 ) -> Result<()> {
     let project_root = project_root_from(config_path.as_deref(), &input_path);
-    let project_config = load_project_configuration(&project_root, config_path.as_deref());
+    let mut project_config = load_project_configuration(&project_root, config_path.as_deref());
+    if prerender_mermaid {
+        project_config.diagrams.enable_embedded("mermaid");
+    }
+    project_config.offline |= offline;
+    project_config.avatars.self_host |= self_host_avatars;
     let assets = Assets::embedded();
     let site_name = resolve_site_name(&project_root, &project_config);
 
@@ -1824,7 +2828,374 @@ fn run_build(
     ) {
         eprintln!("Warning: failed to incorporate pull request revisions: {err}");
     }
+    if let Err(err) = augment_with_git_refs(
+        &mut state,
+        &mut static_mounts,
+        &input_path,
+        &project_root,
+        &project_config,
+    ) {
+        eprintln!("Warning: failed to incorporate branch and tag revisions: {err}");
+    }
+
+    let audience = audience.unwrap_or_else(|| project_config.visibility.audience());
+    report_hidden_specs(
+        state.restrict_to_audience(&mut static_mounts, audience),
+        audience,
+    );
+
+    let avatars = localize_avatars(&mut state, &project_config, &project_root);
+    let written = write_static_site(state, static_mounts, &output_dir, trailing_slashes)
+        .and_then(|()| write_avatars(avatars, &output_dir));
+    // Branch and tag revisions are served from files exported for them; the
+    // site holds its own copies now.
+    let _ = fs::remove_dir_all(git_ref_workspace());
+    written
+}
+
+/// With `[avatars] self_host` on, point every author avatar in `state` at a
+/// copy under `assets/avatars` and return the store holding those copies.
+fn localize_avatars(
+    state: &mut AppState,
+    project_config: &ProjectConfiguration,
+    project_root: &Path,
+) -> Option<AvatarStore> {
+    if !project_config.avatars.self_host {
+        return None;
+    }
+    let mut store = AvatarStore::new(
+        &project_config.avatars,
+        project_root,
+        project_config.offline,
+    );
+    for author in state
+        .specs
+        .iter_mut()
+        .flat_map(|spec| spec.authors_meta.iter_mut())
+    {
+        store.localize(author);
+    }
+    Some(store)
+}
+
+fn write_avatars(avatars: Option<AvatarStore>, output_dir: &Path) -> Result<()> {
+    let Some(store) = avatars else {
+        return Ok(());
+    };
+    store.write(output_dir)?;
+    println!("Self-hosted {} author avatar(s)", store.len());
+    Ok(())
+}
+
+fn report_hidden_specs(count: usize, audience: Visibility) {
+    if count > 0 {
+        println!("Leaving out {count} spec(s) not published to the {audience} audience");
+    }
+}
+
+fn run_build_from_bundle(
+    bundle: PathBuf,
+    output_dir: PathBuf,
+    config_path: Option<PathBuf>,
+    trailing_slashes: bool,
+    prerender_mermaid: bool,
+    audience: Option<Visibility>,
+    self_host_avatars: bool,
+) -> Result<()> {
+    let workspace = bundle_workspace();
+    let built = load_bundle_state(
+        &bundle,
+        &workspace,
+        config_path.as_deref(),
+        Assets::embedded(),
+        prerender_mermaid,
+    )
+    .and_then(|(mut state, mut static_mounts)| {
+        // The package already holds only what its producer's audience allowed,
+        // so links to specs it lacks were left out there; narrow it further
+        // only when asked.
+        let missing: HashSet<String> = state
+            .specs
+            .iter()
+            .flat_map(|spec| collect_references(&spec.source, spec.format).1)
+            .map(|reference| reference.target_spec)
+            .filter(|id| !state.spec_ids.contains(id))
+            .collect();
+        state.hidden_spec_ids.extend(missing);
+        if let Some(audience) = audience {
+            report_hidden_specs(
+                state.restrict_to_audience(&mut static_mounts, audience),
+                audience,
+            );
+        }
+        // The package's own configuration, as `load_bundle_state` read it.
+        let packaged_config = workspace.join("project.toml");
+        let mut project_config = load_project_configuration(
+            &workspace,
+            config_path.as_deref().or(packaged_config
+                .exists()
+                .then_some(packaged_config.as_path())),
+        );
+        project_config.avatars.self_host |= self_host_avatars;
+        // The workspace is temporary; keep the avatar cache where the build runs.
+        let cache_root = env::current_dir().unwrap_or_else(|_| workspace.clone());
+        let avatars = localize_avatars(&mut state, &project_config, &cache_root);
+        write_static_site(state, static_mounts, &output_dir, trailing_slashes)?;
+        write_avatars(avatars, &output_dir)
+    });
+    let _ = fs::remove_dir_all(&workspace);
+    built
+}
+
+/// Where `--from-bundle` unpacks a package while its site is rendered.
+fn bundle_workspace() -> PathBuf {
+    env::temp_dir().join(format!("dossiers-bundle-{}", std::process::id()))
+}
+
+/// Load a site from a package zip rather than a spec directory.
+///
+/// The package is unpacked into `workspace` so assets can be copied or served
+/// from disk. Metadata comes from the manifest's spec index and each PR's
+/// `spec_meta`, which the producer already resolved from git and GitHub, so
+/// neither is consulted here; specs without an index entry fall back to their
+/// frontmatter. The packaged `project.toml` is used unless `config_path`
+/// overrides it.
+fn load_bundle_state(
+    bundle: &Path,
+    workspace: &Path,
+    config_path: Option<&Path>,
+    assets: Assets,
+    prerender_mermaid: bool,
+) -> Result<(AppState, Vec<StaticMount>)> {
+    use dossiers::bundle::{AssetChange, SpecChange};
+
+    let (package, _, _) = read_bundle(bundle)?;
+    if workspace.exists() {
+        fs::remove_dir_all(workspace)
+            .with_context(|| format!("clearing {}", workspace.display()))?;
+    }
+    package
+        .write_dir(workspace)
+        .with_context(|| format!("unpacking {} to {}", bundle.display(), workspace.display()))?;
+
+    let packaged_config = package
+        .project_config
+        .as_ref()
+        .map(|_| workspace.join("project.toml"));
+    let mut project_config =
+        load_project_configuration(workspace, config_path.or(packaged_config.as_deref()));
+    if prerender_mermaid {
+        project_config.diagrams.enable_embedded("mermaid");
+    }
+    let site_name = resolve_site_name(workspace, &project_config);
+    let reader = MetadataReader::new(project_config.clone());
+    let generated_at = package.manifest.timestamp.timestamp_millis();
+
+    let index: HashMap<&str, &dossiers::bundle::SpecIndexEntry> = package
+        .manifest
+        .specs
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry))
+        .collect();
+    let main_root = workspace.join("main");
+    let mut static_mounts = Vec::new();
+    let mut specs = Vec::new();
+    for spec in &package.mainline.specs {
+        let mut doc = spec_document_from_package(spec, &reader, None);
+        if let Some(entry) = index.get(spec.id.as_str()) {
+            doc.title = entry.title.clone();
+            doc.status = entry.status.clone();
+            doc.created = entry.created.map(|t| t.timestamp_millis()).or(doc.created);
+            doc.updated = entry.updated.map(|t| t.timestamp_millis()).or(doc.updated);
+            if !entry.authors.is_empty() {
+                doc.authors = entry.authors.clone();
+                doc.authors_meta = entry.authors_meta.clone();
+            }
+            doc.links = entry
+                .links
+                .iter()
+                .map(|link| Link {
+                    label: link.label.clone(),
+                    href: link.href.clone(),
+                })
+                .collect();
+            doc.extra = entry.extra.clone().into_iter().collect();
+            doc.approved_by = entry.approved_by.clone();
+            doc.owners = entry.owners.clone();
+            doc.teams = entry.teams.clone();
+            doc.visibility = doc
+                .visibility
+                .max(Visibility::from_wire(entry.visibility.as_deref()));
+        }
+        doc.updated_sort = doc.updated.or(doc.created).unwrap_or(generated_at);
+        static_mounts.push((format!("/{}", spec.id), main_root.join(&spec.dir_name)));
+        specs.push(doc);
+    }
+    specs.sort_by(|a, b| {
+        b.updated_sort
+            .cmp(&a.updated_sort)
+            .then_with(|| b.id.cmp(&a.id))
+    });
+
+    let mut state = app_state_from_specs(
+        specs,
+        &main_root,
+        workspace,
+        site_name,
+        assets,
+        project_config,
+    );
+    state.generated_at = generated_at;
+
+    for pr in &package.pr_changes {
+        state
+            .pull_reviews
+            .insert(pr.pr_number, PullReviewInfo::from_change_set(pr));
+        let pr_root = workspace
+            .join("prs")
+            .join(pr.pr_number.to_string())
+            .join("specs");
+        for change in &pr.spec_changes {
+            let SpecChange::Upsert(spec) = change else {
+                continue;
+            };
+            // A change-set only carries the files the PR touched; fill in the
+            // rest of the spec's assets from mainline so its preview is whole.
+            let static_root = pr_root.join(&spec.dir_name);
+            let base = package
+                .mainline
+                .specs
+                .iter()
+                .find(|base| base.id == spec.id);
+            for asset in base.map(|base| base.assets.as_slice()).unwrap_or_default() {
+                let removed = pr.asset_changes.iter().any(|change| {
+                    matches!(change, AssetChange::Remove { spec_id, path }
+                        if *spec_id == spec.id && *path == asset.path)
+                });
+                let target = static_root.join(&asset.path);
+                if removed || target.exists() {
+                    continue;
+                }
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, &asset.bytes)
+                    .with_context(|| format!("writing {}", target.display()))?;
+            }
+
+            let mut doc = spec_document_from_package(spec, &reader, Some(&pr.state));
+            doc.owners = index
+                .get(spec.id.as_str())
+                .map(|entry| entry.owners.clone())
+                .unwrap_or_default();
+            doc.created = doc.created.or(pr.created_at.map(|t| t.timestamp_millis()));
+            doc.updated = doc.updated.or(pr.updated_at.map(|t| t.timestamp_millis()));
+            if let Some(meta) = pr.spec_meta.iter().find(|meta| meta.spec_id == spec.id) {
+                doc.title = meta.title.clone();
+                doc.status = meta.status.clone();
+                doc.created = meta.created.map(|t| t.timestamp_millis()).or(doc.created);
+                doc.updated = meta.updated.map(|t| t.timestamp_millis()).or(doc.updated);
+                if !meta.authors.is_empty() {
+                    doc.authors = meta.authors.clone();
+                    doc.authors_meta = meta.authors_meta.clone();
+                }
+                doc.links = meta
+                    .links
+                    .iter()
+                    .map(|link| Link {
+                        label: link.label.clone(),
+                        href: link.href.clone(),
+                    })
+                    .collect();
+                doc.visibility = doc
+                    .visibility
+                    .max(Visibility::from_wire(meta.visibility.as_deref()));
+            }
+            doc.updated_sort = doc.updated.or(doc.created).unwrap_or(generated_at);
+            insert_revision_spec(
+                &mut state,
+                &mut static_mounts,
+                doc,
+                RevisionSource::Pull(pr.pr_number),
+                static_root,
+            );
+        }
+    }
+    for revisions in state.revisions.values_mut() {
+        sort_revisions(revisions);
+    }
+
+    Ok((state, static_mounts))
+}
+
+/// A packaged spec with metadata read from its own frontmatter, as a starting
+/// point for the producer-resolved metadata in the manifest. `status_fallback`
+/// applies when the frontmatter declares no status.
+fn spec_document_from_package(
+    spec: &dossiers::bundle::Spec,
+    reader: &MetadataReader,
+    status_fallback: Option<&str>,
+) -> SpecDocument {
+    let source = String::from_utf8_lossy(&spec.source);
+    let format = match spec.format {
+        dossiers::bundle::DocFormat::Markdown => DocFormat::Markdown,
+        dossiers::bundle::DocFormat::Asciidoc => DocFormat::Asciidoc,
+    };
+    let display_name = display_name_from_dir(&spec.dir_name);
+    let parsed = reader.read(&source, format, &display_name);
+    let meta = parsed.metadata;
+
+    let created = meta.created.as_deref().and_then(parse_date);
+    let updated = meta.updated.as_deref().and_then(parse_date).or(created);
+    let status = match status_fallback {
+        Some(fallback) if meta.status.is_none() => fallback.to_string(),
+        _ => reader.resolve_status(meta.status, false),
+    };
+    let visibility = reader.resolve_visibility(meta.visibility.as_deref(), &status);
+    let author_seeds = dossiers::authors::seeds_from_names(&meta.raw_authors);
+    let authors_meta = dossiers::authors::resolve_authors(&author_seeds, &reader.author_resolver());
+
+    SpecDocument {
+        id: spec.id.clone(),
+        dir_name: spec.dir_name.clone(),
+        title: meta.title.filter(|t| !t.is_empty()).unwrap_or(display_name),
+        status,
+        created,
+        updated,
+        authors: if authors_meta.is_empty() {
+            meta.authors
+        } else {
+            authors_meta
+                .iter()
+                .map(|author| author.name.clone())
+                .collect()
+        },
+        authors_meta,
+        author_seeds,
+        links: meta.links,
+        updated_sort: updated.or(created).unwrap_or_default(),
+        extra: metadata_extra_to_json(&meta.extra),
+        source: parsed.body,
+        format,
+        listed: true,
+        revision_of: None,
+        pr_number: None,
+        git_ref: None,
+        approved_by: Vec::new(),
+        last_commit: None,
+        visibility,
+        owners: Vec::new(),
+        teams: meta.teams,
+    }
+}
 
+/// Render every page of `state` into `output_dir`, replacing its contents.
+fn write_static_site(
+    mut state: AppState,
+    static_mounts: Vec<StaticMount>,
+    output_dir: &Path,
+    trailing_slashes: bool,
+) -> Result<()> {
     // Recalculate next_id now that PR specs have been added to spec_ids
     state.refresh_next_id();
 
@@ -1835,10 +3206,10 @@ fn run_build(
     });
 
     if output_dir.exists() {
-        fs::remove_dir_all(&output_dir)
+        fs::remove_dir_all(output_dir)
             .with_context(|| format!("Clearing output directory {}", output_dir.display()))?;
     }
-    fs::create_dir_all(&output_dir)
+    fs::create_dir_all(output_dir)
         .with_context(|| format!("Creating output directory {}", output_dir.display()))?;
 
     let mount_map: HashMap<String, PathBuf> = static_mounts.into_iter().collect();
@@ -1846,8 +3217,8 @@ fn run_build(
     let index_path = output_dir.join("index.html");
     let index_html = render_index(&state, "./", trailing_slashes).into_string();
     write_html_file(&index_path, index_html)?;
-    write_embedded_favicon(&output_dir)?;
-    write_mermaid_script(&output_dir, &state.assets.mermaid_script())?;
+    write_embedded_favicon(output_dir)?;
+    write_mermaid_script(output_dir, &state.assets.mermaid_script())?;
 
     for spec in &state.specs {
         let prefix = relative_prefix_for_spec_id(&spec.id);
@@ -1863,11 +3234,18 @@ fn run_build(
         write_html_file(&dest, page)?;
 
         let asset_paths = collect_doc_assets(&rendered_html, Some(&spec.id));
-        copy_doc_assets(&mount_map, &spec.id, &asset_paths, &output_dir)?;
+        copy_doc_assets(&mount_map, &spec.id, &asset_paths, output_dir)?;
     }
 
+    // Author pages list live specs and their previews, not frozen releases.
+    let authored_specs = || {
+        state
+            .specs
+            .iter()
+            .filter(|spec| !matches!(spec.git_ref, Some(RevisionSource::Tag(_))))
+    };
     let mut authors: HashMap<String, dossiers::bundle::Author> = HashMap::new();
-    for author in state.specs.iter().flat_map(|spec| spec.authors_meta.iter()) {
+    for author in authored_specs().flat_map(|spec| spec.authors_meta.iter()) {
         let slug = slugify_author(&author.name);
         let entry = authors.entry(slug).or_insert_with(|| author.clone());
         // Prefer an identity that carries an avatar for the heading.
@@ -1877,9 +3255,7 @@ fn run_build(
     }
 
     for (slug, author) in authors {
-        let authored: Vec<&SpecDocument> = state
-            .specs
-            .iter()
+        let authored: Vec<&SpecDocument> = authored_specs()
             .filter(|spec| {
                 spec.authors_meta
                     .iter()
@@ -1892,6 +3268,13 @@ fn run_build(
         write_html_file(&dest, page)?;
     }
 
+    for team in state.team_summaries() {
+        let matching = state.team_specs(&team.slug);
+        let page = render_team(&state, &team, &matching, "../../", trailing_slashes).into_string();
+        let dest = output_dir.join("team").join(&team.slug).join("index.html");
+        write_html_file(&dest, page)?;
+    }
+
     let status_summaries = collect_status_summaries(&state.specs);
     let status_index = render_status_index(&state, &status_summaries, "../").into_string();
     let status_index_path = output_dir.join("status").join("index.html");
@@ -1963,6 +3346,10 @@ struct SyncState {
     /// PR number (as a string) → head commit last synced. `None` on a server
     /// that predates incremental sync.
     pr_heads: Option<HashMap<String, String>>,
+    /// Whether the server resolves `unchanged` manifest entries from content it
+    /// already holds. Older servers need every file of a changed spec re-sent.
+    #[serde(default)]
+    unchanged_content: bool,
 }
 
 /// Fetch the server's synced state. `None` on any error — offline, auth failure,
@@ -1981,32 +3368,15 @@ fn fetch_sync_state(
     response.json().ok()
 }
 
-/// Every open PR's head SHA keyed by PR number, resolving the GitHub repo the
-/// same way PR gathering does. Empty when PRs would be skipped anyway (no
-/// `GITHUB_TOKEN` / no repo) or on failure — matching what a full push carries.
+/// Every open PR's head SHA keyed by PR number, resolving the forge the same
+/// way PR gathering does. Empty when PRs would be skipped anyway (no token / no
+/// repo) or on failure — matching what a full push carries.
 fn open_pull_heads(
     spec_root: &Path,
     project_config: &ProjectConfiguration,
 ) -> HashMap<u64, String> {
-    let token = match env::var("GITHUB_TOKEN") {
-        Ok(value) if !value.trim().is_empty() => value,
-        _ => return HashMap::new(),
-    };
     // Discover from the spec directory (inside the repo), matching PR gathering.
-    let git_repo = open_git_repository(spec_root);
-    let repo_from_config = project_config
-        .repository
-        .as_deref()
-        .and_then(parse_github_repo);
-    let repo_from_git = git_repo
-        .as_ref()
-        .and_then(|repo| repo.remote_url())
-        .as_deref()
-        .and_then(parse_github_repo);
-    let Some(github_repo) = repo_from_config.or(repo_from_git) else {
-        return HashMap::new();
-    };
-    let Ok(client) = GithubClient::new(github_repo, &token) else {
+    let Ok(client) = connect_forge(spec_root, project_config) else {
         return HashMap::new();
     };
     let Ok(pulls) = client.list_open_pulls() else {
@@ -2019,10 +3389,10 @@ fn open_pull_heads(
         .collect()
 }
 
-/// Outcome of attempting an incremental push.
+/// Outcome of preparing an incremental push.
 enum DeltaOutcome {
-    /// A delta was pushed; carries the server's response counts.
-    Pushed(PushSyncResponse),
+    /// A delta package is ready to send.
+    Ready(Box<DeltaPush>),
     /// Nothing changed since the last sync — no push needed.
     UpToDate,
     /// A delta wasn't possible; the `String` says why (shown to the user), and
@@ -2030,12 +3400,19 @@ enum DeltaOutcome {
     Fallback(String),
 }
 
-/// Try to push only what changed since the server's last-synced ref for this
-/// branch: mainline specs added/modified/deleted via `git diff base..HEAD`, and
-/// PRs whose head moved (plus deletes for closed PRs). Returns [`DeltaOutcome`];
-/// the caller does a full sync on `Fallback`.
+/// An encoded delta package and the plan describing it.
+struct DeltaPush {
+    package: dossiers::bundle::Package,
+    zip_bytes: Vec<u8>,
+    plan: PushPlan,
+}
+
+/// Prepare a push of only what changed since the server's last-synced ref for
+/// this branch: mainline specs added/modified/deleted via `git diff base..HEAD`,
+/// and PRs whose head moved (plus deletes for closed PRs). Returns
+/// [`DeltaOutcome`]; the caller does a full sync on `Fallback`.
 #[allow(clippy::too_many_arguments)]
-fn try_delta_push(
+fn prepare_delta(
     client: &reqwest::blocking::Client,
     api_url: &str,
     project: &str,
@@ -2047,7 +3424,9 @@ fn try_delta_push(
     branch: Option<&str>,
     commit: Option<&str>,
     no_prs: bool,
-    timeout_secs: u64,
+    target: Option<&PushTarget>,
+    audience: Visibility,
+    signing_key: Option<&SigningKey>,
 ) -> Result<DeltaOutcome> {
     // Cheap local checks first, before any network round-trip. Discover git
     // from the spec directory (inside the repo), not `project_root` — with an
@@ -2124,22 +3503,12 @@ fn try_delta_push(
     let config_rel =
         resolve_config_path(project_root, config_path).and_then(|c| relative_to(&c, workdir));
 
-    let mut touched: HashSet<String> = HashSet::new();
-    for path in &changed {
-        if config_rel.as_deref() == Some(path.as_path()) {
-            return Ok(DeltaOutcome::Fallback(
-                "project config changed (affects all specs)".into(),
-            ));
-        }
-        if let Ok(rest) = path.strip_prefix(&spec_rel) {
-            if let Some(first) = rest.components().next() {
-                let name = first.as_os_str().to_string_lossy();
-                if let Some(id) = extract_spec_id(&name) {
-                    touched.insert(id);
-                }
-            }
-        }
+    if config_rel.is_some_and(|config| changed.contains(&config)) {
+        return Ok(DeltaOutcome::Fallback(
+            "project config changed (affects all specs)".into(),
+        ));
     }
+    let touched = touched_spec_ids(&changed, &spec_rel);
 
     // Scan the working tree once (cheap filesystem reads) to split touched specs
     // into upserts (still present) vs deletes (gone), and to source content.
@@ -2199,7 +3568,18 @@ fn try_delta_push(
     // with the explicit deletes and PR changes recorded in the manifest.
     let mut mainline = full_mainline;
     mainline.specs.retain(|s| upsert_ids.contains(&s.id));
-    let specs_index = build_spec_index(
+    let existed_at_base: HashSet<String> = mainline
+        .specs
+        .iter()
+        .filter(|spec| spec_existed_at(&repo, &base, &spec_rel, spec))
+        .map(|spec| spec.id.clone())
+        .collect();
+    let unchanged = if state.unchanged_content {
+        omit_unchanged_assets(&repo, &base, &spec_rel, &mut mainline)
+    } else {
+        Default::default()
+    };
+    let specs_index = build_spec_index(
         &resolved_input,
         project_config,
         &mainline,
@@ -2209,7 +3589,7 @@ fn try_delta_push(
     let project_config_bytes =
         resolve_config_path(project_root, config_path).and_then(|p| fs::read(&p).ok());
 
-    let package = dossiers::bundle::Package {
+    let mut package = dossiers::bundle::Package {
         manifest: dossiers::bundle::Manifest {
             package_version: dossiers::bundle::PACKAGE_VERSION,
             commit: empty_to_none(resolved_commit),
@@ -2220,17 +3600,25 @@ fn try_delta_push(
             base_commit: Some(base),
             deleted_specs,
             deleted_prs,
+            digests: Default::default(),
+            unchanged,
         },
         project_config: project_config_bytes,
         mainline,
         pr_changes: changed_prs,
     };
+    // A spec the target no longer publishes (say its status moved away from
+    // an allowed one, or it became private) is deleted there, as is a PR left
+    // with nothing to show.
+    let (dropped_specs, dropped_prs) = filter_package(&mut package, target, audience);
+    package.manifest.deleted_specs.extend(dropped_specs);
+    package.manifest.deleted_prs.extend(
+        dropped_prs
+            .into_iter()
+            .filter(|number| server_pr_heads.contains_key(number)),
+    );
 
-    let mut buf = std::io::Cursor::new(Vec::new());
-    package
-        .write_zip(&mut buf)
-        .context("writing delta package")?;
-    let zip_bytes = buf.into_inner();
+    let zip_bytes = encode_package(&package, signing_key).context("writing delta package")?;
 
     // A delta big enough to exceed the single-request limit is rare; rather than
     // chunk a delta (which the server doesn't apply), fall back to a full sync.
@@ -2241,23 +3629,428 @@ fn try_delta_push(
         )));
     }
 
-    let sync_url = format!("{api_url}/api/v1/projects/{project}/sync");
-    println!(
-        "Incremental sync to {sync_url}: {} spec(s) changed, {} deleted, \
-         {} PR change-set(s), {} PR(s) closed ({} bytes)",
-        package.mainline.specs.len(),
-        package.manifest.deleted_specs.len(),
-        package.pr_changes.len(),
-        package.manifest.deleted_prs.len(),
-        zip_bytes.len(),
-    );
+    let mut plan = PushPlan {
+        base: package.manifest.base_commit.clone(),
+        deleted: package.manifest.deleted_specs.clone(),
+        prs_closed: package.manifest.deleted_prs.clone(),
+        assets_unchanged: package.manifest.unchanged.len(),
+        bytes: zip_bytes.len(),
+        ..PushPlan::default()
+    };
+    for entry in &package.manifest.specs {
+        let line = (entry.id.clone(), entry.title.clone());
+        if existed_at_base.contains(&entry.id) {
+            plan.modified.push(line);
+        } else {
+            plan.added.push(line);
+        }
+    }
+    for pr in &package.pr_changes {
+        if server_pr_heads.contains_key(&pr.pr_number) {
+            plan.prs_updated.push(pr.pr_number);
+        } else {
+            plan.prs_opened.push(pr.pr_number);
+        }
+    }
+    plan.assets_sent = package.mainline.specs.iter().map(|s| s.assets.len()).sum();
+
+    Ok(DeltaOutcome::Ready(Box::new(DeltaPush {
+        package,
+        zip_bytes,
+        plan,
+    })))
+}
+
+/// Drop what a package's readers may not see: mainline specs more restricted
+/// than `audience` or not admitted by the push target, and PR revisions of
+/// such specs. Returns the ids of the dropped specs and the numbers of PRs
+/// left with nothing to publish.
+fn filter_package(
+    package: &mut dossiers::bundle::Package,
+    target: Option<&PushTarget>,
+    audience: Visibility,
+) -> (Vec<String>, Vec<u64>) {
+    use dossiers::bundle::{AssetChange, SpecChange};
+    let target = target.filter(|target| target.is_filtered());
+    let admits = |id: &str, status: &str, visibility: Option<&str>| {
+        Visibility::from_wire(visibility) <= audience
+            && target.is_none_or(|target| target.admits(id, status))
+    };
+    let mainline: HashMap<String, (String, Option<String>)> = package
+        .manifest
+        .specs
+        .iter()
+        .map(|entry| {
+            (
+                entry.id.clone(),
+                (entry.status.clone(), entry.visibility.clone()),
+            )
+        })
+        .collect();
+
+    let mut dropped_specs = Vec::new();
+    package.manifest.specs.retain(|entry| {
+        let admitted = admits(&entry.id, &entry.status, entry.visibility.as_deref());
+        if !admitted {
+            dropped_specs.push(entry.id.clone());
+        }
+        admitted
+    });
+    package
+        .mainline
+        .specs
+        .retain(|spec| !dropped_specs.contains(&spec.id));
+    let kept_dirs: HashSet<&str> = package
+        .mainline
+        .specs
+        .iter()
+        .map(|spec| spec.dir_name.as_str())
+        .collect();
+    package.manifest.unchanged.retain(|path, _| {
+        path.strip_prefix("main/")
+            .and_then(|rest| rest.split('/').next())
+            .is_some_and(|dir| kept_dirs.contains(dir))
+    });
+
+    let mut dropped_prs = Vec::new();
+    package.pr_changes.retain_mut(|pr| {
+        // A PR's own metadata says what the spec would become; specs it only
+        // touches through assets fall back to their mainline status.
+        let admitted_in_pr = |id: &str| {
+            let (status, visibility) = pr
+                .spec_meta
+                .iter()
+                .find(|meta| meta.spec_id == id)
+                .map(|meta| (meta.status.clone(), meta.visibility.clone()))
+                .or_else(|| mainline.get(id).cloned())
+                .unwrap_or_default();
+            admits(id, &status, visibility.as_deref())
+        };
+        let ids: HashSet<String> = pr
+            .spec_changes
+            .iter()
+            .map(|change| match change {
+                SpecChange::Upsert(spec) => spec.id.clone(),
+                SpecChange::Remove { id } => id.clone(),
+            })
+            .chain(pr.asset_changes.iter().map(|change| match change {
+                AssetChange::Upsert { spec_id, .. } | AssetChange::Remove { spec_id, .. } => {
+                    spec_id.clone()
+                }
+            }))
+            .collect();
+        let admitted: HashSet<String> = ids.into_iter().filter(|id| admitted_in_pr(id)).collect();
+        pr.spec_changes.retain(|change| match change {
+            SpecChange::Upsert(spec) => admitted.contains(&spec.id),
+            SpecChange::Remove { id } => admitted.contains(id),
+        });
+        pr.asset_changes.retain(|change| match change {
+            AssetChange::Upsert { spec_id, .. } | AssetChange::Remove { spec_id, .. } => {
+                admitted.contains(spec_id)
+            }
+        });
+        pr.spec_meta.retain(|meta| admitted.contains(&meta.spec_id));
+        let keep = !(pr.spec_changes.is_empty() && pr.asset_changes.is_empty());
+        if !keep {
+            dropped_prs.push(pr.pr_number);
+        }
+        keep
+    });
+
+    (dropped_specs, dropped_prs)
+}
+
+/// Ids of the specs with a file among `changed` under `spec_rel`.
+fn touched_spec_ids(changed: &HashSet<PathBuf>, spec_rel: &Path) -> HashSet<String> {
+    changed
+        .iter()
+        .filter_map(|path| path.strip_prefix(spec_rel).ok())
+        .filter_map(|rest| rest.components().next())
+        .filter_map(|first| extract_spec_id(&first.as_os_str().to_string_lossy()))
+        .collect()
+}
+
+/// Whether `spec`'s source file was committed at `base`, i.e. whether pushing
+/// it changes an existing spec rather than adding one.
+fn spec_existed_at(
+    repo: &GitRepository,
+    base: &str,
+    spec_rel: &Path,
+    spec: &dossiers::bundle::Spec,
+) -> bool {
+    let path = spec_rel.join(&spec.dir_name).join(&spec.source_path);
+    repo.read_file_at(base, &path).is_some()
+}
+
+/// What a push will change on the server, printed by `push --plan` and before
+/// `push --confirm` asks to go ahead.
+#[derive(Debug, Default)]
+struct PushPlan {
+    /// The server's last-synced commit the changes are measured against;
+    /// `None` when it is unknown.
+    base: Option<String>,
+    /// Why a full snapshot is sent; `None` for a delta. A full snapshot also
+    /// deletes whatever the server holds that it doesn't carry.
+    full_reason: Option<String>,
+    /// `(id, title)` of specs new since `base`.
+    added: Vec<(String, String)>,
+    /// `(id, title)` of specs changed since `base`.
+    modified: Vec<(String, String)>,
+    /// `(id, title)` of specs a full snapshot sends again without a known
+    /// change (every spec, when `base` is unknown).
+    resent: Vec<(String, String)>,
+    deleted: Vec<String>,
+    prs_opened: Vec<u64>,
+    prs_updated: Vec<u64>,
+    /// PRs a full snapshot sends again with the head the server already has.
+    prs_resent: Vec<u64>,
+    prs_closed: Vec<u64>,
+    assets_sent: usize,
+    /// Assets left out because the server already holds identical content.
+    assets_unchanged: usize,
+    bytes: usize,
+}
+
+impl PushPlan {
+    fn render(&self, sync_url: &str) -> String {
+        let mut out = String::new();
+        let short = |sha: &str| sha.chars().take(7).collect::<String>();
+        match (&self.full_reason, &self.base) {
+            (None, Some(base)) => {
+                out.push_str(&format!(
+                    "Push plan for {sync_url} (delta against {}):\n",
+                    short(base)
+                ));
+            }
+            (Some(reason), Some(base)) => {
+                out.push_str(&format!(
+                    "Push plan for {sync_url} (full snapshot, {reason}; changes since {}):\n",
+                    short(base)
+                ));
+            }
+            (reason, None) => {
+                out.push_str(&format!(
+                    "Push plan for {sync_url} (full snapshot, {}; server state unknown):\n",
+                    reason.as_deref().unwrap_or("no previous sync")
+                ));
+            }
+        }
+        out.push('\n');
+
+        let mut lines = Vec::new();
+        for (id, title) in &self.added {
+            lines.push(format!("  + spec {id}  {title}"));
+        }
+        for (id, title) in &self.modified {
+            lines.push(format!("  ~ spec {id}  {title}"));
+        }
+        for id in &self.deleted {
+            lines.push(format!("  - spec {id}"));
+        }
+        if !self.resent.is_empty() {
+            lines.push(format!(
+                "  = {} spec(s) sent again{}",
+                self.resent.len(),
+                if self.base.is_some() {
+                    " unchanged"
+                } else {
+                    ""
+                }
+            ));
+        }
+        for number in &self.prs_opened {
+            lines.push(format!("  + PR #{number}"));
+        }
+        for number in &self.prs_updated {
+            lines.push(format!("  ~ PR #{number}"));
+        }
+        for number in &self.prs_closed {
+            lines.push(format!("  - PR #{number}"));
+        }
+        if !self.prs_resent.is_empty() {
+            lines.push(format!(
+                "  = {} PR revision(s) sent again unchanged",
+                self.prs_resent.len()
+            ));
+        }
+        if lines.is_empty() {
+            lines.push("  (no spec or PR changes)".to_string());
+        }
+        for line in lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push('\n');
+
+        out.push_str(&format!(
+            "Plan: {} to add, {} to change, {} to delete; PR revisions: {} opened, {} updated, {} closed.\n",
+            self.added.len(),
+            self.modified.len(),
+            self.deleted.len(),
+            self.prs_opened.len(),
+            self.prs_updated.len(),
+            self.prs_closed.len()
+        ));
+        out.push_str(&format!(
+            "Sends {} asset(s){} in {}{}.\n",
+            self.assets_sent,
+            if self.assets_unchanged > 0 {
+                format!(
+                    " ({} unchanged, reused from the server)",
+                    self.assets_unchanged
+                )
+            } else {
+                String::new()
+            },
+            format_bytes(self.bytes as u64),
+            if self.bytes > SINGLE_SHOT_MAX_BYTES {
+                ", split into chunks"
+            } else {
+                ""
+            }
+        ));
+        if self.full_reason.is_some() {
+            out.push_str(
+                "A full snapshot replaces the server's content: anything it holds that is \
+                 not in this push is deleted.\n",
+            );
+        }
+        out
+    }
+}
+
+/// Plan a full-snapshot push, measuring it against the server's last-synced
+/// commit for the package's branch when that commit is in local history.
+fn plan_full_push(
+    state: Option<&SyncState>,
+    input_path: &Path,
+    project_config: &ProjectConfiguration,
+    package: &dossiers::bundle::Package,
+    zip_len: usize,
+    reason: String,
+) -> PushPlan {
+    let mut plan = PushPlan {
+        full_reason: Some(reason),
+        assets_sent: package.mainline.specs.iter().map(|s| s.assets.len()).sum(),
+        bytes: zip_len,
+        ..PushPlan::default()
+    };
+    let titles: HashMap<&str, &str> = package
+        .manifest
+        .specs
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry.title.as_str()))
+        .collect();
+    let line = |spec: &dossiers::bundle::Spec| {
+        let title = titles.get(spec.id.as_str()).copied().unwrap_or_default();
+        (spec.id.clone(), title.to_string())
+    };
+
+    let resolved_input = resolve_spec_input_path(input_path, project_config);
+    let repo = open_git_repository(&resolved_input);
+    let since_base = state
+        .zip(package.manifest.branch.as_ref())
+        .and_then(|(state, branch)| state.branches.get(branch))
+        .zip(repo.as_ref())
+        .filter(|(base, repo)| repo.has_commit(base))
+        .and_then(|(base, repo)| {
+            let spec_rel = relative_to(&resolved_input, repo.workdir())?;
+            let changed = repo.changed_paths_since(base)?;
+            Some((base.clone(), repo, spec_rel, changed))
+        });
+    match since_base {
+        Some((base, repo, spec_rel, changed)) => {
+            let touched = touched_spec_ids(&changed, &spec_rel);
+            let current: HashSet<&str> = package
+                .mainline
+                .specs
+                .iter()
+                .map(|spec| spec.id.as_str())
+                .collect();
+            for spec in &package.mainline.specs {
+                if !spec_existed_at(repo, &base, &spec_rel, spec) {
+                    plan.added.push(line(spec));
+                } else if touched.contains(&spec.id) {
+                    plan.modified.push(line(spec));
+                } else {
+                    plan.resent.push(line(spec));
+                }
+            }
+            plan.deleted = touched
+                .into_iter()
+                .filter(|id| !current.contains(id.as_str()))
+                .collect();
+            plan.deleted.sort();
+            plan.base = Some(base);
+        }
+        None => plan.resent = package.mainline.specs.iter().map(line).collect(),
+    }
 
-    match post_delta(client, &sync_url, token, zip_bytes, timeout_secs)? {
-        Some(resp) => Ok(DeltaOutcome::Pushed(resp)),
-        None => Ok(DeltaOutcome::Fallback(
-            "server rejected the delta (sync base out of date)".into(),
-        )),
+    match state.and_then(|state| state.pr_heads.as_ref()) {
+        Some(heads) => {
+            for pr in &package.pr_changes {
+                match heads.get(&pr.pr_number.to_string()) {
+                    None => plan.prs_opened.push(pr.pr_number),
+                    Some(head) if *head != pr.head_sha => plan.prs_updated.push(pr.pr_number),
+                    Some(_) => plan.prs_resent.push(pr.pr_number),
+                }
+            }
+            let sent: HashSet<u64> = package.pr_changes.iter().map(|pr| pr.pr_number).collect();
+            plan.prs_closed = heads
+                .keys()
+                .filter_map(|number| number.parse::<u64>().ok())
+                .filter(|number| !sent.contains(number))
+                .collect();
+            plan.prs_closed.sort_unstable();
+        }
+        None => plan.prs_opened = package.pr_changes.iter().map(|pr| pr.pr_number).collect(),
+    }
+    plan
+}
+
+/// Ask on the terminal whether to go ahead with a push; anything but `y` or
+/// `yes` declines.
+fn confirm_push() -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+    if !std::io::stdin().is_terminal() {
+        bail!("--confirm needs an interactive terminal; use --plan to review a push without one");
+    }
+    print!("Push these changes? [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Drop assets of the delta's upserted specs that are byte-identical to their
+/// committed copy at `base`, returning them as `unchanged` manifest entries
+/// (in-zip path → digest) so the server reuses the content it already holds.
+fn omit_unchanged_assets(
+    repo: &GitRepository,
+    base: &str,
+    spec_rel: &Path,
+    mainline: &mut dossiers::bundle::Mainline,
+) -> BTreeMap<String, String> {
+    let mut unchanged = BTreeMap::new();
+    for spec in &mut mainline.specs {
+        spec.assets.retain(|asset| {
+            let path = spec_rel.join(&spec.dir_name).join(&asset.path);
+            let same = repo
+                .read_file_at(base, &path)
+                .is_some_and(|committed| committed == asset.bytes);
+            if same {
+                unchanged.insert(
+                    format!("main/{}/{}", spec.dir_name, asset.path),
+                    dossiers::bundle::digest(&asset.bytes),
+                );
+            }
+            !same
+        });
     }
+    unchanged
 }
 
 /// POST a delta package. `Ok(Some(_))` on success, `Ok(None)` on a 409 (the
@@ -2309,17 +4102,58 @@ fn run_push(
     api_url: Option<String>,
     project: Option<String>,
     token: Option<String>,
+    target: Option<String>,
     branch: Option<String>,
     commit: Option<String>,
     no_prs: bool,
+    offline: bool,
+    audience: Option<Visibility>,
     timeout: Option<u64>,
+    retries: Option<u32>,
     dry_run: bool,
+    plan: bool,
+    confirm: bool,
     full: bool,
 ) -> Result<()> {
     let project_root = project_root_from(config_path.as_deref(), &input_path);
-    let project_config = load_project_configuration(&project_root, config_path.as_deref());
+    let mut project_config = load_project_configuration(&project_root, config_path.as_deref());
+    project_config.offline |= offline;
+
+    // A named target's settings sit between the command line and the ambient
+    // environment: `--target public` means that target's URL and project even
+    // when $DOSSIERS_API_URL points elsewhere.
+    let push_target = match target.or_else(|| {
+        env::var("DOSSIERS_TARGET")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+    }) {
+        Some(name) => Some(
+            project_config
+                .push_targets
+                .iter()
+                .find(|t| t.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    let names: Vec<&str> = project_config
+                        .push_targets
+                        .iter()
+                        .map(|t| t.name.as_str())
+                        .collect();
+                    anyhow!(
+                        "unknown push target '{name}' (configured: {})",
+                        if names.is_empty() {
+                            "none; add [push.targets.<name>] to dossiers.toml".to_string()
+                        } else {
+                            names.join(", ")
+                        }
+                    )
+                })?,
+        ),
+        None => None,
+    };
 
     let api_url_resolved = api_url
+        .or_else(|| push_target.as_ref().and_then(|t| t.api_url.clone()))
         .or_else(|| {
             env::var("DOSSIERS_API_URL")
                 .ok()
@@ -2330,6 +4164,7 @@ fn run_push(
     let api_url_trimmed = api_url_resolved.trim_end_matches('/').to_string();
 
     let project_resolved = project
+        .or_else(|| push_target.as_ref().and_then(|t| t.project.clone()))
         .or_else(|| env::var("DOSSIERS_PROJECT").ok().filter(|s| !s.trim().is_empty()))
         .or_else(|| project_config.push_project.clone())
         .ok_or_else(|| {
@@ -2338,13 +4173,32 @@ fn run_push(
             )
         })?;
 
-    let token_resolved = token.or_else(|| {
-        env::var("DOSSIERS_TOKEN")
-            .ok()
-            .filter(|s| !s.trim().is_empty())
-    });
+    // A target with its own token variable never falls back to $DOSSIERS_TOKEN,
+    // so one environment's token is not sent to another's server.
+    let token_env = push_target
+        .as_ref()
+        .and_then(|t| t.token_env.clone())
+        .unwrap_or_else(|| "DOSSIERS_TOKEN".to_string());
+    let token_resolved =
+        token.or_else(|| env::var(&token_env).ok().filter(|s| !s.trim().is_empty()));
     if !dry_run && token_resolved.is_none() {
-        bail!("project token is required (use --token or $DOSSIERS_TOKEN)");
+        bail!("project token is required (use --token or ${token_env})");
+    }
+
+    let no_prs = no_prs
+        || !push_target
+            .as_ref()
+            .and_then(|t| t.include_prs)
+            .or(project_config.push_include_prs)
+            .unwrap_or(true);
+    let audience = audience
+        .or_else(|| push_target.as_ref().and_then(|t| t.audience))
+        .unwrap_or_else(|| project_config.visibility.audience());
+    if let Some(target) = &push_target {
+        println!(
+            "Push target '{}': project {project_resolved} at {api_url_trimmed}",
+            target.name
+        );
     }
 
     // Bound the request so a stalled or unresponsive server fails with a clear
@@ -2357,20 +4211,35 @@ fn run_push(
                 .and_then(|s| s.trim().parse::<u64>().ok())
         })
         .unwrap_or(300);
+    let retries = retries
+        .or_else(|| {
+            env::var("DOSSIERS_RETRIES")
+                .ok()
+                .and_then(|s| s.trim().parse::<u32>().ok())
+        })
+        .unwrap_or(4);
     let mut builder = reqwest::blocking::Client::builder().connect_timeout(Duration::from_secs(30));
     if timeout_secs > 0 {
         builder = builder.timeout(Duration::from_secs(timeout_secs));
     }
     let client = builder.build().context("building HTTP client")?;
+    let signing_key = resolve_signing_key(&project_root, &project_config)?;
+
+    let sync_url = format!(
+        "{api_url}/api/v1/projects/{project}/sync",
+        api_url = api_url_trimmed,
+        project = project_resolved
+    );
 
     // Incremental fast path: push only what changed since the server's last
     // synced ref for this branch. Falls through to a full sync when a delta
     // isn't possible (first sync, older server, base lost, config change,
     // oversize, or a 409 base mismatch). `--full` opts out entirely and always
     // sends a full snapshot.
+    let mut full_reason = "--full".to_string();
     if !dry_run && !full {
         let token = token_resolved.as_deref().unwrap_or("");
-        match try_delta_push(
+        match prepare_delta(
             &client,
             &api_url_trimmed,
             &project_resolved,
@@ -2382,14 +4251,44 @@ fn run_push(
             branch.as_deref(),
             commit.as_deref(),
             no_prs,
-            timeout_secs,
+            push_target.as_ref(),
+            audience,
+            signing_key.as_ref(),
         )? {
-            DeltaOutcome::Pushed(resp) => {
+            DeltaOutcome::Ready(delta) => {
+                if plan || confirm {
+                    print!("{}", delta.plan.render(&sync_url));
+                    if plan || !confirm_push()? {
+                        if confirm {
+                            println!("Push cancelled.");
+                        }
+                        return Ok(());
+                    }
+                }
                 println!(
-                    "Synced incrementally: {} created, {} updated, {} deleted, {} PR revision(s)",
-                    resp.created, resp.updated, resp.deleted, resp.pr_revisions
+                    "Incremental sync to {sync_url}: {} spec(s) changed, {} deleted, \
+                     {} PR change-set(s), {} PR(s) closed ({} bytes)",
+                    delta.package.mainline.specs.len(),
+                    delta.package.manifest.deleted_specs.len(),
+                    delta.package.pr_changes.len(),
+                    delta.package.manifest.deleted_prs.len(),
+                    delta.zip_bytes.len(),
                 );
-                return Ok(());
+                match post_delta(&client, &sync_url, token, delta.zip_bytes, timeout_secs)? {
+                    Some(resp) => {
+                        println!(
+                            "Synced incrementally: {} created, {} updated, {} deleted, {} PR revision(s)",
+                            resp.created, resp.updated, resp.deleted, resp.pr_revisions
+                        );
+                        return Ok(());
+                    }
+                    None => {
+                        full_reason = "server rejected the delta (sync base out of date)".into();
+                        eprintln!(
+                            "Incremental sync unavailable ({full_reason}); doing a full sync."
+                        );
+                    }
+                }
             }
             DeltaOutcome::UpToDate => {
                 println!(
@@ -2399,6 +4298,7 @@ fn run_push(
             }
             DeltaOutcome::Fallback(reason) => {
                 eprintln!("Incremental sync unavailable ({reason}); doing a full sync.");
+                full_reason = reason;
             }
         }
     }
@@ -2411,6 +4311,9 @@ fn run_push(
         branch,
         commit,
         no_prs,
+        push_target.as_ref(),
+        audience,
+        signing_key.as_ref(),
     )?;
 
     let total_assets: usize = package.mainline.specs.iter().map(|s| s.assets.len()).sum();
@@ -2432,18 +4335,39 @@ fn run_push(
         return Ok(());
     }
 
-    let sync_url = format!(
-        "{api_url}/api/v1/projects/{project}/sync",
-        api_url = api_url_trimmed,
-        project = project_resolved
-    );
-    println!("Pushing to {sync_url}");
-
     let token = token_resolved.as_deref().unwrap_or("");
+    if plan || confirm {
+        let state = fetch_sync_state(&client, &api_url_trimmed, &project_resolved, token);
+        let push_plan = plan_full_push(
+            state.as_ref(),
+            &input_path,
+            &project_config,
+            &package,
+            zip_bytes.len(),
+            full_reason,
+        );
+        print!("{}", push_plan.render(&sync_url));
+        if plan || !confirm_push()? {
+            if confirm {
+                println!("Push cancelled.");
+            }
+            return Ok(());
+        }
+    }
+    println!("Pushing to {sync_url}");
 
     if zip_bytes.len() <= SINGLE_SHOT_MAX_BYTES {
         // Small enough for one request: push the whole snapshot as before.
-        let parsed = post_sync(&client, &sync_url, token, zip_bytes, "", timeout_secs)?;
+        let parsed = with_retries(retries, RETRY_BASE_DELAY, "push", || {
+            post_sync(
+                &client,
+                &sync_url,
+                token,
+                zip_bytes.clone().into(),
+                "",
+                timeout_secs,
+            )
+        })?;
         println!(
             "Synced {} spec(s): {} created, {} updated, {} deleted",
             parsed.synced, parsed.created, parsed.updated, parsed.deleted
@@ -2458,81 +4382,366 @@ fn run_push(
             zip_bytes.len(),
             total
         );
-        let run_id = format!(
-            "run-{}-{}",
-            Utc::now().timestamp_millis(),
-            std::process::id()
+        let fingerprints = chunks
+            .iter()
+            .map(dossiers::bundle::Package::fingerprint)
+            .collect::<Result<Vec<_>, _>>()
+            .context("fingerprinting chunks")?;
+        let encoded = chunks
+            .iter()
+            .map(|chunk| encode_package(chunk, signing_key.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .context("encoding chunk package")?;
+
+        // A previous push of the same chunks that stopped part-way resumes
+        // with its run id, as long as the server still holds what it sent.
+        let resume = PushResume::load(&sync_url, &fingerprints).filter(|resume| {
+            let held =
+                fetch_run_chunks(&client, &sync_url, token, &resume.run_id).is_some_and(|run| {
+                    !run.finalized && (0..resume.acknowledged).all(|i| run.chunks.contains(&i))
+                });
+            if !held {
+                println!(
+                    "Server no longer holds upload {}; starting it over.",
+                    resume.run_id
+                );
+            }
+            held
+        });
+        let mut resume = match resume {
+            Some(resume) => {
+                println!(
+                    "Resuming upload {}: {} of {total} chunk(s) already sent.",
+                    resume.run_id, resume.acknowledged
+                );
+                resume
+            }
+            None => PushResume {
+                run_id: format!(
+                    "run-{}-{}",
+                    Utc::now().timestamp_millis(),
+                    std::process::id()
+                ),
+                chunks: fingerprints,
+                acknowledged: 0,
+            },
+        };
+
+        let run_id = resume.run_id.clone();
+        let resumed_from = resume.acknowledged;
+        let mut progress = UploadProgress::new(
+            encoded.iter().map(Vec::len).sum::<usize>() as u64,
+            encoded[..resume.acknowledged]
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>() as u64,
         );
         let mut summary = PushSyncResponse::default();
-        for (i, chunk) in chunks.into_iter().enumerate() {
+        for (i, bytes) in encoded.into_iter().enumerate().skip(resume.acknowledged) {
             let is_final = i + 1 == total;
-            let mut buf = std::io::Cursor::new(Vec::new());
-            chunk
-                .write_zip(&mut buf)
-                .context("encoding chunk package")?;
-            let bytes = buf.into_inner();
-            eprintln!(
-                "  chunk {}/{} ({} bytes){}",
-                i + 1,
-                total,
-                bytes.len(),
-                if is_final { " [final]" } else { "" }
-            );
-            let query = format!("?run_id={run_id}&final={is_final}");
-            summary += post_sync(&client, &sync_url, token, bytes, &query, timeout_secs)?;
+            let query = format!("?run_id={run_id}&chunk={i}&final={is_final}");
+            let label = format!("chunk {}/{total}", i + 1);
+            let mut posted = false;
+            summary += with_retries(retries, RETRY_BASE_DELAY, &label, || {
+                // The final chunk replaces the server's snapshot. When a try
+                // fails without an answer the server may still have applied
+                // it, so ask before sending it again.
+                if is_final && std::mem::replace(&mut posted, true) {
+                    let run = fetch_run_chunks(&client, &sync_url, token, &run_id);
+                    if run.is_some_and(|run| run.finalized) {
+                        println!("  {label} was already applied by the server.");
+                        return Ok(PushSyncResponse::default());
+                    }
+                }
+                post_sync(
+                    &client,
+                    &sync_url,
+                    token,
+                    progress.body(&label, bytes.clone()),
+                    &query,
+                    timeout_secs,
+                )
+            })
+            .with_context(|| {
+                format!("{label} failed; run the same push again to resume from this chunk")
+            })?;
+            progress.sent(&label, bytes.len());
+            resume.acknowledged = i + 1;
+            if is_final {
+                PushResume::clear(&sync_url);
+            } else {
+                resume.save(&sync_url);
+            }
         }
         println!(
-            "Synced {} spec(s) across {} chunk(s): {} created, {} updated, {} deleted",
-            summary.synced, total, summary.created, summary.updated, summary.deleted
+            "Synced {} spec(s) across {} chunk(s){}: {} created, {} updated, {} deleted",
+            summary.synced,
+            total - resumed_from,
+            if resumed_from > 0 {
+                " of the resumed upload"
+            } else {
+                ""
+            },
+            summary.created,
+            summary.updated,
+            summary.deleted
         );
     }
 
     Ok(())
 }
 
-/// Full-package size (compressed zip bytes) at or below which a push is sent as
-/// a single request. Above this the package is chunked to stay under the
-/// serverless request-body limit (Vercel caps function payloads at ~4.5 MB).
-const SINGLE_SHOT_MAX_BYTES: usize = 4 * 1024 * 1024;
+/// Delay before the first retry of a failed push request; each further retry
+/// doubles it, up to [`MAX_RETRY_DELAY`].
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
 
-/// Target raw (uncompressed) content per chunk. Kept well under the request
-/// limit so even incompressible (binary) chunks fit once zipped.
-const MAX_CHUNK_RAW_BYTES: usize = 3 * 1024 * 1024;
+/// A push request failure worth retrying: the request timed out or never got
+/// through, or the server answered 429 or 5xx.
+#[derive(Debug, Error)]
+#[error("{0}")]
+struct TransientPushError(String);
+
+/// Run `attempt`, retrying transient failures up to `retries` times with
+/// exponential backoff. Any other error is returned straight away.
+fn with_retries<T>(
+    retries: u32,
+    base_delay: Duration,
+    label: &str,
+    mut attempt: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut retry = 0;
+    loop {
+        match attempt() {
+            Err(err) if retry < retries && err.downcast_ref::<TransientPushError>().is_some() => {
+                let delay = backoff_delay(base_delay, retry);
+                retry += 1;
+                eprintln!(
+                    "  {label} failed: {err}; retrying in {:.1}s ({retry}/{retries})",
+                    delay.as_secs_f64()
+                );
+                std::thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
 
-/// POST one package zip to the sync endpoint (optionally with chunk query
-/// params) and parse the response.
-fn post_sync(
+fn backoff_delay(base_delay: Duration, retry: u32) -> Duration {
+    base_delay
+        .saturating_mul(1 << retry.min(16))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Progress of a chunked push, saved after each acknowledged chunk so that
+/// running the same push again resumes instead of starting over. The run id
+/// is the resume token the server buffers chunks under.
+#[derive(Debug, Serialize, Deserialize)]
+struct PushResume {
+    run_id: String,
+    /// Fingerprint of every chunk; a resume needs the same chunks.
+    chunks: Vec<String>,
+    acknowledged: usize,
+}
+
+impl PushResume {
+    fn path(sync_url: &str) -> PathBuf {
+        let key = dossiers::bundle::digest(sync_url.as_bytes());
+        env::temp_dir().join(format!("dossiers-push-{}.json", &key[7..23]))
+    }
+
+    fn load(sync_url: &str, chunks: &[String]) -> Option<Self> {
+        let bytes = fs::read(Self::path(sync_url)).ok()?;
+        serde_json::from_slice::<Self>(&bytes)
+            .ok()
+            .filter(|resume| resume.chunks == chunks && resume.acknowledged < chunks.len())
+    }
+
+    /// Best effort: failing to record progress only costs a resume.
+    fn save(&self, sync_url: &str) {
+        if let Ok(bytes) = serde_json::to_vec(self) {
+            let _ = fs::write(Self::path(sync_url), bytes);
+        }
+    }
+
+    fn clear(sync_url: &str) {
+        let _ = fs::remove_file(Self::path(sync_url));
+    }
+}
+
+/// What the server holds for a chunked run, from `GET .../sync/runs/{run_id}`.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct RunStatus {
+    /// Chunk indices buffered for an unfinished run.
+    chunks: Vec<usize>,
+    /// Whether the run's final chunk has been applied.
+    #[serde(default)]
+    finalized: bool,
+}
+
+/// The server's view of a chunked run. `None` when the run is unknown or the
+/// server cannot say, in which case the push starts a new run.
+fn fetch_run_chunks(
     client: &reqwest::blocking::Client,
     sync_url: &str,
     token: &str,
-    zip_bytes: Vec<u8>,
-    query: &str,
-    timeout_secs: u64,
-) -> Result<PushSyncResponse> {
-    let url = format!("{sync_url}{query}");
+    run_id: &str,
+) -> Option<RunStatus> {
     let response = client
-        .post(&url)
+        .get(format!("{sync_url}/runs/{run_id}"))
         .bearer_auth(token)
-        .header(reqwest::header::CONTENT_TYPE, "application/zip")
-        .body(zip_bytes)
         .send()
-        .map_err(|err| {
-            if err.is_timeout() {
-                anyhow!(
-                    "push to {sync_url} timed out after {timeout_secs}s. The server may still be \
-                     processing a large package; retry, raise --timeout, or check the server."
-                )
-            } else if err.is_connect() {
-                anyhow!("could not connect to {sync_url}: {err}")
-            } else {
-                anyhow::Error::new(err).context(format!("sending sync request to {sync_url}"))
-            }
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json::<RunStatus>().ok()
+}
+
+/// Upload progress for a chunked push: a byte counter redrawn as each chunk
+/// streams out when stderr is a terminal, and one line per chunk either way.
+struct UploadProgress {
+    total_bytes: u64,
+    sent_bytes: u64,
+    interactive: bool,
+}
+
+impl UploadProgress {
+    fn new(total_bytes: u64, sent_bytes: u64) -> Self {
+        use std::io::IsTerminal;
+        Self {
+            total_bytes,
+            sent_bytes,
+            interactive: std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// The request body for a chunk, reporting bytes as the client reads them.
+    fn body(&self, label: &str, bytes: Vec<u8>) -> reqwest::blocking::Body {
+        if !self.interactive {
+            return bytes.into();
+        }
+        let len = bytes.len() as u64;
+        reqwest::blocking::Body::sized(
+            ProgressReader {
+                inner: std::io::Cursor::new(bytes),
+                label: label.to_string(),
+                base: self.sent_bytes,
+                total: self.total_bytes,
+                last_draw: None,
+            },
+            len,
+        )
+    }
+
+    fn sent(&mut self, label: &str, len: usize) {
+        self.sent_bytes += len as u64;
+        if self.interactive {
+            eprint!("\r\x1b[2K");
+        }
+        eprintln!(
+            "  {label} sent ({}), {} of {} ({}%)",
+            format_bytes(len as u64),
+            format_bytes(self.sent_bytes),
+            format_bytes(self.total_bytes),
+            percent(self.sent_bytes, self.total_bytes)
+        );
+    }
+}
+
+struct ProgressReader {
+    inner: std::io::Cursor<Vec<u8>>,
+    label: String,
+    base: u64,
+    total: u64,
+    last_draw: Option<std::time::Instant>,
+}
+
+impl std::io::Read for ProgressReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let now = std::time::Instant::now();
+        if self
+            .last_draw
+            .is_none_or(|at| now.duration_since(at) >= Duration::from_millis(100))
+        {
+            self.last_draw = Some(now);
+            let sent = self.base + self.inner.position();
+            eprint!(
+                "\r\x1b[2K  {}: {} of {} ({}%)",
+                self.label,
+                format_bytes(sent),
+                format_bytes(self.total),
+                percent(sent, self.total)
+            );
+        }
+        Ok(read)
+    }
+}
+
+fn percent(part: u64, whole: u64) -> u64 {
+    (part * 100).checked_div(whole).unwrap_or(100)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Full-package size (compressed zip bytes) at or below which a push is sent as
+/// a single request. Above this the package is chunked to stay under the
+/// serverless request-body limit (Vercel caps function payloads at ~4.5 MB).
+const SINGLE_SHOT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// Target raw (uncompressed) content per chunk. Kept well under the request
+/// limit so even incompressible (binary) chunks fit once zipped.
+const MAX_CHUNK_RAW_BYTES: usize = 3 * 1024 * 1024;
+
+/// POST one package zip to the sync endpoint (optionally with chunk query
+/// params) and parse the response.
+fn post_sync(
+    client: &reqwest::blocking::Client,
+    sync_url: &str,
+    token: &str,
+    body: reqwest::blocking::Body,
+    query: &str,
+    timeout_secs: u64,
+) -> Result<PushSyncResponse> {
+    let url = format!("{sync_url}{query}");
+    let response = client
+        .post(&url)
+        .bearer_auth(token)
+        .header(reqwest::header::CONTENT_TYPE, "application/zip")
+        .body(body)
+        .send()
+        .map_err(|err| {
+            let message = if err.is_timeout() {
+                format!(
+                    "push to {sync_url} timed out after {timeout_secs}s. The server may still be \
+                     processing a large package; retry, raise --timeout, or check the server."
+                )
+            } else if err.is_connect() {
+                format!("could not connect to {sync_url}: {err}")
+            } else {
+                format!("sending sync request to {sync_url}: {err}")
+            };
+            anyhow::Error::new(TransientPushError(message))
         })?;
 
     let status = response.status();
     if !status.is_success() {
         let detail = response.text().unwrap_or_default();
-        bail!(
+        let message = format!(
             "server returned {status}{}",
             if detail.is_empty() {
                 String::new()
@@ -2540,6 +4749,10 @@ fn post_sync(
                 format!(": {detail}")
             }
         );
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(TransientPushError(message).into());
+        }
+        bail!(message);
     }
 
     response.json().context("parsing sync response")
@@ -2568,7 +4781,7 @@ fn split_package(
 
     enum Item {
         Spec(Spec),
-        Pr(PrChangeSet),
+        Pr(Box<PrChangeSet>),
     }
     let mut items: Vec<(usize, Item)> = Vec::new();
     for spec in package.mainline.specs {
@@ -2576,7 +4789,7 @@ fn split_package(
         items.push((size, Item::Spec(spec)));
     }
     for pr in package.pr_changes {
-        items.push((pr_change_raw_size(&pr), Item::Pr(pr)));
+        items.push((pr_change_raw_size(&pr), Item::Pr(Box::new(pr))));
     }
 
     // Greedy bin-packing: start a new chunk once adding an item would overflow
@@ -2592,7 +4805,7 @@ fn split_package(
         }
         match item {
             Item::Spec(s) => specs.push(s),
-            Item::Pr(p) => prs.push(p),
+            Item::Pr(p) => prs.push(*p),
         }
         size += item_size;
     }
@@ -2653,9 +4866,14 @@ fn run_bundle(
     output: PathBuf,
     branch: Option<String>,
     commit: Option<String>,
+    audience: Option<Visibility>,
+    offline: bool,
 ) -> Result<()> {
     let project_root = project_root_from(config_path.as_deref(), &input_path);
-    let project_config = load_project_configuration(&project_root, config_path.as_deref());
+    let mut project_config = load_project_configuration(&project_root, config_path.as_deref());
+    project_config.offline |= offline;
+    let signing_key = resolve_signing_key(&project_root, &project_config)?;
+    let audience = audience.unwrap_or_else(|| project_config.visibility.audience());
 
     let (package, zip_bytes) = build_package(
         &input_path,
@@ -2665,6 +4883,9 @@ fn run_bundle(
         branch,
         commit,
         false,
+        None,
+        audience,
+        signing_key.as_ref(),
     )?;
 
     let total_assets: usize = package.mainline.specs.iter().map(|s| s.assets.len()).sum();
@@ -2682,6 +4903,255 @@ fn run_bundle(
     Ok(())
 }
 
+fn read_bundle(
+    file: &Path,
+) -> Result<(
+    dossiers::bundle::Package,
+    Option<dossiers::bundle::VerifyingKey>,
+    u64,
+)> {
+    let handle = File::open(file).with_context(|| format!("opening bundle {}", file.display()))?;
+    let size = handle.metadata().map(|meta| meta.len()).unwrap_or_default();
+    let (package, signer) =
+        dossiers::bundle::Package::read_zip_with_signer(std::io::BufReader::new(handle))
+            .with_context(|| format!("reading bundle {}", file.display()))?;
+    Ok((package, signer, size))
+}
+
+fn run_bundle_inspect(file: &Path) -> Result<()> {
+    use dossiers::bundle::{AssetChange, SpecChange, SpecIndexEntry};
+
+    let (package, signer, zip_size) = read_bundle(file)?;
+    let manifest = &package.manifest;
+    let or_dash = |value: Option<&str>| {
+        value
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("Package {} ({zip_size} bytes)", file.display());
+    println!("  version:   {}", manifest.package_version);
+    println!("  source:    {:?}", manifest.source);
+    println!("  branch:    {}", or_dash(manifest.branch.as_deref()));
+    println!("  commit:    {}", or_dash(manifest.commit.as_deref()));
+    println!("  timestamp: {}", manifest.timestamp.to_rfc3339());
+    match manifest.base_commit.as_deref() {
+        Some(base) => {
+            println!("  mode:      delta against {base}");
+            println!(
+                "  deleted specs: {}",
+                or_dash(Some(&manifest.deleted_specs.join(", ")))
+            );
+            let deleted_prs = manifest
+                .deleted_prs
+                .iter()
+                .map(|n| format!("#{n}"))
+                .collect::<Vec<_>>();
+            println!(
+                "  deleted PRs:   {}",
+                or_dash(Some(&deleted_prs.join(", ")))
+            );
+        }
+        None => println!("  mode:      full snapshot"),
+    }
+    if !manifest.unchanged.is_empty() {
+        println!(
+            "  unchanged: {} file(s) reused from the base commit",
+            manifest.unchanged.len()
+        );
+    }
+    match manifest.digests.len() {
+        0 => println!("  digests:   -"),
+        count => println!("  digests:   sha256 for {count} file(s)"),
+    }
+    match &signer {
+        Some(key) => println!(
+            "  signature: ed25519 {}",
+            dossiers::bundle::encode_hex(key.as_bytes())
+        ),
+        None => println!("  signature: -"),
+    }
+    match &package.project_config {
+        Some(bytes) => println!("  config:    project.toml ({} bytes)", bytes.len()),
+        None => println!("  config:    -"),
+    }
+
+    let index: HashMap<&str, &SpecIndexEntry> = manifest
+        .specs
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry))
+        .collect();
+    let mainline_size: usize = package
+        .mainline
+        .specs
+        .iter()
+        .map(|spec| spec.source.len() + spec.assets.iter().map(|a| a.bytes.len()).sum::<usize>())
+        .sum();
+    println!();
+    println!(
+        "Specs: {} ({} indexed, {mainline_size} bytes)",
+        package.mainline.specs.len(),
+        manifest.specs.len()
+    );
+    for spec in &package.mainline.specs {
+        let entry = index.get(spec.id.as_str());
+        let assets: usize = spec.assets.iter().map(|a| a.bytes.len()).sum();
+        println!(
+            "  {}  {}/{}  [{}] {}",
+            spec.id,
+            spec.dir_name,
+            spec.source_path,
+            entry.map_or("-", |e| e.status.as_str()),
+            entry.map_or("(not indexed)", |e| e.title.as_str()),
+        );
+        println!(
+            "        {} bytes source, {} asset(s) ({assets} bytes)",
+            spec.source.len(),
+            spec.assets.len()
+        );
+    }
+
+    println!();
+    println!("Pull requests: {}", package.pr_changes.len());
+    for pr in &package.pr_changes {
+        println!(
+            "  #{} {} [{}] {} @ {} ({} bytes)",
+            pr.pr_number,
+            pr.title,
+            pr.state,
+            pr.branch,
+            pr.head_sha,
+            pr_change_raw_size(pr)
+        );
+        let mut changes = Vec::new();
+        for change in &pr.spec_changes {
+            changes.push(match change {
+                SpecChange::Upsert(spec) => format!("update {}", spec.dir_name),
+                SpecChange::Remove { id } => format!("remove {id}"),
+            });
+        }
+        for change in &pr.asset_changes {
+            changes.push(match change {
+                AssetChange::Upsert { spec_id, asset } => {
+                    format!("update {spec_id}/{}", asset.path)
+                }
+                AssetChange::Remove { spec_id, path } => format!("remove {spec_id}/{path}"),
+            });
+        }
+        if !changes.is_empty() {
+            println!("        {}", changes.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+fn run_bundle_unpack(file: &Path, output: Option<PathBuf>) -> Result<()> {
+    let (package, _, _) = read_bundle(file)?;
+    let output = output.unwrap_or_else(|| file.with_extension(""));
+    if output.exists() && fs::read_dir(&output)?.next().is_some() {
+        bail!("{} already exists and is not empty", output.display());
+    }
+    package
+        .write_dir(&output)
+        .with_context(|| format!("unpacking to {}", output.display()))?;
+    println!(
+        "Unpacked {} spec(s){} to {}",
+        package.mainline.specs.len(),
+        pr_summary(&package.pr_changes),
+        output.display()
+    );
+    Ok(())
+}
+
+fn run_bundle_verify(file: &Path, public_keys: &[String]) -> Result<()> {
+    let trusted = public_keys
+        .iter()
+        .map(|key| {
+            dossiers::bundle::parse_verifying_key(key)
+                .with_context(|| format!("parsing public key {key}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (package, signer, _) = read_bundle(file)?;
+    let verification = package.verify();
+    let mut report = CategoryReport::default();
+    for error in verification.errors {
+        report.add_error(error, None);
+    }
+    for warning in verification.warnings {
+        report.add_warning(warning, None);
+    }
+    if package.manifest.digests.is_empty() {
+        report.add_warning(
+            "package has no file digests (written by an older CLI)".to_string(),
+            None,
+        );
+    }
+    match signer {
+        Some(key) if !trusted.is_empty() && !trusted.contains(&key) => report.add_error(
+            format!(
+                "package is signed by {}, which is not a trusted key",
+                dossiers::bundle::encode_hex(key.as_bytes())
+            ),
+            None,
+        ),
+        None if !trusted.is_empty() => report.add_error("package is not signed".to_string(), None),
+        _ => {}
+    }
+
+    println!("Verifying {}...", file.display());
+    let (errors, _) = print_category_report(&report, "Package is valid");
+    if errors > 0 {
+        bail!(
+            "{} failed verification with {errors} error(s)",
+            file.display()
+        );
+    }
+    Ok(())
+}
+
+fn run_bundle_keygen(output: Option<PathBuf>, force: bool) -> Result<()> {
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    let secret = dossiers::bundle::encode_hex(key.as_bytes());
+    let public = dossiers::bundle::encode_hex(key.verifying_key().as_bytes());
+    match output {
+        Some(path) => {
+            write_secret_file(&path, &format!("{secret}\n"), force)?;
+            println!("Secret key written to {}", path.display());
+        }
+        None => println!("Secret key: {secret}"),
+    }
+    println!("Public key: {public}");
+    Ok(())
+}
+
+/// Create `path` holding `contents`, readable only by its owner on unix. An
+/// existing file is an error unless `force`, which replaces it.
+fn write_secret_file(path: &Path, contents: &str, force: bool) -> Result<()> {
+    use std::io::Write as _;
+    if path.exists() {
+        if !force {
+            bail!(
+                "{} already exists; pass --force to replace it",
+                path.display()
+            );
+        }
+        fs::remove_file(path).with_context(|| format!("removing {}", path.display()))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("writing secret key to {}", path.display()))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_package(
     input_path: &Path,
@@ -2691,6 +5161,9 @@ fn build_package(
     branch: Option<String>,
     commit: Option<String>,
     no_prs: bool,
+    target: Option<&PushTarget>,
+    audience: Visibility,
+    signing_key: Option<&SigningKey>,
 ) -> Result<(dossiers::bundle::Package, Vec<u8>)> {
     // Discover the git repo from the spec directory, not `project_root`: with an
     // explicit `-c config.toml` the project root is the config's parent, which
@@ -2732,7 +5205,7 @@ fn build_package(
         })
     };
 
-    let package = dossiers::bundle::Package {
+    let mut package = dossiers::bundle::Package {
         manifest: dossiers::bundle::Manifest {
             package_version: dossiers::bundle::PACKAGE_VERSION,
             commit: empty_to_none(resolved_commit),
@@ -2743,19 +5216,70 @@ fn build_package(
             base_commit: None,
             deleted_specs: Vec::new(),
             deleted_prs: Vec::new(),
+            digests: Default::default(),
+            unchanged: Default::default(),
         },
         project_config: project_config_bytes,
         mainline,
         pr_changes,
     };
+    let (dropped, _) = filter_package(&mut package, target, audience);
+    if !dropped.is_empty() {
+        let readers = match target {
+            Some(target) => format!("push target '{}'", target.name),
+            None => format!("the {audience} audience"),
+        };
+        println!(
+            "Leaving out {} spec(s) not published to {readers}: {}",
+            dropped.len(),
+            dropped.join(", ")
+        );
+    }
 
-    let mut buf = std::io::Cursor::new(Vec::new());
-    package.write_zip(&mut buf).context("writing package zip")?;
-    let zip_bytes = buf.into_inner();
+    let zip_bytes = encode_package(&package, signing_key).context("writing package zip")?;
 
     Ok((package, zip_bytes))
 }
 
+/// Serialise a package as zip bytes, signed when a key is configured.
+fn encode_package(
+    package: &dossiers::bundle::Package,
+    signing_key: Option<&SigningKey>,
+) -> Result<Vec<u8>, dossiers::bundle::BundleError> {
+    let buf = std::io::Cursor::new(Vec::new());
+    let buf = match signing_key {
+        Some(key) => package.write_zip_signed(buf, key)?,
+        None => package.write_zip(buf)?,
+    };
+    Ok(buf.into_inner())
+}
+
+/// The key packages are signed with: `$DOSSIERS_SIGNING_KEY` (hex), else the
+/// file named by `[push] signing_key_file`. Packages are unsigned when neither
+/// is set.
+fn resolve_signing_key(
+    project_root: &Path,
+    project_config: &ProjectConfiguration,
+) -> Result<Option<SigningKey>> {
+    if let Some(key) = env::var("DOSSIERS_SIGNING_KEY")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    {
+        return dossiers::bundle::parse_signing_key(&key)
+            .map(Some)
+            .context("reading $DOSSIERS_SIGNING_KEY");
+    }
+    let Some(file) = &project_config.push_signing_key_file else {
+        return Ok(None);
+    };
+    let path = project_root.join(file);
+    let key = fs::read_to_string(&path)
+        .with_context(|| format!("reading signing key {}", path.display()))?;
+    dossiers::bundle::parse_signing_key(&key)
+        .map(Some)
+        .with_context(|| format!("reading signing key {}", path.display()))
+}
+
 fn build_spec_index(
     specs_dir: &Path,
     project_config: &ProjectConfiguration,
@@ -2776,12 +5300,19 @@ fn build_spec_index(
     // authors' linked GitHub accounts (one commits-API call per unique addition
     // commit); without it, the local Gravatar / noreply avatars computed during
     // load are kept.
-    let resolver = match github_client_for(specs_dir, project_config) {
-        Some(client) => dossiers::authors::AuthorResolver::from_github(
-            &client,
+    let forge = connect_forge(specs_dir, project_config).ok();
+    let resolver = match &forge {
+        Some(client) => dossiers::authors::AuthorResolver::from_forge(
+            client.as_ref(),
             by_id.values().flat_map(|doc| doc.author_seeds.iter()),
         ),
         None => dossiers::authors::AuthorResolver::local(),
+    }
+    .with_host(project_config.github_host())
+    .with_directory(project_config.authors.clone());
+    let mut approvals = match &forge {
+        Some(client) => merged_pull_approvals(client.as_ref(), by_id.values()),
+        None => HashMap::new(),
     };
 
     let mut entries = Vec::with_capacity(mainline.specs.len());
@@ -2799,6 +5330,12 @@ fn build_spec_index(
         } else {
             authors_meta.iter().map(|a| a.name.clone()).collect()
         };
+        let teams = project_config.teams.teams_of(
+            &doc.teams,
+            &authors,
+            &author_emails(&doc.author_seeds),
+            &doc.owners,
+        );
         entries.push(dossiers::bundle::SpecIndexEntry {
             id: spec.id.clone(),
             dir_name: spec.dir_name.clone(),
@@ -2810,39 +5347,151 @@ fn build_spec_index(
             updated: doc.updated.and_then(millis_to_utc),
             authors,
             authors_meta,
+            approved_by: approvals.remove(&spec.id).unwrap_or_default(),
+            owners: doc.owners,
+            teams,
             extra: doc.extra.into_iter().collect(),
             links,
             fields,
+            visibility: doc.visibility.to_wire(),
         });
     }
     Ok(entries)
 }
 
-/// A GitHub client for avatar resolution, from `GITHUB_TOKEN` plus the repo in
-/// the project config or the spec directory's git remote. `None` when the token
-/// or repo is missing — the caller then keeps the local Gravatar/noreply
-/// avatars rather than failing the push.
-fn github_client_for(
-    specs_dir: &Path,
+/// Who approved the merged pull request behind each spec's last change: the
+/// request the forge associates with the commit git names for it. Specs
+/// changed outside a pull request, or whose request has no standing
+/// approvals, are left out; each commit is looked up once.
+fn merged_pull_approvals<'a>(
+    client: &dyn Forge,
+    specs: impl IntoIterator<Item = &'a SpecDocument>,
+) -> HashMap<String, Vec<String>> {
+    let mut by_commit: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut approvals = HashMap::new();
+    for spec in specs {
+        let Some(sha) = spec.last_commit.as_deref() else {
+            continue;
+        };
+        let approved = by_commit.entry(sha).or_insert_with(|| {
+            let pulls = match client.list_commit_pulls(sha) {
+                Ok(pulls) => pulls,
+                Err(err) => {
+                    eprintln!("Warning: unable to find the pull request of commit {sha}: {err}");
+                    return Vec::new();
+                }
+            };
+            let Some(pull) = pulls
+                .iter()
+                .find(|pull| pull.merge_commit_sha.as_deref() == Some(sha))
+                .or(pulls.first())
+            else {
+                return Vec::new();
+            };
+            match client.list_pull_reviews(pull.number) {
+                Ok(reviews) => approvers(&reviews),
+                Err(err) => {
+                    eprintln!(
+                        "Warning: unable to list reviews of merged PR #{}: {err}",
+                        pull.number
+                    );
+                    Vec::new()
+                }
+            }
+        });
+        if !approved.is_empty() {
+            approvals.insert(spec.id.clone(), approved.clone());
+        }
+    }
+    approvals
+}
+
+/// The forge PR revisions and author avatars come from, with the repository
+/// from the project config or the git remote at `discover_from`. GitHub needs
+/// `GITHUB_TOKEN` and GitLab `GITLAB_TOKEN`. `forge` in the config picks one;
+/// otherwise a remote on the configured GitLab host means GitLab. GitHub
+/// responses are cached under the repository root, and PR files whose head is
+/// in the local clone are read from git; in offline mode the forge is never
+/// contacted. `Err` says why PRs are skipped.
+fn connect_forge(
+    discover_from: &Path,
     project_config: &ProjectConfiguration,
-) -> Option<GithubClient> {
-    let token = match env::var("GITHUB_TOKEN") {
+) -> std::result::Result<Box<dyn Forge>, String> {
+    let git_repo = open_git_repository(discover_from);
+    let remote = git_repo.as_ref().and_then(|repo| repo.remote_url());
+    let repo_root = git_repo
+        .as_ref()
+        .map_or(discover_from, |repo| repo.workdir())
+        .to_path_buf();
+    let candidates: Vec<&str> = [project_config.repository.as_deref(), remote.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let github = project_config.github_host();
+    let gitlab = project_config.gitlab_host();
+    let kind = project_config
+        .forge_kind()
+        .or_else(|| {
+            candidates.iter().find_map(|raw| {
+                if github.parse_repo(raw).is_some() {
+                    Some(ForgeKind::Github)
+                } else {
+                    gitlab.parse_project(raw).map(|_| ForgeKind::Gitlab)
+                }
+            })
+        })
+        .unwrap_or(ForgeKind::Github);
+
+    let token_var = match kind {
+        ForgeKind::Github => "GITHUB_TOKEN",
+        ForgeKind::Gitlab => "GITLAB_TOKEN",
+    };
+    let offline = project_config.offline;
+    let token = match env::var(token_var) {
         Ok(value) if !value.trim().is_empty() => value,
-        _ => return None,
+        // Offline, the client only supplies web links and is never used.
+        _ if offline => String::new(),
+        _ => return Err(format!("{token_var} not set")),
     };
-    let git_repo = open_git_repository(specs_dir);
-    let repo = project_config
-        .repository
-        .as_deref()
-        .and_then(parse_github_repo)
-        .or_else(|| {
-            git_repo
-                .as_ref()
-                .and_then(|r| r.remote_url())
-                .as_deref()
-                .and_then(parse_github_repo)
-        })?;
-    GithubClient::new(repo, &token).ok()
+    let missing = || format!("no {kind} repository found in config or git remotes");
+    let client: anyhow::Result<Box<dyn Forge>> = match kind {
+        ForgeKind::Github => {
+            let repo = candidates
+                .iter()
+                .find_map(|raw| github.parse_repo(raw))
+                .ok_or_else(missing)?;
+            GithubClient::new(repo, &token).map(|client| {
+                let client = client.with_host(github);
+                let client = match project_config.github_cache(&repo_root) {
+                    Some(cache) => client.with_cache(cache),
+                    None => client,
+                };
+                Box::new(client) as Box<dyn Forge>
+            })
+        }
+        ForgeKind::Gitlab => {
+            let project = candidates
+                .iter()
+                .find_map(|raw| gitlab.parse_project(raw))
+                .ok_or_else(missing)?;
+            GitlabClient::new(project, &token)
+                .map(|client| Box::new(client.with_host(gitlab)) as Box<dyn Forge>)
+        }
+    };
+    let client = client.map_err(|err| format!("{err:#}"))?;
+
+    // Serve PR files from git objects when the clone has the heads.
+    let base = git_repo
+        .as_ref()
+        .and_then(|repo| repo.base_commit_sha(project_config.default_branch.as_deref()));
+    match (git_repo, base) {
+        (Some(git_repo), Some(base)) => {
+            let local = LocalGitForge::new(git_repo, base, client);
+            Ok(Box::new(if offline { local.offline() } else { local }))
+        }
+        _ if offline => Err("offline mode needs a git repository with commits".to_string()),
+        _ => Ok(client),
+    }
 }
 
 fn millis_to_utc(ms: i64) -> Option<chrono::DateTime<Utc>> {
@@ -2877,10 +5526,10 @@ fn augment_with_pull_requests(
     project_root: &Path,
     project_config: &ProjectConfiguration,
 ) -> Result<()> {
-    let token = match env::var("GITHUB_TOKEN") {
-        Ok(value) if !value.trim().is_empty() => value,
-        _ => {
-            eprintln!("Skipping PR revisions: GITHUB_TOKEN not set.");
+    let client = match connect_forge(project_root, project_config) {
+        Ok(client) => client,
+        Err(reason) => {
+            eprintln!("Skipping PR revisions: {reason}.");
             return Ok(());
         }
     };
@@ -2891,27 +5540,10 @@ fn augment_with_pull_requests(
         .map(|repo| repo.workdir().to_path_buf())
         .unwrap_or_else(|| project_root.to_path_buf());
 
-    let repo_from_config = project_config
-        .repository
-        .as_deref()
-        .and_then(parse_github_repo);
-    let repo_from_git = git_repo
-        .as_ref()
-        .and_then(|repo| repo.remote_url())
-        .as_deref()
-        .and_then(parse_github_repo);
-
-    let Some(github_repo) = repo_from_config.or(repo_from_git) else {
-        eprintln!("Skipping PR revisions: no GitHub repository found in config or git remotes.");
-        return Ok(());
-    };
-    let repo_slug = format!("{}/{}", github_repo.owner, github_repo.name);
-    if state.github_repo.is_none() {
-        state.github_repo = Some(repo_slug.clone());
-    }
     eprintln!(
-        "Using GitHub repository {}/{} for PR revisions.",
-        github_repo.owner, github_repo.name
+        "Using {} repository {} for PR revisions.",
+        client.kind(),
+        client.repo_slug()
     );
 
     let spec_root = resolve_spec_input_path(input_path, project_config);
@@ -2924,11 +5556,21 @@ fn augment_with_pull_requests(
         return Ok(());
     };
 
-    let client = GithubClient::new(github_repo, &token)
-        .context("creating GitHub client for pull request previews")?;
+    let approvals = merged_pull_approvals(client.as_ref(), &state.specs);
+    for spec in state.specs.iter_mut() {
+        if let Some(logins) = approvals.get(&spec.id) {
+            spec.approved_by = logins.clone();
+        }
+    }
+    for (id, spec) in state.specs_by_id.iter_mut() {
+        if let Some(logins) = approvals.get(id) {
+            spec.approved_by = logins.clone();
+        }
+    }
+
     let pulls = client
         .list_open_pulls()
-        .context("listing open GitHub pull requests")?;
+        .with_context(|| format!("listing open {} pull requests", client.kind()))?;
 
     if pulls.is_empty() {
         eprintln!("No open pull requests found for preview.");
@@ -2939,6 +5581,14 @@ fn augment_with_pull_requests(
     let metadata_reader = MetadataReader::new(project_config.clone());
     for pull in pulls {
         eprintln!("Inspecting PR #{} for revisions...", pull.number);
+        let url = if pull.html_url.is_empty() {
+            client.pull_url(pull.number)
+        } else {
+            pull.html_url.clone()
+        };
+        state
+            .pull_links
+            .insert(pull.number, (client.pull_reference(pull.number), url));
         let files = match client.list_pull_files(pull.number) {
             Ok(files) => files,
             Err(err) => {
@@ -2974,6 +5624,9 @@ fn augment_with_pull_requests(
             pull.number,
             targets.len()
         );
+        state
+            .pull_reviews
+            .insert(pull.number, PullReviewInfo::fetch(client.as_ref(), &pull));
 
         for target in targets {
             eprintln!(
@@ -2992,7 +5645,7 @@ fn augment_with_pull_requests(
             if let Err(err) = build_pr_spec_version(
                 state,
                 static_mounts,
-                &client,
+                client.as_ref(),
                 &metadata_reader,
                 &pull,
                 &files,
@@ -3025,7 +5678,7 @@ fn augment_with_pull_requests(
     }
 
     for revisions in state.revisions.values_mut() {
-        revisions.sort_by_key(|rev| rev.pr_number);
+        sort_revisions(revisions);
     }
 
     Ok(())
@@ -3039,7 +5692,7 @@ struct SpecTarget {
 }
 
 fn map_pull_to_specs(
-    files: &[GithubFile],
+    files: &[PullFile],
     spec_root_relative: &Path,
     pr_number: u64,
     pr_number_as_spec_id: bool,
@@ -3092,10 +5745,10 @@ fn map_pull_to_specs(
 fn build_pr_spec_version(
     state: &mut AppState,
     static_mounts: &mut Vec<StaticMount>,
-    client: &GithubClient,
+    client: &dyn Forge,
     metadata_reader: &MetadataReader,
-    pull: &GithubPull,
-    files: &[GithubFile],
+    pull: &PullRequest,
+    files: &[PullFile],
     spec_id: &str,
     spec_relative_dir: &Path,
     spec_root: &Path,
@@ -3219,17 +5872,8 @@ fn build_pr_spec_version(
 
     let meta_created = meta.created.as_deref().and_then(parse_date);
     let meta_updated = meta.updated.as_deref().and_then(parse_date);
-    let authors = if meta.authors.is_empty() {
-        pull.author
-            .as_ref()
-            .map(|a| vec![a.clone()])
-            .unwrap_or_default()
-    } else {
-        meta.authors.clone()
-    };
     let base_created = state.specs_by_id.get(spec_id).and_then(|spec| spec.created);
     let base_updated = state.specs_by_id.get(spec_id).and_then(|spec| spec.updated);
-    let base_exists = state.specs_by_id.contains_key(spec_id);
     let (file_created, file_modified) = file_timestamps(&doc_path);
 
     let created = meta_created
@@ -3246,31 +5890,42 @@ fn build_pr_spec_version(
     let updated_sort = updated;
 
     let status = meta.status.unwrap_or(status_fallback);
+    let visibility = metadata_reader.resolve_visibility(meta.visibility.as_deref(), &status);
+    // Owners follow the base branch's CODEOWNERS, as the forge's review rules do.
+    let owners = match state.specs_by_id.get(spec_id) {
+        Some(base) => base.owners.clone(),
+        None => open_git_repository(spec_root)
+            .and_then(|repo| CodeOwners::load(repo.workdir()))
+            .zip(doc_path.strip_prefix(&spec_root_temp).ok())
+            .map(|(codeowners, relative)| codeowners.owners_of(&spec_root_relative.join(relative)))
+            .unwrap_or_default(),
+    };
     // Credit the PR preview: declared frontmatter authors override; otherwise
-    // the PR author with the avatar GitHub returned on the pulls listing.
+    // the PR author with the avatar the forge returned on the pulls listing.
     let author_seeds = dossiers::authors::seeds_from_names(&meta.raw_authors);
+    let resolver = metadata_reader.author_resolver();
     let authors_meta: Vec<dossiers::bundle::Author> = if meta.raw_authors.is_empty() {
         match &pull.author {
-            Some(login) => vec![dossiers::bundle::Author {
-                name: login.clone(),
-                avatar_url: pull.avatar_url.clone(),
-                url: Some(format!("https://github.com/{login}")),
-            }],
+            Some(login) => vec![resolver.resolve_login(
+                login,
+                pull.avatar_url.clone(),
+                client.profile_url(login),
+            )],
             None => Vec::new(),
         }
     } else {
-        dossiers::authors::resolve_authors(
-            &author_seeds,
-            &dossiers::authors::AuthorResolver::local(),
-        )
+        dossiers::authors::resolve_authors(&author_seeds, &resolver)
     };
-    let pr_id = if base_exists {
-        format!("{}/pr/{}", spec_id, pull.number)
+    let authors: Vec<String> = if authors_meta.is_empty() {
+        meta.authors.clone()
     } else {
-        spec_id.to_string()
+        authors_meta
+            .iter()
+            .map(|author| author.name.clone())
+            .collect()
     };
     let pr_spec = SpecDocument {
-        id: pr_id.clone(),
+        id: spec_id.to_string(),
         dir_name,
         title: meta
             .title
@@ -3288,40 +5943,271 @@ fn build_pr_spec_version(
         extra: metadata_extra_to_json(&meta.extra),
         source: parsed.body,
         format,
-        listed: !base_exists,
-        revision_of: base_exists.then(|| spec_id.to_string()),
+        listed: true,
+        revision_of: None,
         pr_number: Some(pull.number),
+        git_ref: None,
+        approved_by: Vec::new(),
+        last_commit: None,
+        visibility,
+        owners,
+        teams: meta.teams,
     };
 
-    let mount_path = if base_exists {
-        format!("/{}/pr/{}", spec_id, pull.number)
-    } else {
-        format!("/{spec_id}")
-    };
     let static_root = if doc_root.is_dir() {
         doc_root.clone()
     } else {
         doc_root.parent().unwrap_or(&doc_root).to_path_buf()
     };
-    static_mounts.push((mount_path, static_root));
-    insert_spec_document(state, pr_spec.clone());
+    insert_revision_spec(
+        state,
+        static_mounts,
+        pr_spec,
+        RevisionSource::Pull(pull.number),
+        static_root,
+    );
+
+    Ok(())
+}
+
+/// Add a PR's or branch's version of a spec to the site. When the spec exists
+/// on mainline the version becomes a revision of it (at `<id>/pr/<n>` or
+/// `<id>/branch/<name>`); otherwise it is listed as a new spec under its own
+/// id. `spec.id` is the target spec id.
+fn insert_revision_spec(
+    state: &mut AppState,
+    static_mounts: &mut Vec<StaticMount>,
+    mut spec: SpecDocument,
+    source: RevisionSource,
+    static_root: PathBuf,
+) {
+    let spec_id = spec.id.clone();
+    let base_exists = state.specs_by_id.contains_key(&spec_id);
+    if base_exists {
+        spec.id = source.revision_id(&spec_id);
+        spec.listed = false;
+        spec.revision_of = Some(spec_id.clone());
+    }
+    match &source {
+        RevisionSource::Pull(number) => spec.pr_number = Some(*number),
+        other => spec.git_ref = Some(other.clone()),
+    }
+
+    static_mounts.push((format!("/{}", spec.id), static_root));
+    insert_spec_document(state, spec.clone());
 
     if base_exists {
-        state
-            .revisions
-            .entry(spec_id.to_string())
-            .or_default()
-            .push(RevisionLink {
-                pr_number: pull.number,
-                status: pr_spec.status.clone(),
-                updated: pr_spec.updated,
-                href: pr_spec.id.clone(),
-            });
+        push_revision_link(state, &spec_id, source, &spec);
+    }
+}
+
+fn push_revision_link(
+    state: &mut AppState,
+    base_id: &str,
+    source: RevisionSource,
+    revision: &SpecDocument,
+) {
+    state
+        .revisions
+        .entry(base_id.to_string())
+        .or_default()
+        .push(RevisionLink {
+            source,
+            status: revision.status.clone(),
+            updated: revision.updated,
+            href: revision.id.clone(),
+        });
+}
+
+/// Where branch and tag revisions are exported while their site is rendered.
+/// It lies outside the repository so the exported specs are not mistaken for
+/// files in its history. `run_build` removes it once the site is written.
+fn git_ref_workspace() -> PathBuf {
+    env::temp_dir().join(format!("dossiers-refs-{}", std::process::id()))
+}
+
+/// Add the branches and tags matching `[revisions]` to the site, read from
+/// the local clone. A branch becomes a revision of each spec it changes
+/// relative to `default_branch`. A tag freezes every spec as released at
+/// `v/<tag>/<id>`, and is listed as a revision of the specs it changed since
+/// the previous matching tag.
+fn augment_with_git_refs(
+    state: &mut AppState,
+    static_mounts: &mut Vec<StaticMount>,
+    input_path: &Path,
+    project_root: &Path,
+    project_config: &ProjectConfiguration,
+) -> Result<()> {
+    let patterns = &project_config.revisions;
+    if patterns.is_empty() {
+        return Ok(());
+    }
+    let Some(repo) = open_git_repository(project_root) else {
+        eprintln!("Skipping branch and tag revisions: no git repository found.");
+        return Ok(());
+    };
+    let spec_root = resolve_spec_input_path(input_path, project_config);
+    let Some(spec_root_relative) = relative_to(&spec_root, repo.workdir()) else {
+        eprintln!(
+            "Warning: unable to relate spec root {} to repository root {}; skipping branch and tag revisions.",
+            spec_root.display(),
+            repo.workdir().display()
+        );
+        return Ok(());
+    };
+    let base = repo
+        .base_commit_sha(project_config.default_branch.as_deref())
+        .context("resolving the default branch")?;
+
+    let workspace = git_ref_workspace();
+    if workspace.exists() {
+        let _ = fs::remove_dir_all(&workspace);
+    }
+    // Committed specs that declare no status fall back as on mainline; a
+    // branch under review reads like a pull request.
+    let mut branch_config = project_config.clone();
+    branch_config.new_status = Some("REVIEW".to_string());
+    let mut tag_config = project_config.clone();
+    tag_config.new_status = Some(MetadataReader::new(project_config.clone()).default_status());
+
+    let touched_specs = |from: &str, to: &str| -> Option<HashSet<String>> {
+        let files = repo.changed_files_between(from, to)?;
+        Some(
+            files
+                .iter()
+                .flat_map(|file| std::iter::once(&file.path).chain(file.previous_path.as_ref()))
+                .filter_map(|path| spec_id_from_repo_path(Path::new(path), &spec_root_relative))
+                .map(|(id, _)| id)
+                .collect(),
+        )
+    };
+
+    for branch in repo.branches() {
+        if !patterns.matches_branch(&branch.name) || branch.sha == base {
+            continue;
+        }
+        let touched = touched_specs(&base, &branch.sha).unwrap_or_default();
+        if touched.is_empty() {
+            continue;
+        }
+        let source = RevisionSource::Branch(branch.name.clone());
+        let dir = workspace.join(format!("branch-{}", ref_slug(&branch.name)));
+        let specs = match load_git_ref_specs(
+            &repo,
+            &branch.sha,
+            &spec_root_relative,
+            &dir,
+            &branch_config,
+            Some(&touched),
+        ) {
+            Ok(specs) => specs,
+            Err(err) => {
+                eprintln!("Warning: skipping branch {}: {err:#}", branch.name);
+                continue;
+            }
+        };
+        for (mut spec, static_root) in specs {
+            let base_spec = state.specs_by_id.get(&spec.id);
+            adopt_base_metadata(&mut spec, base_spec, branch.committed_at);
+            eprintln!("Branch {} -> spec {}", branch.name, spec.id);
+            insert_revision_spec(state, static_mounts, spec, source.clone(), static_root);
+        }
+    }
+
+    let mut previous_tag: Option<String> = None;
+    for tag in repo.tags() {
+        if !patterns.matches_tag(&tag.name) {
+            continue;
+        }
+        let source = RevisionSource::Tag(tag.name.clone());
+        let dir = workspace.join(format!("tag-{}", ref_slug(&tag.name)));
+        let specs = match load_git_ref_specs(
+            &repo,
+            &tag.sha,
+            &spec_root_relative,
+            &dir,
+            &tag_config,
+            None,
+        ) {
+            Ok(specs) => specs,
+            Err(err) => {
+                eprintln!("Warning: skipping tag {}: {err:#}", tag.name);
+                continue;
+            }
+        };
+        // The first release touches every spec it holds.
+        let touched = previous_tag
+            .as_deref()
+            .and_then(|previous| touched_specs(previous, &tag.sha));
+        eprintln!("Release {} -> {} spec(s)", tag.name, specs.len());
+        for (mut spec, static_root) in specs {
+            let spec_id = spec.id.clone();
+            let base_spec = state.specs_by_id.get(&spec_id);
+            let base_exists = base_spec.is_some();
+            adopt_base_metadata(&mut spec, base_spec, tag.committed_at);
+            spec.id = source.revision_id(&spec_id);
+            spec.listed = false;
+            spec.revision_of = Some(spec_id.clone());
+            spec.git_ref = Some(source.clone());
+            static_mounts.push((format!("/{}", spec.id), static_root));
+            insert_spec_document(state, spec.clone());
+            let changed = touched
+                .as_ref()
+                .is_none_or(|touched| touched.contains(&spec_id));
+            if base_exists && changed {
+                push_revision_link(state, &spec_id, source.clone(), &spec);
+            }
+        }
+        previous_tag = Some(tag.sha);
     }
 
+    for revisions in state.revisions.values_mut() {
+        sort_revisions(revisions);
+    }
     Ok(())
 }
 
+/// Export the spec root as committed at `rev` into `dir` and load its specs
+/// (only `only_ids`, when given), each with the directory its assets are
+/// served from.
+fn load_git_ref_specs(
+    repo: &GitRepository,
+    rev: &str,
+    spec_root_relative: &Path,
+    dir: &Path,
+    project_config: &ProjectConfiguration,
+    only_ids: Option<&HashSet<String>>,
+) -> Result<Vec<(SpecDocument, PathBuf)>> {
+    repo.export_tree(rev, spec_root_relative, dir)
+        .with_context(|| format!("exporting {} at {rev}", spec_root_relative.display()))?;
+    let loaded = load_specs_from_directory(dir, project_config, only_ids, None)?;
+    let mut mounts: HashMap<String, PathBuf> = loaded.static_mounts.into_iter().collect();
+    Ok(loaded
+        .specs
+        .into_iter()
+        .filter_map(|spec| {
+            let static_root = mounts.remove(&format!("/{}", spec.id))?;
+            Some((spec, static_root))
+        })
+        .collect())
+}
+
+/// Date a spec read from a branch or tag by its ref, keeping the mainline
+/// spec's creation date and, when the document names no authors, its credits.
+fn adopt_base_metadata(spec: &mut SpecDocument, base: Option<&SpecDocument>, ref_time: i64) {
+    spec.created = base.and_then(|base| base.created).or(Some(ref_time));
+    spec.updated = Some(ref_time);
+    spec.updated_sort = ref_time;
+    if let Some(base) = base.filter(|_| spec.author_seeds.is_empty()) {
+        spec.authors = base.authors.clone();
+        spec.authors_meta = base.authors_meta.clone();
+        spec.author_seeds = base.author_seeds.clone();
+    }
+    if let Some(base) = base {
+        spec.owners = base.owners.clone();
+    }
+}
+
 /// Result of turning one PR target spec into wire-format changes.
 struct BuiltPrTarget {
     spec_changes: Vec<dossiers::bundle::SpecChange>,
@@ -3333,7 +6219,8 @@ struct BuiltPrTarget {
 /// changed files to specs, and downloads the PR's version of each changed file
 /// so the server can render a preview. Mirrors the discovery in
 /// [`augment_with_pull_requests`] but emits wire-format change-sets instead of
-/// in-memory preview specs. Requires `GITHUB_TOKEN`; returns an empty vec (with
+/// in-memory preview specs. Requires a forge token (see [`connect_forge`]);
+/// returns an empty vec (with
 /// a note on stderr) when PRs can't be fetched.
 fn gather_pr_changes_for_package(
     project_root: &Path,
@@ -3343,37 +6230,21 @@ fn gather_pr_changes_for_package(
     // incremental sync passes the server's known heads here). Empty = fetch all.
     skip_unchanged: &HashMap<u64, String>,
 ) -> Result<Vec<dossiers::bundle::PrChangeSet>> {
-    let token = match env::var("GITHUB_TOKEN") {
-        Ok(value) if !value.trim().is_empty() => value,
-        _ => {
-            eprintln!("Skipping PR revisions: GITHUB_TOKEN not set.");
+    // Discover from the spec directory (inside the repo), not `project_root`,
+    // which can be the `-c` config's parent and sit outside the repo.
+    let client = match connect_forge(spec_root, project_config) {
+        Ok(client) => client,
+        Err(reason) => {
+            eprintln!("Skipping PR revisions: {reason}.");
             return Ok(Vec::new());
         }
     };
-
-    // Discover from the spec directory (inside the repo), not `project_root`,
-    // which can be the `-c` config's parent and sit outside the repo.
     let git_repo = open_git_repository(spec_root);
     let repo_root = git_repo
         .as_ref()
         .map(|repo| repo.workdir().to_path_buf())
         .unwrap_or_else(|| project_root.to_path_buf());
 
-    let repo_from_config = project_config
-        .repository
-        .as_deref()
-        .and_then(parse_github_repo);
-    let repo_from_git = git_repo
-        .as_ref()
-        .and_then(|repo| repo.remote_url())
-        .as_deref()
-        .and_then(parse_github_repo);
-    let Some(github_repo) = repo_from_config.or(repo_from_git) else {
-        eprintln!("Skipping PR revisions: no GitHub repository found in config or git remotes.");
-        return Ok(Vec::new());
-    };
-    let repo_slug = format!("{}/{}", github_repo.owner, github_repo.name);
-
     let Some(spec_root_relative) = relative_to(spec_root, &repo_root) else {
         eprintln!(
             "Skipping PR revisions: unable to relate spec root {} to repository root {}.",
@@ -3383,35 +6254,35 @@ fn gather_pr_changes_for_package(
         return Ok(Vec::new());
     };
 
-    eprintln!("Using GitHub repository {repo_slug} for PR revisions.");
-    let client = GithubClient::new(github_repo, &token)
-        .context("creating GitHub client for pull request change-sets")?;
+    eprintln!(
+        "Using {} repository {} for PR revisions.",
+        client.kind(),
+        client.repo_slug()
+    );
     let metadata_reader = MetadataReader::new(project_config.clone());
 
     gather_pr_changes(
-        &client,
+        client.as_ref(),
         &metadata_reader,
         spec_root,
         &spec_root_relative,
         project_config.pr_number_as_spec_id,
-        &repo_slug,
         skip_unchanged,
     )
 }
 
 #[allow(clippy::too_many_arguments)]
 fn gather_pr_changes(
-    client: &GithubClient,
+    client: &dyn Forge,
     metadata_reader: &MetadataReader,
     spec_root: &Path,
     spec_root_relative: &Path,
     pr_number_as_spec_id: bool,
-    repo_slug: &str,
     skip_unchanged: &HashMap<u64, String>,
 ) -> Result<Vec<dossiers::bundle::PrChangeSet>> {
     let pulls = client
         .list_open_pulls()
-        .context("listing open GitHub pull requests")?;
+        .with_context(|| format!("listing open {} pull requests", client.kind()))?;
     if pulls.is_empty() {
         eprintln!("No open pull requests found.");
         return Ok(Vec::new());
@@ -3428,196 +6299,491 @@ fn gather_pr_changes(
         {
             continue;
         }
-        let files = match client.list_pull_files(pull.number) {
-            Ok(files) => files,
-            Err(err) => {
-                eprintln!("Warning: skipping PR #{}: {err}", pull.number);
-                continue;
-            }
-        };
-
-        let Some(targets) = map_pull_to_specs(
-            &files,
+        change_sets.extend(build_pr_change_set(
+            client,
+            metadata_reader,
+            spec_root,
             spec_root_relative,
-            pull.number,
             pr_number_as_spec_id,
-        ) else {
-            continue;
-        };
-
-        let mut spec_changes: Vec<dossiers::bundle::SpecChange> = Vec::new();
-        let mut asset_changes: Vec<dossiers::bundle::AssetChange> = Vec::new();
-        let mut spec_meta: Vec<dossiers::bundle::PrSpecMeta> = Vec::new();
-
-        for target in &targets {
-            match build_pr_change_target(
-                client,
-                metadata_reader,
-                spec_root,
-                spec_root_relative,
-                &pull,
-                &files,
-                target,
-            ) {
-                Ok(Some(built)) => {
-                    spec_changes.extend(built.spec_changes);
-                    asset_changes.extend(built.asset_changes);
-                    if let Some(meta) = built.meta {
-                        spec_meta.push(meta);
-                    }
-                }
-                Ok(None) => {}
-                Err(err) => eprintln!(
-                    "Warning: failed to build PR #{} change for spec {}: {err}",
-                    pull.number, target.spec_id
-                ),
-            }
-        }
-
-        if spec_changes.is_empty() && asset_changes.is_empty() {
-            continue;
-        }
-
-        let state = if pull.draft { "DRAFT" } else { "REVIEW" };
-        let url = if pull.html_url.is_empty() {
-            format!("https://github.com/{repo_slug}/pull/{}", pull.number)
-        } else {
-            pull.html_url.clone()
-        };
-        let branch = if pull.head_ref.is_empty() {
-            format!("pull/{}", pull.number)
-        } else {
-            pull.head_ref.clone()
-        };
-
-        eprintln!(
-            "PR #{}: {} spec change(s), {} asset change(s).",
-            pull.number,
-            spec_changes.len(),
-            asset_changes.len()
-        );
-
-        change_sets.push(dossiers::bundle::PrChangeSet {
-            pr_number: pull.number,
-            branch,
-            head_sha: pull.head_sha.clone(),
-            title: pull.title.clone(),
-            author: pull.author.clone(),
-            state: state.to_string(),
-            url,
-            created_at: millis_to_utc(pull.created_at),
-            updated_at: millis_to_utc(pull.updated_at),
-            spec_changes,
-            asset_changes,
-            spec_meta,
-        });
+            &pull,
+        ));
     }
 
     Ok(change_sets)
 }
 
-/// Build the change(s) for a single PR target: the PR's version of the source
-/// document (or the local baseline if the PR didn't touch it) plus any changed
-/// or removed assets, along with resolved display metadata.
-fn build_pr_change_target(
-    client: &GithubClient,
+/// The sparse change-set for one PR: the PR's version of each spec it
+/// touches, or `None` when it touches none.
+fn build_pr_change_set(
+    client: &dyn Forge,
     metadata_reader: &MetadataReader,
     spec_root: &Path,
     spec_root_relative: &Path,
-    pull: &GithubPull,
-    files: &[GithubFile],
-    target: &SpecTarget,
-) -> Result<Option<BuiltPrTarget>> {
-    let spec_dir_rel = target.spec_relative_dir.as_path();
-
-    // Partition this target's touched files into upserts and removals, keyed by
-    // their path within the spec directory.
-    let mut upserts: Vec<(String, &GithubFile)> = Vec::new();
-    let mut removals: Vec<String> = Vec::new();
-    for file in files {
-        let repo_path = Path::new(&file.filename);
-        let Some((_current_id, relative_path)) =
-            spec_id_from_repo_path(repo_path, spec_root_relative)
-        else {
-            continue;
-        };
-        let Some(within) = within_spec_dir(&relative_path, spec_dir_rel) else {
-            continue;
-        };
-        if file.status == "removed" {
-            removals.push(within);
-        } else {
-            upserts.push((within, file));
+    pr_number_as_spec_id: bool,
+    pull: &PullRequest,
+) -> Option<dossiers::bundle::PrChangeSet> {
+    let files = match client.list_pull_files(pull.number) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("Warning: skipping PR #{}: {err}", pull.number);
+            return None;
         }
-    }
-    if upserts.is_empty() && removals.is_empty() {
-        return Ok(None);
-    }
+    };
 
-    // Pick the source doc: prefer Markdown, then AsciiDoc, alphabetical within.
-    let mut docs: Vec<&(String, &GithubFile)> = upserts
-        .iter()
-        .filter(|(within, _)| bundle_format_from_path(within).is_some())
-        .collect();
-    docs.sort_by(|a, b| {
-        doc_rank(&a.0)
-            .cmp(&doc_rank(&b.0))
-            .then_with(|| a.0.cmp(&b.0))
-    });
+    let targets = map_pull_to_specs(
+        &files,
+        spec_root_relative,
+        pull.number,
+        pr_number_as_spec_id,
+    )?;
 
     let mut spec_changes: Vec<dossiers::bundle::SpecChange> = Vec::new();
     let mut asset_changes: Vec<dossiers::bundle::AssetChange> = Vec::new();
+    let mut spec_meta: Vec<dossiers::bundle::PrSpecMeta> = Vec::new();
 
-    let dir_name = pr_spec_dir_name(&target.spec_id, spec_dir_rel);
-
-    // Source document: the PR's version if it changed one, else the local
-    // baseline so the revision still renders.
-    let source_within = docs.first().map(|d| d.0.clone());
-    let (source_path, format, source_bytes) = if let Some((within, file)) =
-        docs.first().map(|d| (d.0.clone(), d.1))
-    {
-        let bytes = download_pr_file(client, file, &pull.head_sha)?;
-        let format = bundle_format_from_path(&within).expect("candidate doc has a doc extension");
-        (within, format, bytes)
-    } else if let Some((within, format, bytes)) = load_baseline_source(spec_root, spec_dir_rel) {
-        (within, format, bytes)
-    } else if !removals.is_empty() {
-        // Nothing to render and the source is gone — record a spec removal.
-        spec_changes.push(dossiers::bundle::SpecChange::Remove {
-            id: target.spec_id.clone(),
-        });
-        return Ok(Some(BuiltPrTarget {
-            spec_changes,
-            asset_changes,
-            meta: None,
-        }));
-    } else {
-        return Ok(None);
-    };
-
-    // Remaining changed files (not the source doc) travel as bundled assets.
-    let mut assets: Vec<dossiers::bundle::Asset> = Vec::new();
-    for (within, file) in &upserts {
-        if source_within.as_ref() == Some(within) {
-            continue;
+    for target in &targets {
+        match build_pr_change_target(
+            client,
+            metadata_reader,
+            spec_root,
+            spec_root_relative,
+            pull,
+            &files,
+            target,
+        ) {
+            Ok(Some(built)) => {
+                spec_changes.extend(built.spec_changes);
+                asset_changes.extend(built.asset_changes);
+                if let Some(meta) = built.meta {
+                    spec_meta.push(meta);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!(
+                "Warning: failed to build PR #{} change for spec {}: {err}",
+                pull.number, target.spec_id
+            ),
         }
-        let bytes = download_pr_file(client, file, &pull.head_sha)?;
-        assets.push(dossiers::bundle::Asset {
-            path: within.clone(),
-            content_type: None,
-            bytes,
-        });
     }
 
-    // Removed non-source files become asset removals.
-    for within in &removals {
-        if source_within.as_ref() == Some(within) {
-            continue;
-        }
-        asset_changes.push(dossiers::bundle::AssetChange::Remove {
-            spec_id: target.spec_id.clone(),
-            path: within.clone(),
-        });
+    if spec_changes.is_empty() && asset_changes.is_empty() {
+        return None;
+    }
+
+    let state = if pull.draft { "DRAFT" } else { "REVIEW" };
+    let url = if pull.html_url.is_empty() {
+        client.pull_url(pull.number)
+    } else {
+        pull.html_url.clone()
+    };
+    let branch = if pull.head_ref.is_empty() {
+        format!("pull/{}", pull.number)
+    } else {
+        pull.head_ref.clone()
+    };
+
+    eprintln!(
+        "PR #{}: {} spec change(s), {} asset change(s).",
+        pull.number,
+        spec_changes.len(),
+        asset_changes.len()
+    );
+    let review = PullReviewInfo::fetch(client, pull);
+
+    Some(dossiers::bundle::PrChangeSet {
+        pr_number: pull.number,
+        branch,
+        head_sha: pull.head_sha.clone(),
+        title: pull.title.clone(),
+        author: pull.author.clone(),
+        state: state.to_string(),
+        url,
+        created_at: millis_to_utc(pull.created_at),
+        updated_at: millis_to_utc(pull.updated_at),
+        reviewers: review.reviewers,
+        reviews: review.reviews,
+        labels: review.labels,
+        merge_commit_sha: review.merge_commit_sha,
+        spec_changes,
+        asset_changes,
+        spec_meta,
+    })
+}
+
+/// Marks the comment `pr-comment` owns, so reruns edit it in place.
+const PR_COMMENT_MARKER: &str = "<!-- dossiers:pr-summary -->";
+
+/// The PR a CI job runs for: GitHub's `refs/pull/<n>/merge` in `GITHUB_REF`,
+/// or GitLab's `CI_MERGE_REQUEST_IID`.
+fn pull_number_from_env() -> Option<u64> {
+    let from_github = env::var("GITHUB_REF").ok().and_then(|git_ref| {
+        git_ref
+            .strip_prefix("refs/pull/")?
+            .split('/')
+            .next()?
+            .parse()
+            .ok()
+    });
+    from_github.or_else(|| env::var("CI_MERGE_REQUEST_IID").ok()?.trim().parse().ok())
+}
+
+/// One spec row of a PR summary comment.
+struct PrCommentSpec {
+    spec_id: String,
+    /// Title and status on the default branch; `None` for a new spec.
+    base: Option<(String, String)>,
+    /// Title and status in the PR; `None` when the PR removes the spec.
+    head: Option<(String, String)>,
+    preview_url: Option<String>,
+}
+
+fn run_pr_comment(
+    input_path: PathBuf,
+    config_path: Option<PathBuf>,
+    pr: Option<u64>,
+    site_url: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let project_root = project_root_from(config_path.as_deref(), &input_path);
+    let project_config = load_project_configuration(&project_root, config_path.as_deref());
+    let spec_root = resolve_spec_input_path(&input_path, &project_config);
+    let Some(number) = pr.or_else(pull_number_from_env) else {
+        bail!("no pull request to comment on; pass --pr or run in a pull request pipeline");
+    };
+    let client = connect_forge(&spec_root, &project_config)
+        .map_err(|reason| anyhow!("cannot comment on pull requests: {reason}"))?;
+    let git_repo = open_git_repository(&spec_root);
+    let repo_root = git_repo
+        .as_ref()
+        .map(|repo| repo.workdir().to_path_buf())
+        .unwrap_or_else(|| project_root.clone());
+    let spec_root_relative = relative_to(&spec_root, &repo_root).with_context(|| {
+        format!(
+            "relating spec root {} to repository root {}",
+            spec_root.display(),
+            repo_root.display()
+        )
+    })?;
+
+    let pull = client
+        .list_open_pulls()
+        .with_context(|| format!("listing open {} pull requests", client.kind()))?
+        .into_iter()
+        .find(|pull| pull.number == number)
+        .with_context(|| format!("{} #{number} is not open", client.kind()))?;
+    let metadata_reader = MetadataReader::new(project_config.clone());
+    let Some(change_set) = build_pr_change_set(
+        client.as_ref(),
+        &metadata_reader,
+        &spec_root,
+        &spec_root_relative,
+        project_config.pr_number_as_spec_id,
+        &pull,
+    ) else {
+        println!("#{number} changes no specs; not commenting.");
+        return Ok(());
+    };
+
+    let mut touched: Vec<String> = change_set
+        .spec_changes
+        .iter()
+        .map(|change| match change {
+            dossiers::bundle::SpecChange::Upsert(spec) => spec.id.clone(),
+            dossiers::bundle::SpecChange::Remove { id } => id.clone(),
+        })
+        .chain(change_set.spec_meta.iter().map(|meta| meta.spec_id.clone()))
+        .collect();
+    touched.sort();
+    touched.dedup();
+
+    // Read the mainline versions from git, so the comment can say what changed.
+    let mut base_specs: HashMap<String, SpecDocument> = HashMap::new();
+    if let Some(repo) = &git_repo {
+        if let Some(base) = repo.base_commit_sha(project_config.default_branch.as_deref()) {
+            let workspace = git_ref_workspace();
+            let only: HashSet<String> = touched.iter().cloned().collect();
+            match load_git_ref_specs(
+                repo,
+                &base,
+                &spec_root_relative,
+                &workspace.join("base"),
+                &project_config,
+                Some(&only),
+            ) {
+                Ok(specs) => {
+                    base_specs.extend(specs.into_iter().map(|(spec, _)| (spec.id.clone(), spec)))
+                }
+                Err(err) => {
+                    eprintln!("Warning: unable to read specs on the default branch: {err:#}")
+                }
+            }
+            let _ = fs::remove_dir_all(&workspace);
+        }
+    }
+
+    let site_url = site_url
+        .or_else(|| project_config.site_url.clone())
+        .map(|url| url.trim_end_matches('/').to_string());
+    // Findings come from the local tree, which is the pull request's checkout.
+    let reports = check_specs(&spec_root, &project_root, &project_config);
+    // A PR document without a status falls back to the review status; report
+    // it as keeping its mainline status rather than as a change.
+    let undeclared_status: HashSet<&str> = reports
+        .specs
+        .iter()
+        .filter(|spec| {
+            let display_name = display_name_from_dir(&spec.dir_name);
+            let parsed = metadata_reader.read(&spec.source, spec.format, &display_name);
+            parsed.metadata.status.is_none()
+        })
+        .map(|spec| spec.id.as_str())
+        .collect();
+    let prefix = project_config.prefix.clone().unwrap_or_default();
+    let specs: Vec<PrCommentSpec> = touched
+        .iter()
+        .map(|id| {
+            let base = base_specs
+                .get(id)
+                .map(|spec| (spec.title.clone(), spec.status.clone()));
+            let head = change_set
+                .spec_meta
+                .iter()
+                .find(|meta| &meta.spec_id == id)
+                .map(|meta| {
+                    let status = match &base {
+                        Some((_, status)) if undeclared_status.contains(id.as_str()) => {
+                            status.clone()
+                        }
+                        _ => meta.status.clone(),
+                    };
+                    (meta.title.clone(), status)
+                });
+            let preview_url = site_url.as_ref().filter(|_| head.is_some()).map(|url| {
+                if base.is_some() {
+                    format!("{url}/{id}/pr/{number}")
+                } else {
+                    format!("{url}/{id}")
+                }
+            });
+            PrCommentSpec {
+                spec_id: format!("{prefix}{id}"),
+                base,
+                head,
+                preview_url,
+            }
+        })
+        .collect();
+
+    let doc_paths: Vec<String> = reports
+        .specs
+        .iter()
+        .filter(|spec| touched.contains(&spec.id))
+        .map(|spec| spec.doc_path.display().to_string())
+        .collect();
+    let root_prefix = format!("{}/", project_root.display());
+    let mut findings: Vec<(bool, String)> = Vec::new();
+    for report in reports.categories() {
+        let entries = report
+            .errors
+            .iter()
+            .map(|entry| (true, entry))
+            .chain(report.warnings.iter().map(|entry| (false, entry)));
+        for (is_error, entry) in entries {
+            let mentions = |text: &str| doc_paths.iter().any(|path| text.contains(path.as_str()));
+            if !mentions(&entry.message) && !entry.location.as_deref().is_some_and(mentions) {
+                continue;
+            }
+            let text = match &entry.location {
+                Some(location) => format!("{} ({location})", entry.message),
+                None => entry.message.clone(),
+            };
+            findings.push((is_error, text.replace(&root_prefix, "")));
+        }
+    }
+
+    let body = compose_pr_comment(&specs, &findings);
+    if dry_run {
+        println!("{body}");
+        return Ok(());
+    }
+    let url = client
+        .upsert_pull_comment(number, PR_COMMENT_MARKER, &body)
+        .with_context(|| format!("commenting on {} #{number}", client.kind()))?;
+    println!("Updated the preview summary on #{number}: {url}");
+    Ok(())
+}
+
+/// The markdown of a PR summary comment: a table of the changed specs, a
+/// callout for status changes, and the `check` findings. `findings` pairs
+/// each message with whether it is an error.
+fn compose_pr_comment(specs: &[PrCommentSpec], findings: &[(bool, String)]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let change = |before: Option<&str>, after: Option<&str>, emphasise: bool| match (before, after)
+    {
+        (Some(before), Some(after)) if before != after => {
+            if emphasise {
+                format!("{} → **{}**", cell(before), cell(after))
+            } else {
+                format!("{} → {}", cell(before), cell(after))
+            }
+        }
+        (_, Some(after)) => cell(after),
+        (Some(before), None) => format!("~~{}~~", cell(before)),
+        (None, None) => String::new(),
+    };
+
+    let mut body = format!("{PR_COMMENT_MARKER}\n### Spec preview\n\n");
+    body.push_str("| Spec | Title | Status | Preview |\n| --- | --- | --- | --- |\n");
+    for spec in specs {
+        let base = spec.base.as_ref();
+        let head = spec.head.as_ref();
+        let preview = match (&spec.preview_url, head) {
+            (Some(url), _) => format!("[preview]({url})"),
+            (None, None) => "removed".to_string(),
+            (None, Some(_)) => "—".to_string(),
+        };
+        body.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            spec.spec_id,
+            change(
+                base.map(|b| b.0.as_str()),
+                head.map(|h| h.0.as_str()),
+                false
+            ),
+            change(base.map(|b| b.1.as_str()), head.map(|h| h.1.as_str()), true),
+            preview
+        ));
+    }
+
+    let status_changes: Vec<String> = specs
+        .iter()
+        .filter_map(|spec| {
+            let (_, before) = spec.base.as_ref()?;
+            let (_, after) = spec.head.as_ref()?;
+            (before != after).then(|| format!("{} from {before} to **{after}**", spec.spec_id))
+        })
+        .collect();
+    if !status_changes.is_empty() {
+        body.push_str("\n> [!IMPORTANT]\n> This pull request changes the status of ");
+        body.push_str(&status_changes.join(", "));
+        body.push_str(".\n");
+    }
+
+    body.push('\n');
+    if findings.is_empty() {
+        body.push_str("No `dossiers check` findings in the changed specs.\n");
+    } else {
+        body.push_str("**`dossiers check` findings**\n\n");
+        for (is_error, text) in findings {
+            let mark = if *is_error { "✗" } else { "!" };
+            body.push_str(&format!("- {mark} {text}\n"));
+        }
+    }
+    body
+}
+
+/// Build the change(s) for a single PR target: the PR's version of the source
+/// document (or the local baseline if the PR didn't touch it) plus any changed
+/// or removed assets, along with resolved display metadata.
+fn build_pr_change_target(
+    client: &dyn Forge,
+    metadata_reader: &MetadataReader,
+    spec_root: &Path,
+    spec_root_relative: &Path,
+    pull: &PullRequest,
+    files: &[PullFile],
+    target: &SpecTarget,
+) -> Result<Option<BuiltPrTarget>> {
+    let spec_dir_rel = target.spec_relative_dir.as_path();
+
+    // Partition this target's touched files into upserts and removals, keyed by
+    // their path within the spec directory.
+    let mut upserts: Vec<(String, &PullFile)> = Vec::new();
+    let mut removals: Vec<String> = Vec::new();
+    for file in files {
+        let repo_path = Path::new(&file.filename);
+        let Some((_current_id, relative_path)) =
+            spec_id_from_repo_path(repo_path, spec_root_relative)
+        else {
+            continue;
+        };
+        let Some(within) = within_spec_dir(&relative_path, spec_dir_rel) else {
+            continue;
+        };
+        if file.status == "removed" {
+            removals.push(within);
+        } else {
+            upserts.push((within, file));
+        }
+    }
+    if upserts.is_empty() && removals.is_empty() {
+        return Ok(None);
+    }
+
+    // Pick the source doc: prefer Markdown, then AsciiDoc, alphabetical within.
+    let mut docs: Vec<&(String, &PullFile)> = upserts
+        .iter()
+        .filter(|(within, _)| bundle_format_from_path(within).is_some())
+        .collect();
+    docs.sort_by(|a, b| {
+        doc_rank(&a.0)
+            .cmp(&doc_rank(&b.0))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let mut spec_changes: Vec<dossiers::bundle::SpecChange> = Vec::new();
+    let mut asset_changes: Vec<dossiers::bundle::AssetChange> = Vec::new();
+
+    let dir_name = pr_spec_dir_name(&target.spec_id, spec_dir_rel);
+
+    // Source document: the PR's version if it changed one, else the local
+    // baseline so the revision still renders.
+    let source_within = docs.first().map(|d| d.0.clone());
+    let (source_path, format, source_bytes) = if let Some((within, file)) =
+        docs.first().map(|d| (d.0.clone(), d.1))
+    {
+        let bytes = download_pr_file(client, file, &pull.head_sha)?;
+        let format = bundle_format_from_path(&within).expect("candidate doc has a doc extension");
+        (within, format, bytes)
+    } else if let Some((within, format, bytes)) = load_baseline_source(spec_root, spec_dir_rel) {
+        (within, format, bytes)
+    } else if !removals.is_empty() {
+        // Nothing to render and the source is gone — record a spec removal.
+        spec_changes.push(dossiers::bundle::SpecChange::Remove {
+            id: target.spec_id.clone(),
+        });
+        return Ok(Some(BuiltPrTarget {
+            spec_changes,
+            asset_changes,
+            meta: None,
+        }));
+    } else {
+        return Ok(None);
+    };
+
+    // Remaining changed files (not the source doc) travel as bundled assets.
+    let mut assets: Vec<dossiers::bundle::Asset> = Vec::new();
+    for (within, file) in &upserts {
+        if source_within.as_ref() == Some(within) {
+            continue;
+        }
+        let bytes = download_pr_file(client, file, &pull.head_sha)?;
+        assets.push(dossiers::bundle::Asset {
+            path: within.clone(),
+            content_type: None,
+            bytes,
+        });
+    }
+
+    // Removed non-source files become asset removals.
+    for within in &removals {
+        if source_within.as_ref() == Some(within) {
+            continue;
+        }
+        asset_changes.push(dossiers::bundle::AssetChange::Remove {
+            spec_id: target.spec_id.clone(),
+            path: within.clone(),
+        });
     }
 
     // Resolve display metadata from the source, falling back to PR fields.
@@ -3647,19 +6813,20 @@ fn build_pr_change_target(
             }
         });
     // Credit the PR: declared frontmatter authors override; otherwise the PR
-    // author, whose avatar GitHub already gave us on the pulls listing.
+    // author, whose avatar the forge already gave us on the pulls listing.
+    let resolver = metadata_reader.author_resolver();
     let authors_meta: Vec<dossiers::bundle::Author> = if meta.raw_authors.is_empty() {
         match &pull.author {
-            Some(login) => vec![dossiers::bundle::Author {
-                name: login.clone(),
-                avatar_url: pull.avatar_url.clone(),
-                url: Some(format!("https://github.com/{login}")),
-            }],
+            Some(login) => vec![resolver.resolve_login(
+                login,
+                pull.avatar_url.clone(),
+                client.profile_url(login),
+            )],
             None => Vec::new(),
         }
     } else {
         let seeds = dossiers::authors::seeds_from_names(&meta.raw_authors);
-        dossiers::authors::resolve_authors(&seeds, &dossiers::authors::AuthorResolver::local())
+        dossiers::authors::resolve_authors(&seeds, &resolver)
     };
     let authors: Vec<String> = if authors_meta.is_empty() {
         pull.author.clone().map(|a| vec![a]).unwrap_or_default()
@@ -3693,6 +6860,7 @@ fn build_pr_change_target(
     let links = resolve_meta_links(&meta.links);
     let extra_json = metadata_extra_to_json(&meta.extra);
     let fields = resolve_meta_fields(&extra_json, metadata_reader.extra_fields());
+    let visibility = metadata_reader.resolve_visibility(meta.visibility.as_deref(), &status);
 
     Ok(Some(BuiltPrTarget {
         spec_changes,
@@ -3707,6 +6875,7 @@ fn build_pr_change_target(
             updated,
             links,
             fields,
+            visibility: visibility.to_wire(),
         }),
     }))
 }
@@ -3772,7 +6941,7 @@ fn pr_spec_dir_name(spec_id: &str, spec_dir_rel: &Path) -> String {
     }
 }
 
-fn download_pr_file(client: &GithubClient, file: &GithubFile, head_sha: &str) -> Result<Vec<u8>> {
+fn download_pr_file(client: &dyn Forge, file: &PullFile, head_sha: &str) -> Result<Vec<u8>> {
     if let Some(raw_url) = file.raw_url.as_deref() {
         match client.download_bytes(raw_url) {
             Ok(bytes) => return Ok(bytes),
@@ -3876,6 +7045,14 @@ fn relative_to_spec_root(path: &Path, spec_root_relative: &Path) -> Option<PathB
     None
 }
 
+/// The root `file=` snippets resolve against: the git work tree containing
+/// the project, or the project root itself outside git.
+fn snippet_root(project_root: &Path) -> PathBuf {
+    open_git_repository(project_root)
+        .map(|repo| repo.workdir().to_path_buf())
+        .unwrap_or_else(|| project_root.to_path_buf())
+}
+
 fn build_app_state(
     input_path: &Path,
     project_root: &Path,
@@ -3884,9 +7061,41 @@ fn build_app_state(
     project_config: ProjectConfiguration,
 ) -> Result<(AppState, Vec<StaticMount>)> {
     let (specs, static_mounts) = load_and_sort_specs(input_path, &project_config)?;
+    let spec_root = resolve_spec_input_path(input_path, &project_config);
+    let state = app_state_from_specs(
+        specs,
+        &spec_root,
+        project_root,
+        site_name,
+        assets,
+        project_config,
+    );
+    Ok((state, static_mounts))
+}
+
+/// Assemble the site state around already-loaded specs. `spec_root` is where
+/// the spec sources live, used to infer the layout for new documents.
+fn app_state_from_specs(
+    specs: Vec<SpecDocument>,
+    spec_root: &Path,
+    project_root: &Path,
+    site_name: String,
+    assets: Assets,
+    project_config: ProjectConfiguration,
+) -> AppState {
     let generated_at = chrono::Utc::now().timestamp_millis();
     let spec_ids = specs.iter().map(|s| s.id.clone()).collect::<HashSet<_>>();
-    let renderer = DocRenderer::new();
+    let mut diagrams = DiagramRegistry::from_config(&project_config.diagrams, project_root);
+    if diagrams.handles("mermaid") {
+        diagrams = diagrams.with_mermaid_script(assets.mermaid_script());
+    }
+    let (highlighter, problems) = Highlighter::from_config(&project_config.highlight, project_root);
+    for problem in problems {
+        eprintln!("Warning: {problem}");
+    }
+    let renderer = DocRenderer::with_diagrams(diagrams)
+        .with_highlighter(highlighter)
+        .with_snippet_root(snippet_root(project_root));
     let specs_by_id = specs
         .iter()
         .cloned()
@@ -3894,22 +7103,22 @@ fn build_app_state(
         .collect::<HashMap<_, _>>();
 
     // Try config first, then fall back to git remote
+    let github = project_config.github_host();
     let repo_from_config = project_config
         .repository
         .as_deref()
-        .and_then(parse_github_repo);
+        .and_then(|raw| github.parse_repo(raw));
     let git_repo = open_git_repository(project_root);
     let repo_from_git = git_repo
         .as_ref()
         .and_then(|repo| repo.remote_url())
         .as_deref()
-        .and_then(parse_github_repo);
+        .and_then(|raw| github.parse_repo(raw));
     let github_repo = repo_from_config
         .or(repo_from_git)
         .map(|repo| format!("{}/{}", repo.owner, repo.name));
 
     // Compute create config if GitHub repo is available
-    let spec_root = resolve_spec_input_path(input_path, &project_config);
     let create_config = github_repo.as_ref().map(|repo| {
         // Find the next available spec ID by parsing existing IDs as numbers
         let next_id = spec_ids
@@ -3956,6 +7165,7 @@ fn build_app_state(
 
         CreateDocConfig {
             github_repo: repo.clone(),
+            repo_url: github.repo_url(repo),
             default_branch: project_config
                 .default_branch
                 .clone()
@@ -3967,7 +7177,7 @@ fn build_app_state(
         }
     });
 
-    let state = AppState {
+    AppState {
         specs,
         specs_by_id,
         spec_ids,
@@ -3976,14 +7186,18 @@ fn build_app_state(
         site_name,
         site_description: project_config.description.unwrap_or_default(),
         github_repo,
+        github,
+        pull_links: HashMap::new(),
+        pull_reviews: HashMap::new(),
         extra_fields: project_config.extra_metadata_fields.clone(),
         generated_at,
         assets,
         renderer,
         create_config,
-    };
-
-    Ok((state, static_mounts))
+        hidden_spec_ids: HashSet::new(),
+        teams: project_config.teams,
+        workflow: project_config.workflow,
+    }
 }
 
 fn copy_dir_contents(source: &Path, target: &Path) -> Result<()> {
@@ -4338,26 +7552,61 @@ async fn status_page(
         .body(markup.into_string())
 }
 
-/// Byline authors for a card: an overlapping avatar stack (the first few that
-/// have avatars) plus a compact label. Not individually linked (the whole card
-/// is the link). Mirrors the server renderer's `byline_authors`.
-fn byline_authors(authors: &[dossiers::bundle::Author]) -> Markup {
-    let stack: Vec<&dossiers::bundle::Author> = authors
-        .iter()
-        .filter(|a| a.avatar_url.is_some())
-        .take(3)
-        .collect();
-    html! {
-        span class="byline-item byline-authors" {
-            @if !stack.is_empty() {
-                span class="byline-avatars" {
-                    @for author in &stack {
-                        @if let Some(avatar) = &author.avatar_url {
-                            img class="author-avatar author-avatar--sm" src=(avatar)
-                                alt="" loading="lazy" referrerpolicy="no-referrer";
-                        }
-                    }
-                }
+async fn team_redirect(path: web::Path<String>) -> impl Responder {
+    let slug = path.into_inner();
+    HttpResponse::MovedPermanently()
+        .append_header(("Location", format!("/team/{slug}")))
+        .finish()
+}
+
+async fn team_page(
+    path: web::Path<String>,
+    state: web::Data<ReloadableAppState>,
+) -> impl Responder {
+    let slug = path.into_inner();
+    let loaded = match state.load() {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("Failed to load specs for team page: {err:?}");
+            return HttpResponse::InternalServerError()
+                .body(format!("Failed to load team page: {err}"));
+        }
+    };
+    let Some(team) = loaded
+        .team_summaries()
+        .into_iter()
+        .find(|team| team.slug == slug)
+    else {
+        return HttpResponse::NotFound().body("Team not found");
+    };
+
+    let matching = loaded.team_specs(&slug);
+    let markup = render_team(&loaded, &team, &matching, "/", false);
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(markup.into_string())
+}
+
+/// Byline authors for a card: an overlapping avatar stack (the first few that
+/// have avatars) plus a compact label. Not individually linked (the whole card
+/// is the link). Mirrors the server renderer's `byline_authors`.
+fn byline_authors(prefix: &str, authors: &[dossiers::bundle::Author]) -> Markup {
+    let stack: Vec<&dossiers::bundle::Author> = authors
+        .iter()
+        .filter(|a| a.avatar_url.is_some())
+        .take(3)
+        .collect();
+    html! {
+        span class="byline-item byline-authors" {
+            @if !stack.is_empty() {
+                span class="byline-avatars" {
+                    @for author in &stack {
+                        @if let Some(avatar) = &author.avatar_url {
+                            img class="author-avatar author-avatar--sm" src=(avatar_src(prefix, avatar))
+                                alt="" loading="lazy" referrerpolicy="no-referrer";
+                        }
+                    }
+                }
             }
             span class="byline-author-names" { (byline_author_label(authors)) }
         }
@@ -4388,7 +7637,7 @@ fn author_row(prefix: &str, authors: &[dossiers::bundle::Author]) -> Markup {
                         a class="spec-author-link"
                             href={(join_prefix(prefix, format!("author/{}", slugify_author(&author.name))))} {
                             @if let Some(avatar) = &author.avatar_url {
-                                img class="author-avatar" src=(avatar)
+                                img class="author-avatar" src=(avatar_src(prefix, avatar))
                                     alt="" loading="lazy" referrerpolicy="no-referrer";
                             }
                             span class="author-name" { (&author.name) }
@@ -4400,7 +7649,7 @@ fn author_row(prefix: &str, authors: &[dossiers::bundle::Author]) -> Markup {
     }
 }
 
-fn spec_card(href: String, display_id: &str, spec: &SpecDocument) -> Markup {
+fn spec_card(prefix: &str, href: String, display_id: &str, spec: &SpecDocument) -> Markup {
     html! {
         a class="spec-card" href=(href) {
             div class="spec-card-main" {
@@ -4410,7 +7659,7 @@ fn spec_card(href: String, display_id: &str, spec: &SpecDocument) -> Markup {
                 }
                 div class="spec-card-byline" {
                     @if !spec.authors_meta.is_empty() {
-                        (byline_authors(&spec.authors_meta))
+                        (byline_authors(prefix, &spec.authors_meta))
                     }
                     span class="byline-item" { "Created " (format_spec_date(spec.created, false).unwrap_or_else(|| "n/a".into())) }
                     span class="byline-item" { "Updated " (format_spec_date(spec.updated, false).unwrap_or_else(|| "n/a".into())) }
@@ -4425,6 +7674,7 @@ fn render_index(state: &AppState, prefix: &str, trailing_slashes: bool) -> Marku
     let site_name = &state.site_name;
     let index_search_js = state.assets.index_search_script();
     let listed_specs: Vec<&SpecDocument> = state.specs.iter().filter(|spec| spec.listed).collect();
+    let teams = state.team_summaries();
     let create_config = &state.create_config;
     let content = html! {
         main class="container" {
@@ -4435,6 +7685,7 @@ fn render_index(state: &AppState, prefix: &str, trailing_slashes: bool) -> Marku
                         button type="button" class="create-btn" onclick="openCreateModal()" { "+ Create..." }
                         // Hidden data for JS
                         input type="hidden" id="create-github-repo" value=(config.github_repo) {}
+                        input type="hidden" id="create-repo-url" value=(config.repo_url) {}
                         input type="hidden" id="create-branch" value=(config.default_branch) {}
                         input type="hidden" id="create-subdir" value=(config.subdirectory.as_deref().unwrap_or("")) {}
                         input type="hidden" id="create-next-id" value=(format!("{:04}", config.next_id)) {}
@@ -4442,14 +7693,24 @@ fn render_index(state: &AppState, prefix: &str, trailing_slashes: bool) -> Marku
                         input type="hidden" id="create-structure" value=(config.structure) {}
                     }
                 }
-                p { "Browse all specifications documents. Search by title, ID, or author to jump straight to what you need." }
+                p { "Browse all specifications documents. Search by title, ID, author, owner, or team to jump straight to what you need." }
                 form class="search-bar" role="search" onsubmit="event.preventDefault();" {
                     label class="sr-only" for="spec-search" { "Search specifications" }
                     div class="search-input" {
-                        input id="spec-search" type="search" name="q" placeholder="Search by title, ID, or author" autocomplete="off" autofocus {}
+                        input id="spec-search" type="search" name="q" placeholder="Search by title, ID, author, owner, or team" autocomplete="off" autofocus {}
                         span class="search-hint" { "/" }
                     }
                 }
+                @if !teams.is_empty() {
+                    nav class="team-facets" aria-label="Teams" {
+                        span class="meta-label" { "Teams" }
+                        @for team in &teams {
+                            a class="tag team" href={(join_prefix(prefix, format!("team/{}", team.slug)))} {
+                                (team.name) " " span class="facet-count" { (team.count) }
+                            }
+                        }
+                    }
+                }
             }
 
             @if listed_specs.is_empty() {
@@ -4462,8 +7723,11 @@ fn render_index(state: &AppState, prefix: &str, trailing_slashes: bool) -> Marku
                             data-title={(spec.title.to_lowercase())}
                             data-id={(base_id.to_lowercase())}
                             data-authors={(spec.authors.iter().map(|a| a.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                            data-owners={(spec.owners.iter().map(|o| o.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                            data-teams={(state.spec_teams(spec).iter().map(|t| t.to_lowercase()).collect::<Vec<_>>().join(" "))}
                         {
                             (spec_card(
+                                prefix,
                                 join_spec_link(prefix, &spec.id, "", trailing_slashes),
                                 base_id,
                                 spec,
@@ -4536,27 +7800,40 @@ fn render_spec(
     let page_id_label = format!("#{base_id}");
     let title_id = if let Some(pr_number) = spec.pr_number {
         format!("{display_id} PR #{pr_number}")
+    } else if let Some(git_ref) = &spec.git_ref {
+        format!("{display_id} {git_ref}")
     } else {
         display_id.clone()
     };
     let title = format!("{title_id} {} - {}", spec.title, state.site_name);
     let description = format!("Rendered specification {}", spec.dir_name);
-    let pr_review = match (spec.pr_number, state.github_repo.as_deref()) {
-        (Some(pr_number), Some(repo_slug)) => Some((
+    let pr_review = spec.pr_number.and_then(|pr_number| {
+        if let Some(link) = state.pull_links.get(&pr_number) {
+            return Some(link.clone());
+        }
+        let repo_slug = state.github_repo.as_deref()?;
+        Some((
             format!("{repo_slug}#{pr_number}"),
-            format!("https://github.com/{repo_slug}/pull/{pr_number}"),
-        )),
-        _ => None,
-    };
+            state.github.pull_url(repo_slug, pr_number),
+        ))
+    });
+    let pull_review = spec
+        .pr_number
+        .and_then(|pr_number| state.pull_reviews.get(&pr_number));
     let links = resolve_meta_links(&spec.links);
     let extra_pairs = resolve_meta_fields(&spec.extra, &state.extra_fields);
     let revisions = state.revisions.get(&base_id);
-    let is_pr_page = spec.pr_number.is_some();
-    let original_link = spec.revision_of.as_ref().map(|id| {
-        let display = format_display_id(&state.display_prefix, id);
-        let href = join_spec_link(prefix, id, "", trailing_slashes);
-        (display, href)
-    });
+    let is_pr_page = spec.pr_number.is_some() || spec.git_ref.is_some();
+    // A release may hold specs since removed from mainline.
+    let original_link = spec
+        .revision_of
+        .as_ref()
+        .filter(|id| state.specs_by_id.contains_key(*id))
+        .map(|id| {
+            let display = format_display_id(&state.display_prefix, id);
+            let href = join_spec_link(prefix, id, "", trailing_slashes);
+            (display, href)
+        });
 
     let mini_toc_js = state.assets.mini_toc_script();
     let needs_mermaid = has_mermaid_markup(rendered_html);
@@ -4590,6 +7867,17 @@ fn render_spec(
                        a class="spec-metadata-link" href=(pr_href) target="_blank" rel="noreferrer noopener" { (pr_text) }
                     }
                 }
+                @match &spec.git_ref {
+                    Some(RevisionSource::Branch(name)) => {
+                        span { "—" }
+                        span { "Preview of branch " code { (name) } }
+                    }
+                    Some(RevisionSource::Tag(name)) => {
+                        span { "—" }
+                        span { "As released in " code { (name) } }
+                    }
+                    _ => {}
+                }
             }
             div class="spec-header" {
                 div class="spec-id-block" { span class="spec-id" { (page_id_label) } }
@@ -4634,6 +7922,64 @@ fn render_spec(
                 }
             }
 
+            @let teams = state.spec_teams(spec);
+            @if !teams.is_empty() {
+                div class="spec-header" {
+                    span class="meta-label" { "Team" @if teams.len() > 1 { "s" } }
+                    span class="author-list" {
+                        @for team in &teams {
+                            a class="tag team" href={(join_prefix(prefix, format!("team/{}", slugify_team(team))))} { (team) }
+                        }
+                    }
+                }
+            }
+
+            @if !spec.owners.is_empty() {
+                div class="spec-header" {
+                    span class="meta-label" { "Owner" @if spec.owners.len() > 1 { "s" } }
+                    span class="meta-value" { (spec.owners.join(", ")) }
+                }
+            }
+
+            @if !spec.approved_by.is_empty() && !is_pr_page {
+                div class="spec-header" {
+                    span class="meta-label" { "Approved by" }
+                    span class="meta-value" { (spec.approved_by.join(", ")) }
+                }
+            }
+
+            @if let Some(review) = pull_review {
+                @let verdicts = review.verdicts();
+                @if !verdicts.is_empty() {
+                    div class="spec-header" {
+                        span class="meta-label" { "Reviewers" }
+                        span {
+                            @for (index, (login, verdict)) in verdicts.iter().enumerate() {
+                                @if index > 0 { span class="meta-divider" { "•" } }
+                                span class="meta-value" { (login) }
+                                span class="meta-note" { " (" (verdict) ")" }
+                            }
+                        }
+                    }
+                }
+                @if !review.labels.is_empty() {
+                    div class="spec-header" {
+                        span class="meta-label" { "Labels" }
+                        span {
+                            @for label in &review.labels {
+                                span class="tag" { (label) }
+                            }
+                        }
+                    }
+                }
+                @if let Some(sha) = &review.merge_commit_sha {
+                    div class="spec-header" {
+                        span class="meta-label" { "Merged as" }
+                        code { (sha.get(..12).unwrap_or(sha)) }
+                    }
+                }
+            }
+
             @if is_pr_page {
                 @if let Some((display, href)) = original_link.as_ref() {
                     div class="spec-header" {
@@ -4649,7 +7995,7 @@ fn render_spec(
                             @for (index, revision) in items.iter().enumerate() {
                                 @if index > 0 { span class="meta-divider" { "•" } }
                                 a class="spec-metadata-link" href={(join_spec_link(prefix, revision.href.trim_start_matches('/'), "", trailing_slashes))} {
-                                    (format!("PR #{}", revision.pr_number))
+                                    (revision.source.to_string())
                                 }
                                 span class="meta-note" {
                                     " (last update: "
@@ -4686,7 +8032,7 @@ fn render_spec(
         &description,
         LayoutAssets {
             css: &css,
-            highlight_css: needs_highlight.then(highlight::highlight_css),
+            highlight_css: needs_highlight.then(|| state.renderer.highlighter.css()),
             theme_init_js: &theme_init_js,
             theme_toggle_js: &theme_toggle_js,
             mermaid_js_url: mermaid_js_url.as_deref(),
@@ -4714,7 +8060,7 @@ fn render_author(
 
             div class="spec-header author-heading" {
                 @if let Some(avatar) = &author.avatar_url {
-                    img class="author-avatar author-avatar--lg" src=(avatar)
+                    img class="author-avatar author-avatar--lg" src=(avatar_src(prefix, avatar))
                         alt="" loading="lazy" referrerpolicy="no-referrer";
                 }
                 h1 { "Specs by " (author.name) }
@@ -4728,6 +8074,7 @@ fn render_author(
                     @for spec in authored {
                         li {
                             (spec_card(
+                                prefix,
                                 join_spec_link(prefix, &spec.id, "", trailing_slashes),
                                 &spec.id,
                                 spec,
@@ -4781,11 +8128,11 @@ fn render_status(
 
             section class="hero" {
                 h1 { "Status: " (status_name) }
-                p { "Browse specs with this status. Search by title, ID, or author." }
+                p { "Browse specs with this status. Search by title, ID, author, owner, or team." }
                 form class="search-bar" role="search" onsubmit="event.preventDefault();" {
                     label class="sr-only" for="spec-search" { "Search specifications" }
                     div class="search-input" {
-                        input id="spec-search" type="search" name="q" placeholder="Search by title, ID, or author" autocomplete="off" {}
+                        input id="spec-search" type="search" name="q" placeholder="Search by title, ID, author, owner, or team" autocomplete="off" {}
                         span class="search-hint" { "/" }
                     }
                 }
@@ -4805,8 +8152,11 @@ fn render_status(
                             data-title={(spec.title.to_lowercase())}
                             data-id={(base_id.to_lowercase())}
                             data-authors={(spec.authors.iter().map(|a| a.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                            data-owners={(spec.owners.iter().map(|o| o.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                            data-teams={(state.spec_teams(spec).iter().map(|t| t.to_lowercase()).collect::<Vec<_>>().join(" "))}
                         {
                             (spec_card(
+                                prefix,
                                 join_spec_link(prefix, &spec.id, "", trailing_slashes),
                                 base_id,
                                 spec,
@@ -4842,6 +8192,111 @@ fn render_status(
     )
 }
 
+/// A team's page: its listed specs grouped by status, larger groups first.
+fn render_team(
+    state: &AppState,
+    team: &TeamSummary,
+    matching: &[&SpecDocument],
+    prefix: &str,
+    trailing_slashes: bool,
+) -> Markup {
+    let title = format!("{} - {}", team.name, state.site_name);
+    let description = team
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("All specs owned by the {} team", team.name));
+    let index_search_js = state.assets.index_search_script();
+
+    let mut groups: Vec<(&str, Vec<&SpecDocument>)> = Vec::new();
+    for spec in matching {
+        match groups
+            .iter_mut()
+            .find(|(status, _)| slugify_status(status) == slugify_status(&spec.status))
+        {
+            Some((_, specs)) => specs.push(spec),
+            None => groups.push((&spec.status, vec![spec])),
+        }
+    }
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+
+    let content = html! {
+        main class="container" {
+            a class="back-link" href={(join_prefix(prefix, ""))} { "← Back to index" }
+
+            section class="hero" {
+                h1 { "Team: " (team.name) }
+                @if let Some(about) = &team.description {
+                    p { (about) }
+                }
+                form class="search-bar" role="search" onsubmit="event.preventDefault();" {
+                    label class="sr-only" for="spec-search" { "Search specifications" }
+                    div class="search-input" {
+                        input id="spec-search" type="search" name="q" placeholder="Search by title, ID, author, owner, or team" autocomplete="off" {}
+                        span class="search-hint" { "/" }
+                    }
+                }
+            }
+            div class="spec-header" {
+                span class="spec-dir" { (format!("{} spec{}", matching.len(), if matching.len() == 1 { "" } else { "s" })) }
+            }
+
+            @if matching.is_empty() {
+                p class="empty-state" { "No specs found for this team." }
+            } @else {
+                @for (status, specs) in &groups {
+                    div class="spec-header" {
+                        a class={(format!("tag {}", status.to_lowercase()))}
+                            href={(join_prefix(prefix, format!("status/{}", slugify_status(status))))} { (status) }
+                        span class="spec-dir" { (specs.len()) }
+                    }
+                    ul class="spec-list" {
+                        @for spec in specs {
+                            @let base_id = spec.revision_of.as_deref().unwrap_or(&spec.id);
+                            li
+                                data-title={(spec.title.to_lowercase())}
+                                data-id={(base_id.to_lowercase())}
+                                data-authors={(spec.authors.iter().map(|a| a.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                                data-owners={(spec.owners.iter().map(|o| o.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                                data-teams={(state.spec_teams(spec).iter().map(|t| t.to_lowercase()).collect::<Vec<_>>().join(" "))}
+                            {
+                                (spec_card(
+                                    prefix,
+                                    join_spec_link(prefix, &spec.id, "", trailing_slashes),
+                                    base_id,
+                                    spec,
+                                ))
+                            }
+                        }
+                    }
+                }
+                p class="empty-state filter-empty" hidden { "No specs match this search." }
+            }
+        }
+        script { (PreEscaped(index_search_js)) }
+    };
+
+    let css = state.assets.css();
+    let theme_init_js = state.assets.theme_init_script();
+    let theme_toggle_js = state.assets.theme_toggle_script();
+    base_layout(
+        &state.site_name,
+        &state.site_description,
+        &title,
+        &description,
+        LayoutAssets {
+            css: &css,
+            highlight_css: None,
+            theme_init_js: &theme_init_js,
+            theme_toggle_js: &theme_toggle_js,
+            mermaid_js_url: None,
+            mermaid_init_js: None,
+        },
+        content,
+        prefix,
+        state.generated_at,
+    )
+}
+
 fn render_status_index(state: &AppState, summaries: &[StatusSummary], prefix: &str) -> Markup {
     let title = format!("Statuses - {}", state.site_name);
     let description = "Browse specs by status.".to_string();
@@ -4855,6 +8310,10 @@ fn render_status_index(state: &AppState, summaries: &[StatusSummary], prefix: &s
                 p { "Browse specification documents grouped by status." }
             }
 
+            @if !state.workflow.is_empty() {
+                (render_workflow_diagram(state))
+            }
+
             @if summaries.is_empty() {
                 p class="empty-state" { "No statuses found." }
             } @else {
@@ -4903,6 +8362,48 @@ fn render_status_index(state: &AppState, summaries: &[StatusSummary], prefix: &s
     )
 }
 
+/// The `[workflow]` as a diagram, or as a list of its transitions when it
+/// can't be drawn.
+fn render_workflow_diagram(state: &AppState) -> Markup {
+    let workflow = &state.workflow;
+    let svg = state.renderer.diagrams.render("dot", &workflow.to_dot());
+    let terminal = workflow.terminal_states();
+    html! {
+        figure class="workflow-diagram" {
+            @if let Some(svg) = svg {
+                div class="diagram" { (PreEscaped(svg)) }
+            } @else {
+                ul class="workflow-transitions" {
+                    @for state in &workflow.states {
+                        @let next = workflow.next_states(state);
+                        @if !next.is_empty() {
+                            li { (state) " → " (next.join(", ")) }
+                        }
+                    }
+                }
+            }
+            figcaption {
+                "Status workflow. New specs start as "
+                (workflow.initial_states().join(" or "))
+                "; "
+                (terminal.join(", "))
+                (if terminal.len() == 1 { " is" } else { " are" })
+                " final."
+            }
+        }
+    }
+}
+
+/// `src` for an avatar: self-hosted avatars are site-root paths resolved
+/// against the page's `prefix`; remote URLs are used as-is.
+fn avatar_src(prefix: &str, avatar: &str) -> String {
+    if avatar.starts_with("/assets/") {
+        join_prefix(prefix, avatar)
+    } else {
+        avatar.to_string()
+    }
+}
+
 fn join_prefix(prefix: &str, path: impl AsRef<str>) -> String {
     let trimmed = path.as_ref().trim_start_matches('/');
     if prefix.is_empty() {
@@ -5066,7 +8567,7 @@ fn base_layout(
     }
 }
 
-fn spec_from_generated(spec: GeneratedSpec) -> Result<SpecDocument> {
+fn spec_from_generated(spec: GeneratedSpec, config: &ProjectConfiguration) -> Result<SpecDocument> {
     let created = normalize_timestamp(spec.created.as_ref());
     let updated = normalize_timestamp(spec.updated.as_ref())
         .or(created)
@@ -5087,10 +8588,7 @@ fn spec_from_generated(spec: GeneratedSpec) -> Result<SpecDocument> {
     // JSON specs carry no git history; resolve avatars from the declared author
     // strings alone (Gravatar / GitHub-noreply when an email is present).
     let author_seeds = dossiers::authors::seeds_from_names(&spec.authors);
-    let authors_meta = dossiers::authors::resolve_authors(
-        &author_seeds,
-        &dossiers::authors::AuthorResolver::local(),
-    );
+    let authors_meta = dossiers::authors::resolve_authors(&author_seeds, &config.author_resolver());
     let authors = if authors_meta.is_empty() {
         normalize_authors(spec.authors)
     } else {
@@ -5115,20 +8613,63 @@ fn spec_from_generated(spec: GeneratedSpec) -> Result<SpecDocument> {
         listed: true,
         revision_of: None,
         pr_number: None,
+        git_ref: None,
+        approved_by: Vec::new(),
+        last_commit: None,
+        visibility: Visibility::from_wire(spec.visibility.as_deref()),
+        owners: Vec::new(),
+        teams: Vec::new(),
     })
 }
 
-#[derive(Clone, Copy)]
-struct DocRenderer;
+#[derive(Clone, Default)]
+struct DocRenderer {
+    /// Build-time diagram renderers; empty unless configured for a project.
+    diagrams: Arc<DiagramRegistry>,
+    /// Syntax highlighting with the project's themes and grammars.
+    highlighter: Arc<Highlighter>,
+    /// Where `file=` snippets are read from; without one, fences keep their
+    /// inline body.
+    snippet_root: Option<PathBuf>,
+}
 
 impl DocRenderer {
     fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    fn with_diagrams(diagrams: DiagramRegistry) -> Self {
+        Self {
+            diagrams: Arc::new(diagrams),
+            ..Self::default()
+        }
+    }
+
+    fn with_highlighter(mut self, highlighter: Highlighter) -> Self {
+        self.highlighter = Arc::new(highlighter);
+        self
+    }
+
+    fn with_snippet_root(mut self, root: PathBuf) -> Self {
+        self.snippet_root = Some(root);
+        self
+    }
+
+    /// The code for a `file=` snippet, or `fallback` (the fence's own body)
+    /// when it cannot be loaded.
+    fn load_snippet(&self, snippet: &SnippetRef, fallback: &str) -> String {
+        let Some(root) = self.snippet_root.as_deref() else {
+            return fallback.to_string();
+        };
+        snippet.load(root).unwrap_or_else(|err| {
+            eprintln!("Warning: failed to embed snippet {snippet}: {err}");
+            fallback.to_string()
+        })
     }
 
     fn render(&self, source: &str, format: DocFormat) -> Result<String, RenderError> {
         match format {
-            DocFormat::Markdown => Ok(render_markdown(source)),
+            DocFormat::Markdown => Ok(render_markdown(source, self)),
             DocFormat::Asciidoc => self.render_asciidoc(source),
         }
     }
@@ -5137,7 +8678,7 @@ impl DocRenderer {
         let rendered = std::panic::catch_unwind(|| {
             let mut parser = AsciidocParser::default();
             let document = parser.parse(source);
-            render_asciidoc_document(&document)
+            render_asciidoc_document(&document, self)
         })
         .map_err(|panic| {
             RenderError::Renderer(format!("asciidoc panic: {}", describe_panic(panic)))
@@ -5145,6 +8686,20 @@ impl DocRenderer {
 
         Ok(rendered)
     }
+
+    /// Renders a diagram block to inline SVG, or to its escaped source when the
+    /// renderer is unavailable or fails. A `language-mermaid` fallback is still
+    /// picked up by the client-side Mermaid runtime.
+    fn render_diagram(&self, lang: &str, source: &str) -> String {
+        let lang = escape_html(lang);
+        match self.diagrams.render(&lang, source) {
+            Some(svg) => format!("<div class=\"diagram diagram--{lang}\">{svg}</div>"),
+            None => format!(
+                "<pre><code class=\"language-{lang}\">{}</code></pre>",
+                escape_html(source)
+            ),
+        }
+    }
 }
 
 fn render_spec_body(
@@ -5166,12 +8721,9 @@ fn render_spec_body(
     };
     let without_heading = remove_leading_heading(&rendered);
     let prefixed_assets = prefix_asset_urls(&without_heading, &asset_base);
-    let rewritten_links = rewrite_spec_links(
-        &prefixed_assets,
-        &state.spec_ids,
-        link_prefix,
-        trailing_slashes,
-    );
+    let scrubbed = scrub_hidden_spec_links(&prefixed_assets, &state.hidden_spec_ids);
+    let rewritten_links =
+        rewrite_spec_links(&scrubbed, &state.spec_ids, link_prefix, trailing_slashes);
     Ok(rewritten_links)
 }
 
@@ -5186,43 +8738,85 @@ fn has_mermaid_markup(html: &str) -> bool {
 /// only content that needs the generated highlight stylesheet inlined. The
 /// marker class is emitted by `render_markdown` when a block is highlighted.
 fn has_highlight_markup(html: &str) -> bool {
-    html.contains("<pre class=\"hl\">")
+    html.contains("<pre class=\"hl")
 }
 
-fn render_asciidoc_document(doc: &AsciidocDocument<'_>) -> String {
+fn render_asciidoc_document(doc: &AsciidocDocument<'_>, renderer: &DocRenderer) -> String {
     let mut html = String::new();
 
     if let Some(title) = doc.header().title() {
+        // The parser substitutes attribute references in the body, but the
+        // title line precedes the header's attribute entries, so resolve them
+        // here.
+        let header_attributes: HashMap<String, String> = doc
+            .header()
+            .attributes()
+            .filter_map(|attr| match attr.value() {
+                InterpretedValue::Value(value) => {
+                    Some((attr.name().data().to_string(), value.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let title = substitute_attribute_references(title, &header_attributes);
         let attrs = build_attrs(None, &["adoc-doc-title"], &[]);
         let _ = write!(html, "<h1{attrs}>{title}</h1>");
     }
 
-    render_asciidoc_blocks(doc.nested_blocks(), &mut html);
+    render_asciidoc_blocks(doc.nested_blocks(), renderer, &mut html);
     html
 }
 
 fn render_asciidoc_blocks<'a>(
     blocks: impl IntoIterator<Item = &'a AsciidocBlock<'a>>,
+    renderer: &DocRenderer,
     buf: &mut String,
 ) {
-    for block in blocks {
-        render_asciidoc_block(block, buf);
+    let blocks: Vec<&AsciidocBlock<'_>> = blocks.into_iter().collect();
+    let mut idx = 0;
+    while idx < blocks.len() {
+        // The parser has no table support: a `|===` table arrives as one
+        // paragraph per blank-line-separated group of rows, so gather them up
+        // to the closing delimiter.
+        if let AsciidocBlock::Simple(first) = blocks[idx] {
+            if is_asciidoc_table_start(first) {
+                let mut chunks = vec![first.content().rendered()];
+                let mut closed = is_asciidoc_table_end(first.content().rendered(), true);
+                idx += 1;
+                while !closed && idx < blocks.len() {
+                    let AsciidocBlock::Simple(next) = blocks[idx] else {
+                        break;
+                    };
+                    if !matches!(next.style(), SimpleBlockStyle::Paragraph) {
+                        break;
+                    }
+                    chunks.push(next.content().rendered());
+                    closed = is_asciidoc_table_end(next.content().rendered(), false);
+                    idx += 1;
+                }
+                render_asciidoc_table(first, &chunks, buf);
+                continue;
+            }
+        }
+        render_asciidoc_block(blocks[idx], renderer, buf);
+        idx += 1;
     }
 }
 
-fn render_asciidoc_block(block: &AsciidocBlock<'_>, buf: &mut String) {
+fn render_asciidoc_block(block: &AsciidocBlock<'_>, renderer: &DocRenderer, buf: &mut String) {
     match block {
-        AsciidocBlock::Simple(b) => render_simple_block(b, buf),
+        AsciidocBlock::Simple(b) => render_simple_block(b, renderer, buf),
         AsciidocBlock::Media(b) => render_media_block(b, buf),
-        AsciidocBlock::Section(b) => render_section_block(b, buf),
-        AsciidocBlock::RawDelimited(b) => render_raw_block(b, buf),
-        AsciidocBlock::CompoundDelimited(b) => render_compound_block(b, buf),
+        AsciidocBlock::Section(b) => render_section_block(b, renderer, buf),
+        AsciidocBlock::RawDelimited(b) => render_raw_block(b, renderer, buf),
+        AsciidocBlock::CompoundDelimited(b) => render_compound_block(b, renderer, buf),
         AsciidocBlock::Preamble(b) => render_container(
             b.id(),
             &b.roles(),
             &["adoc-block", "preamble"],
             None,
             b.nested_blocks(),
+            renderer,
             buf,
         ),
         AsciidocBlock::Break(b) => render_break_block(b, buf),
@@ -5231,8 +8825,22 @@ fn render_asciidoc_block(block: &AsciidocBlock<'_>, buf: &mut String) {
     }
 }
 
-fn render_simple_block(block: &SimpleBlock<'_>, buf: &mut String) {
+fn render_simple_block(block: &SimpleBlock<'_>, renderer: &DocRenderer, buf: &mut String) {
     let roles = block.roles();
+    if matches!(block.style(), SimpleBlockStyle::Paragraph) {
+        let content = block.content().rendered();
+        // `[NOTE]` above the paragraph, or the inline `NOTE: text` form.
+        let admonition = admonition_label(block.declared_style())
+            .map(|label| (label, content))
+            .or_else(|| split_admonition_paragraph(content));
+        if let Some((label, text)) = admonition {
+            render_admonition(block.id(), &roles, label, block.title(), buf, |buf| {
+                let _ = write!(buf, "<p>{}</p>", substitute_xrefs(text));
+            });
+            return;
+        }
+    }
+
     let context = block.resolved_context();
     let classes = ["adoc-block", context.as_ref()];
     let attrs = build_attrs(block.id(), &classes, &roles);
@@ -5243,32 +8851,37 @@ fn render_simple_block(block: &SimpleBlock<'_>, buf: &mut String) {
 
     match block.style() {
         SimpleBlockStyle::Paragraph => {
-            let _ = write!(buf, "<p>{}</p>", block.content().rendered());
+            let content = block.content().rendered();
+            if let Some(items) = parse_callout_list(content) {
+                buf.push_str("<ol class=\"adoc-callouts\">");
+                for (number, text) in items {
+                    let _ = write!(
+                        buf,
+                        "<li value=\"{number}\">{}</li>",
+                        substitute_xrefs(&text)
+                    );
+                }
+                buf.push_str("</ol>");
+            } else if context.as_ref() == "quote" {
+                let _ = write!(
+                    buf,
+                    "<blockquote><p>{}</p></blockquote>",
+                    substitute_xrefs(content)
+                );
+                render_quote_attribution(block.attrlist(), buf);
+            } else {
+                let _ = write!(buf, "<p>{}</p>", substitute_xrefs(content));
+            }
         }
         SimpleBlockStyle::Literal => {
             let _ = write!(
                 buf,
                 "<pre><code>{}</code></pre>",
-                block.content().rendered()
+                render_callout_markers(block.content().rendered())
             );
         }
         SimpleBlockStyle::Listing | SimpleBlockStyle::Source => {
-            if is_mermaid_attrlist(block.attrlist()) {
-                let _ = write!(
-                    buf,
-                    "<pre class=\"mermaid\">{}</pre>",
-                    block.content().rendered()
-                );
-            } else if is_svgbob_attrlist(block.attrlist()) {
-                let svg = render_svgbob_svg(block.content().rendered());
-                let _ = write!(buf, "<div class=\"svgbob\">{svg}</div>");
-            } else {
-                let _ = write!(
-                    buf,
-                    "<pre><code>{}</code></pre>",
-                    block.content().rendered()
-                );
-            }
+            render_asciidoc_verbatim(block.attrlist(), block.content().rendered(), renderer, buf);
         }
     }
 
@@ -5320,7 +8933,7 @@ fn render_media_block(block: &MediaBlock<'_>, buf: &mut String) {
     buf.push_str("</figure>");
 }
 
-fn render_section_block(block: &SectionBlock<'_>, buf: &mut String) {
+fn render_section_block(block: &SectionBlock<'_>, renderer: &DocRenderer, buf: &mut String) {
     let roles = block.roles();
     let attrs = build_attrs(block.id(), &["adoc-section"], &roles);
     buf.push_str("<section");
@@ -5341,12 +8954,12 @@ fn render_section_block(block: &SectionBlock<'_>, buf: &mut String) {
         text = heading_text
     );
 
-    render_asciidoc_blocks(block.nested_blocks(), buf);
+    render_asciidoc_blocks(block.nested_blocks(), renderer, buf);
 
     buf.push_str("</section>");
 }
 
-fn render_raw_block(block: &RawDelimitedBlock<'_>, buf: &mut String) {
+fn render_raw_block(block: &RawDelimitedBlock<'_>, renderer: &DocRenderer, buf: &mut String) {
     let context = block.resolved_context();
     if context.as_ref() == "comment" {
         return;
@@ -5362,41 +8975,8 @@ fn render_raw_block(block: &RawDelimitedBlock<'_>, buf: &mut String) {
 
     match context.as_ref() {
         "pass" => buf.push_str(block.content().rendered()),
-        "literal" => {
-            if is_mermaid_attrlist(block.attrlist()) {
-                let _ = write!(
-                    buf,
-                    "<pre class=\"mermaid\">{}</pre>",
-                    block.content().rendered()
-                );
-            } else if is_svgbob_attrlist(block.attrlist()) {
-                let svg = render_svgbob_svg(block.content().rendered());
-                let _ = write!(buf, "<div class=\"svgbob\">{svg}</div>");
-            } else {
-                let _ = write!(
-                    buf,
-                    "<pre><code>{}</code></pre>",
-                    block.content().rendered()
-                );
-            }
-        }
-        "listing" => {
-            if is_mermaid_attrlist(block.attrlist()) {
-                let _ = write!(
-                    buf,
-                    "<pre class=\"mermaid\">{}</pre>",
-                    block.content().rendered()
-                );
-            } else if is_svgbob_attrlist(block.attrlist()) {
-                let svg = render_svgbob_svg(block.content().rendered());
-                let _ = write!(buf, "<div class=\"svgbob\">{svg}</div>");
-            } else {
-                let _ = write!(
-                    buf,
-                    "<pre><code>{}</code></pre>",
-                    block.content().rendered()
-                );
-            }
+        "literal" | "listing" => {
+            render_asciidoc_verbatim(block.attrlist(), block.content().rendered(), renderer, buf);
         }
         _ => buf.push_str(block.content().rendered()),
     }
@@ -5404,32 +8984,395 @@ fn render_raw_block(block: &RawDelimitedBlock<'_>, buf: &mut String) {
     buf.push_str("</div>");
 }
 
-fn render_compound_block(block: &CompoundDelimitedBlock<'_>, buf: &mut String) {
+fn render_compound_block(
+    block: &CompoundDelimitedBlock<'_>,
+    renderer: &DocRenderer,
+    buf: &mut String,
+) {
     let roles = block.roles();
+    if let Some(label) = admonition_label(block.declared_style()) {
+        render_admonition(block.id(), &roles, label, block.title(), buf, |buf| {
+            render_asciidoc_blocks(block.nested_blocks(), renderer, buf);
+        });
+        return;
+    }
+
     let context = block.resolved_context();
     let classes = ["adoc-block", context.as_ref()];
     let attrs = build_attrs(block.id(), &classes, &roles);
+    let tag = match context.as_ref() {
+        "sidebar" => "aside",
+        "quote" => "blockquote",
+        _ => "div",
+    };
 
-    buf.push_str("<div");
-    buf.push_str(&attrs);
-    buf.push('>');
+    let _ = write!(buf, "<{tag}{attrs}>");
     render_block_title(block.title(), buf);
-    render_asciidoc_blocks(block.nested_blocks(), buf);
-    buf.push_str("</div>");
+    render_asciidoc_blocks(block.nested_blocks(), renderer, buf);
+    if context.as_ref() == "quote" {
+        render_quote_attribution(block.attrlist(), buf);
+    }
+    let _ = write!(buf, "</{tag}>");
 }
 
-fn render_break_block(block: &AsciidocBreak<'_>, buf: &mut String) {
-    let roles = block.roles();
-    let context = block.resolved_context();
-    let classes = ["adoc-block", context.as_ref()];
-    let attrs = build_attrs(block.id(), &classes, &roles);
+const ADMONITION_LABELS: [&str; 5] = ["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"];
 
-    match block.type_() {
-        BreakType::Thematic => {
-            let _ = write!(buf, "<hr{attrs} />");
-        }
-        BreakType::Page => {
-            buf.push_str("<div");
+/// The admonition label declared by a block style such as `[WARNING]`.
+fn admonition_label(style: Option<&str>) -> Option<&'static str> {
+    let style = style?;
+    ADMONITION_LABELS
+        .into_iter()
+        .find(|label| label.eq_ignore_ascii_case(style))
+}
+
+/// Split the inline admonition form `NOTE: text` into its label and text.
+fn split_admonition_paragraph(content: &str) -> Option<(&'static str, &str)> {
+    ADMONITION_LABELS.into_iter().find_map(|label| {
+        content
+            .strip_prefix(label)
+            .and_then(|rest| rest.strip_prefix(": "))
+            .map(|text| (label, text.trim_start()))
+    })
+}
+
+fn render_admonition(
+    id: Option<&str>,
+    roles: &[&str],
+    label: &str,
+    title: Option<&str>,
+    buf: &mut String,
+    body: impl FnOnce(&mut String),
+) {
+    let kind = label.to_ascii_lowercase();
+    let modifier = format!("admonition--{kind}");
+    let attrs = build_attrs(id, &["adoc-block", "admonition", &modifier], roles);
+    let mut display = kind.clone();
+    if let Some(first) = display.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    let _ = write!(
+        buf,
+        "<div{attrs}><div class=\"adoc-admonition-label\">{display}</div><div class=\"adoc-admonition-content\">"
+    );
+    render_block_title(title, buf);
+    body(buf);
+    buf.push_str("</div></div>");
+}
+
+/// The `— attribution, cite title` footer of a `[quote, attribution, title]`
+/// block.
+fn render_quote_attribution(attrlist: Option<&Attrlist<'_>>, buf: &mut String) {
+    let Some(attrlist) = attrlist else {
+        return;
+    };
+    let value = |name: &str, position: usize| {
+        attrlist
+            .named_or_positional_attribute(name, position)
+            .map(|attr| attr.value().trim())
+            .filter(|value| !value.is_empty())
+    };
+    let attribution = value("attribution", 2);
+    let citetitle = value("citetitle", 3);
+    if attribution.is_none() && citetitle.is_none() {
+        return;
+    }
+
+    buf.push_str("<footer class=\"adoc-attribution\">&#8212; ");
+    if let Some(attribution) = attribution {
+        buf.push_str(&escape_html(attribution));
+    }
+    if let Some(citetitle) = citetitle {
+        if attribution.is_some() {
+            buf.push_str(", ");
+        }
+        let _ = write!(buf, "<cite>{}</cite>", escape_html(citetitle));
+    }
+    buf.push_str("</footer>");
+}
+
+fn is_asciidoc_table_start(block: &SimpleBlock<'_>) -> bool {
+    matches!(block.style(), SimpleBlockStyle::Paragraph)
+        && block.content().rendered().lines().next().map(str::trim) == Some("|===")
+}
+
+/// Whether a table paragraph ends with the closing `|===`. The opening chunk
+/// needs a second delimiter line; later chunks only need to end with one.
+fn is_asciidoc_table_end(content: &str, is_first: bool) -> bool {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let min_lines = if is_first { 2 } else { 1 };
+    lines.len() >= min_lines && lines.last() == Some(&"|===")
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct AsciidocTable {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Parse the (already inline-substituted) paragraphs of a `|===` table.
+/// `cols` is the table's `cols` attribute and `header` is the explicit
+/// `%header` / `%noheader` option; without one, a first row followed by a
+/// blank line is the implicit header, as in Asciidoctor.
+fn parse_asciidoc_table(
+    chunks: &[&str],
+    cols: Option<&str>,
+    header: Option<bool>,
+) -> AsciidocTable {
+    let groups: Vec<Vec<&str>> = chunks
+        .iter()
+        .map(|chunk| {
+            chunk
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && *line != "|===")
+                .collect::<Vec<_>>()
+        })
+        .filter(|lines| !lines.is_empty())
+        .collect();
+    let Some(first_line) = groups.first().and_then(|lines| lines.first()) else {
+        return AsciidocTable::default();
+    };
+
+    let column_count = cols
+        .and_then(table_column_count)
+        .unwrap_or_else(|| split_table_cells(first_line).len())
+        .max(1);
+    let implicit_header = groups.len() > 1 && groups[0].len() == 1;
+    let has_header = header.unwrap_or(implicit_header);
+
+    let body = groups
+        .iter()
+        .map(|lines| lines.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let cells = split_table_cells(&body);
+    let mut rows: Vec<Vec<String>> = cells
+        .chunks(column_count)
+        .map(|row| {
+            let mut row = row.to_vec();
+            row.resize(column_count, String::new());
+            row
+        })
+        .collect();
+    let header = if has_header && !rows.is_empty() {
+        Some(rows.remove(0))
+    } else {
+        None
+    };
+    AsciidocTable { header, rows }
+}
+
+/// Column count from a `cols` spec such as `1,2`, `3*` or `2*,1`.
+fn table_column_count(cols: &str) -> Option<usize> {
+    let count: usize = cols
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(|spec| match spec.split_once('*') {
+            Some((repeat, _)) => repeat.trim().parse().unwrap_or(1),
+            None => 1,
+        })
+        .sum();
+    (count > 0).then_some(count)
+}
+
+/// Split table text on unescaped `|` cell separators. Text before the first
+/// separator is not a cell; `\|` is a literal pipe.
+fn split_table_cells(text: &str) -> Vec<String> {
+    let mut cells: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                if let Some(cell) = current.as_mut() {
+                    cell.push('|');
+                }
+            }
+            '|' => {
+                if let Some(cell) = current.take() {
+                    cells.push(cell.trim().to_string());
+                }
+                current = Some(String::new());
+            }
+            _ => {
+                if let Some(cell) = current.as_mut() {
+                    cell.push(ch);
+                }
+            }
+        }
+    }
+    if let Some(cell) = current {
+        cells.push(cell.trim().to_string());
+    }
+    cells
+}
+
+fn render_asciidoc_table(block: &SimpleBlock<'_>, chunks: &[&str], buf: &mut String) {
+    let cols = block
+        .attrlist()
+        .and_then(|attrlist| attrlist.named_attribute("cols"))
+        .map(|attr| attr.value());
+    let header = if block.has_option("header") {
+        Some(true)
+    } else if block.has_option("noheader") {
+        Some(false)
+    } else {
+        None
+    };
+    let table = parse_asciidoc_table(chunks, cols, header);
+
+    let roles = block.roles();
+    let attrs = build_attrs(block.id(), &["adoc-block", "table"], &roles);
+    buf.push_str("<div");
+    buf.push_str(&attrs);
+    buf.push('>');
+    render_block_title(block.title(), buf);
+    buf.push_str("<table>");
+    if let Some(header) = &table.header {
+        buf.push_str("<thead><tr>");
+        for cell in header {
+            let _ = write!(buf, "<th>{}</th>", substitute_xrefs(cell));
+        }
+        buf.push_str("</tr></thead>");
+    }
+    buf.push_str("<tbody>");
+    for row in &table.rows {
+        buf.push_str("<tr>");
+        for cell in row {
+            let _ = write!(buf, "<td>{}</td>", substitute_xrefs(cell));
+        }
+        buf.push_str("</tr>");
+    }
+    buf.push_str("</tbody></table></div>");
+}
+
+/// Replace the `<N>` callout markers at the end of verbatim lines (and any
+/// line comment introducing them) with numbered badges.
+fn render_callout_markers(content: &str) -> String {
+    lazy_static! {
+        static ref CALLOUT_LINE_RE: Regex =
+            Regex::new(r"(?m)[ \t]*(?:(?://|#|--|;;)[ \t]*)?((?:&lt;\d+&gt;[ \t]*)+)$").unwrap();
+        static ref CALLOUT_RE: Regex = Regex::new(r"&lt;(\d+)&gt;").unwrap();
+    }
+
+    CALLOUT_LINE_RE
+        .replace_all(content, |caps: &regex::Captures| {
+            let badges: Vec<String> = CALLOUT_RE
+                .captures_iter(&caps[1])
+                .map(|m| format!("<b class=\"conum\" data-value=\"{0}\">({0})</b>", &m[1]))
+                .collect();
+            format!(" {}", badges.join(" "))
+        })
+        .to_string()
+}
+
+/// Parse a callout list paragraph (`<1> text` lines, `<.>` auto-numbered).
+/// Lines without a marker continue the previous item.
+fn parse_callout_list(content: &str) -> Option<Vec<(usize, String)>> {
+    lazy_static! {
+        static ref CALLOUT_ITEM_RE: Regex = Regex::new(r"^&lt;(\d+|\.)&gt;\s+(.*)$").unwrap();
+    }
+
+    let mut items: Vec<(usize, String)> = Vec::new();
+    for line in content.lines() {
+        if let Some(caps) = CALLOUT_ITEM_RE.captures(line.trim()) {
+            let number = caps[1]
+                .parse()
+                .unwrap_or_else(|_| items.last().map(|(n, _)| n + 1).unwrap_or(1));
+            items.push((number, caps[2].to_string()));
+        } else {
+            let (_, text) = items.last_mut()?;
+            text.push('\n');
+            text.push_str(line.trim());
+        }
+    }
+    (!items.is_empty()).then_some(items)
+}
+
+/// Turn `xref:target[text]` and `<<target,text>>` cross references into links.
+/// References to another document keep their relative path so
+/// `rewrite_spec_links` resolves them like any other inter-spec link;
+/// references without a document become in-page anchors.
+fn substitute_xrefs(html: &str) -> String {
+    lazy_static! {
+        static ref XREF_RE: Regex = Regex::new(
+            r"xref:([^\s\[\]]+)\[([^\]]*)\]|&lt;&lt;([A-Za-z0-9_./#:-]+)(?:,\s*(.*?))?&gt;&gt;"
+        )
+        .unwrap();
+    }
+
+    if !html.contains("xref:") && !html.contains("&lt;&lt;") {
+        return html.to_string();
+    }
+
+    XREF_RE
+        .replace_all(html, |caps: &regex::Captures| {
+            let target = caps
+                .get(1)
+                .or_else(|| caps.get(3))
+                .map(|m| m.as_str())
+                .unwrap_or_default();
+            let text = caps
+                .get(2)
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().trim())
+                .filter(|text| !text.is_empty());
+            let target = target.trim_end_matches('#');
+            let (path, fragment) = match target.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (target, None),
+            };
+            let is_document = path.ends_with(".adoc") || path.ends_with(".md");
+            let href = if is_document {
+                target.to_string()
+            } else {
+                format!("#{}", fragment.unwrap_or(path))
+            };
+            let text = match text {
+                Some(text) => text.to_string(),
+                None if is_document => {
+                    let stem = path.rsplit('/').next().unwrap_or(path);
+                    escape_html(stem.trim_end_matches(".adoc").trim_end_matches(".md"))
+                }
+                None => escape_html(fragment.unwrap_or(path)),
+            };
+            format!("<a href=\"{}\">{text}</a>", escape_attr(&href))
+        })
+        .to_string()
+}
+
+/// Replace `{name}` attribute references that resolve against `attributes`,
+/// leaving unknown references untouched as Asciidoctor does.
+fn substitute_attribute_references(text: &str, attributes: &HashMap<String, String>) -> String {
+    lazy_static! {
+        static ref ATTRIBUTE_REF_RE: Regex =
+            Regex::new(r"\{([A-Za-z0-9_][A-Za-z0-9_-]*)\}").unwrap();
+    }
+
+    ATTRIBUTE_REF_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            attributes
+                .get(&caps[1])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .to_string()
+}
+
+fn render_break_block(block: &AsciidocBreak<'_>, buf: &mut String) {
+    let roles = block.roles();
+    let context = block.resolved_context();
+    let classes = ["adoc-block", context.as_ref()];
+    let attrs = build_attrs(block.id(), &classes, &roles);
+
+    match block.type_() {
+        BreakType::Thematic => {
+            let _ = write!(buf, "<hr{attrs} />");
+        }
+        BreakType::Page => {
+            buf.push_str("<div");
             buf.push_str(&attrs);
             buf.push_str("></div>");
         }
@@ -5442,6 +9385,7 @@ fn render_container<'a>(
     classes: &[&'a str],
     title: Option<&str>,
     blocks: impl IntoIterator<Item = &'a AsciidocBlock<'a>>,
+    renderer: &DocRenderer,
     buf: &mut String,
 ) {
     let attrs = build_attrs(id, classes, roles);
@@ -5449,10 +9393,37 @@ fn render_container<'a>(
     buf.push_str(&attrs);
     buf.push('>');
     render_block_title(title, buf);
-    render_asciidoc_blocks(blocks, buf);
+    render_asciidoc_blocks(blocks, renderer, buf);
     buf.push_str("</div>");
 }
 
+/// Listing and literal content: a diagram with a build-time renderer, a
+/// Mermaid or svgbob diagram, or plain verbatim text.
+fn render_asciidoc_verbatim(
+    attrlist: Option<&Attrlist<'_>>,
+    content: &str,
+    renderer: &DocRenderer,
+    buf: &mut String,
+) {
+    if let Some(lang) =
+        asciidoc_block_language(attrlist).filter(|lang| renderer.diagrams.handles(lang))
+    {
+        // Content arrives with HTML special characters escaped.
+        buf.push_str(&renderer.render_diagram(&lang, &unescape_html(content)));
+    } else if is_mermaid_attrlist(attrlist) {
+        let _ = write!(buf, "<pre class=\"mermaid\">{content}</pre>");
+    } else if is_svgbob_attrlist(attrlist) {
+        let svg = render_svgbob_svg(content);
+        let _ = write!(buf, "<div class=\"svgbob\">{svg}</div>");
+    } else {
+        let _ = write!(
+            buf,
+            "<pre><code>{}</code></pre>",
+            render_callout_markers(content)
+        );
+    }
+}
+
 fn render_block_title(title: Option<&str>, buf: &mut String) {
     if let Some(title) = title {
         let _ = write!(buf, "<div class=\"adoc-title\">{title}</div>");
@@ -5498,6 +9469,15 @@ fn escape_html(value: &str) -> String {
         .replace('>', "&gt;")
 }
 
+/// Reverses the escaping the AsciiDoc parser applies to verbatim content.
+fn unescape_html(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
 /// The leading language token of a fenced code block's info string, e.g.
 /// `rust` from ```` ```rust,ignore ````. Empty when there is no info string.
 fn fence_token(info: &str) -> &str {
@@ -5546,6 +9526,28 @@ fn is_mermaid_attrlist(attrlist: Option<&Attrlist<'_>>) -> bool {
     false
 }
 
+/// The language of a listing block: `[dot]`, `[source,dot]` or a `language`
+/// attribute, lowercased.
+fn asciidoc_block_language(attrlist: Option<&Attrlist<'_>>) -> Option<String> {
+    let attrlist = attrlist?;
+    let lang = match attrlist.block_style() {
+        Some(style)
+            if style.eq_ignore_ascii_case("source") || style.eq_ignore_ascii_case("listing") =>
+        {
+            attrlist.nth_attribute(2).map(|attr| attr.value())
+        }
+        Some(style) => Some(style),
+        None => None,
+    }
+    .or_else(|| {
+        attrlist
+            .named_attribute("language")
+            .or_else(|| attrlist.named_attribute("source-language"))
+            .map(|attr| attr.value())
+    })?;
+    Some(lang.trim().to_ascii_lowercase()).filter(|lang| !lang.is_empty())
+}
+
 fn is_svgbob_attrlist(attrlist: Option<&Attrlist<'_>>) -> bool {
     let Some(attrlist) = attrlist else {
         return false;
@@ -5580,10 +9582,16 @@ fn is_svgbob_attrlist(attrlist: Option<&Attrlist<'_>>) -> bool {
 enum SpecialCodeBlock {
     Mermaid,
     Svgbob,
+    /// A diagram language with a build-time renderer (`dot`, `plantuml`, ...).
+    Diagram {
+        lang: String,
+    },
     /// A highlightable fenced block whose source is captured and re-emitted as a
     /// single pre-rendered, syntax-highlighted HTML event on the closing fence.
     Highlight {
         token: String,
+        options: FenceOptions,
+        snippet: Option<SnippetRef>,
     },
 }
 
@@ -5612,34 +9620,50 @@ fn describe_panic(panic: Box<dyn Any + Send>) -> String {
     }
 }
 
-fn render_markdown(source: &str) -> String {
+fn render_markdown(source: &str, renderer: &DocRenderer) -> String {
     let mut options = MdOptions::empty();
     options.insert(MdOptions::ENABLE_TABLES);
     options.insert(MdOptions::ENABLE_FOOTNOTES);
     let parser = Parser::new_ext(source, options);
-    // Holds the source of the block currently being captured — svgbob (rendered
-    // to SVG) or a highlightable block (rendered to highlighted spans). Both
-    // states are mutually exclusive, so one buffer serves both.
+    // Holds the source of the block currently being captured — svgbob or a
+    // configured diagram (rendered to SVG) or a highlightable block (rendered
+    // to highlighted spans). The states are mutually exclusive, so one buffer
+    // serves all of them.
     let special_block = RefCell::new(None::<SpecialCodeBlock>);
     let capture_buffer = RefCell::new(String::new());
     let is_capturing = || {
         matches!(
             &*special_block.borrow(),
-            Some(SpecialCodeBlock::Svgbob) | Some(SpecialCodeBlock::Highlight { .. })
+            Some(SpecialCodeBlock::Svgbob)
+                | Some(SpecialCodeBlock::Diagram { .. })
+                | Some(SpecialCodeBlock::Highlight { .. })
         )
     };
     let parser = parser.map(|event| match event {
         Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
-            if is_mermaid_fence(&info) {
+            if is_mermaid_fence(&info) && !renderer.diagrams.handles("mermaid") {
                 *special_block.borrow_mut() = Some(SpecialCodeBlock::Mermaid);
                 Event::Html("<pre class=\"mermaid\">".into())
             } else if is_svgbob_fence(&info) {
                 *special_block.borrow_mut() = Some(SpecialCodeBlock::Svgbob);
                 capture_buffer.borrow_mut().clear();
                 Event::Html("".into())
-            } else if highlight::is_highlightable(fence_token(&info)) {
+            } else if renderer.diagrams.handles(fence_token(&info)) {
+                let lang = fence_token(&info).to_ascii_lowercase();
+                *special_block.borrow_mut() = Some(SpecialCodeBlock::Diagram { lang });
+                capture_buffer.borrow_mut().clear();
+                Event::Html("".into())
+            } else if renderer.highlighter.is_highlightable(fence_token(&info))
+                || SnippetRef::parse(&info).is_some()
+            {
                 let token = fence_token(&info).to_string();
-                *special_block.borrow_mut() = Some(SpecialCodeBlock::Highlight { token });
+                let options = FenceOptions::parse(&info);
+                let snippet = SnippetRef::parse(&info);
+                *special_block.borrow_mut() = Some(SpecialCodeBlock::Highlight {
+                    token,
+                    options,
+                    snippet,
+                });
                 capture_buffer.borrow_mut().clear();
                 Event::Html("".into())
             } else {
@@ -5653,13 +9677,37 @@ fn render_markdown(source: &str) -> String {
                 let svg = render_svgbob_svg(&source);
                 Event::Html(format!("<div class=\"svgbob\">{svg}</div>").into())
             }
-            Some(SpecialCodeBlock::Highlight { token }) => {
-                let code = capture_buffer.borrow();
-                let inner =
-                    highlight::highlight(&token, &code).unwrap_or_else(|| escape_html(&code));
+            Some(SpecialCodeBlock::Diagram { lang }) => {
+                let source = capture_buffer.borrow().clone();
+                Event::Html(renderer.render_diagram(&lang, &source).into())
+            }
+            Some(SpecialCodeBlock::Highlight {
+                token,
+                mut options,
+                snippet,
+            }) => {
+                let body = capture_buffer.borrow();
+                let code = match &snippet {
+                    Some(snippet) => {
+                        options.start_line = options.start_line.or(snippet.first_line());
+                        renderer.load_snippet(snippet, &body)
+                    }
+                    None => body.clone(),
+                };
+                let mut inner = renderer
+                    .highlighter
+                    .highlight(&token, &code)
+                    .unwrap_or_else(|| escape_html(&code));
+                let numbered = options
+                    .line_numbers
+                    .unwrap_or(renderer.highlighter.line_numbers());
+                if numbered || !options.marked_lines.is_empty() {
+                    inner = highlight::wrap_lines(&inner, &options);
+                }
+                let class = if numbered { "hl hl--numbered" } else { "hl" };
                 Event::Html(
                     format!(
-                        "<pre class=\"hl\"><code class=\"language-{}\">{inner}</code></pre>\n",
+                        "<pre class=\"{class}\"><code class=\"language-{}\">{inner}</code></pre>\n",
                         escape_html(&token),
                     )
                     .into(),
@@ -5702,10 +9750,6 @@ fn prefix_asset_urls(html: &str, asset_base: &str) -> String {
         static ref ASSET_RE: Regex =
             Regex::new(r#"(?i)\b(src|href)=(\"([^\"]+)\"|'([^']+)')"#).unwrap();
         static ref SCHEME_RE: Regex = Regex::new(r"(?i)^[a-z][a-z0-9+.\-]*:").unwrap();
-        static ref SPEC_RE: Regex = Regex::new(
-            r#"(?i)^(?:\.\./)+(?:specs/)?(\d{4,})-[^/]+/[^#?]*?(?:\.adoc|\.md)?(#[-A-Za-z0-9_]+)?$"#
-        )
-        .unwrap();
     }
 
     let base = if asset_base.is_empty() || asset_base.ends_with('/') {
@@ -5730,7 +9774,7 @@ fn prefix_asset_urls(html: &str, asset_base: &str) -> String {
                 || value.starts_with('/')
                 || value.starts_with("//")
                 || SCHEME_RE.is_match(value)
-                || (attr_lower == "href" && SPEC_RE.is_match(value))
+                || (attr_lower == "href" && spec_link_target(value).is_some())
             {
                 return original.to_string();
             }
@@ -5778,30 +9822,59 @@ fn normalize_spec_link(
         return url.to_string();
     }
 
+    if let Some((spec_id, fragment)) = spec_link_target(url) {
+        if spec_ids.contains(spec_id) {
+            return join_spec_link(prefix, spec_id, fragment, trailing_slashes);
+        }
+    }
+
+    url.to_string()
+}
+
+/// The spec id and `#fragment` a relative source link points at, such as
+/// `../0002-name/README.md#goals` or `0002-name.adoc`.
+fn spec_link_target(url: &str) -> Option<(&str, &str)> {
     lazy_static! {
         static ref SCHEME_RE: Regex = Regex::new(r"(?i)^[a-z][a-z0-9+.\-]*:").unwrap();
         static ref SPEC_RE: Regex = Regex::new(
-            r#"(?i)^(?:\.\./)+(?:specs/)?(\d{4,})-[^/]+/[^#?]*?(?:\.adoc|\.md)?(#[-A-Za-z0-9_]+)?$"#
+            r#"(?i)^(?:(?:\.\./)+(?:specs/)?(\d{4,})-[^/]+/[^#?]*?(?:\.adoc|\.md)?|(?:\./)?(\d{4,})-[^/#?]+\.(?:adoc|md))(#[-A-Za-z0-9_]+)?$"#
         )
         .unwrap();
     }
 
     if SCHEME_RE.is_match(url) {
-        return url.to_string();
+        return None;
     }
+    let caps = SPEC_RE.captures(url)?;
+    let spec_id = caps.get(1).or_else(|| caps.get(2))?.as_str();
+    let fragment = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+    Some((spec_id, fragment))
+}
 
-    if let Some(caps) = SPEC_RE.captures(url) {
-        let spec_id = caps
-            .get(1)
-            .map(|m| m.as_str().to_string())
-            .unwrap_or_default();
-        let fragment = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-        if spec_ids.contains(&spec_id) {
-            return join_spec_link(prefix, &spec_id, fragment, trailing_slashes);
-        }
+/// Replace links to specs left out of the site with their text, so a
+/// published page never points at (or names the path of) a hidden spec.
+fn scrub_hidden_spec_links(html: &str, hidden: &HashSet<String>) -> String {
+    lazy_static! {
+        static ref ANCHOR_RE: Regex =
+            Regex::new(r#"(?is)<a\b[^>]*?\bhref=(?:"([^"]*)"|'([^']*)')[^>]*>(.*?)</a>"#).unwrap();
+    }
+    if hidden.is_empty() {
+        return html.to_string();
     }
 
-    url.to_string()
+    ANCHOR_RE
+        .replace_all(html, |caps: &regex::Captures| {
+            let url = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map(|m| m.as_str())
+                .unwrap_or("");
+            match spec_link_target(url) {
+                Some((spec_id, _)) if hidden.contains(spec_id) => caps[3].to_string(),
+                _ => caps[0].to_string(),
+            }
+        })
+        .to_string()
 }
 
 fn normalize_timestamp(value: Option<&Value>) -> Option<i64> {
@@ -5890,7 +9963,8 @@ fn extract_markdown_leading_h1(source: &str) -> Option<String> {
 }
 
 fn extract_asciidoc_leading_title(source: &str) -> Option<String> {
-    for line in source.lines() {
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
         let trimmed = line.trim();
         if trimmed.starts_with("//") || trimmed.starts_with("/*") {
             continue;
@@ -5904,7 +9978,8 @@ fn extract_asciidoc_leading_title(source: &str) -> Option<String> {
         if leading_equals >= 1 {
             let title = trimmed.trim_start_matches('=').trim();
             if !title.is_empty() {
-                return Some(title.to_string());
+                let attributes = asciidoc_header_attributes(lines);
+                return Some(substitute_attribute_references(title, &attributes));
             }
         }
 
@@ -5914,6 +9989,25 @@ fn extract_asciidoc_leading_title(source: &str) -> Option<String> {
     None
 }
 
+/// The `:name: value` attribute entries of the header that follows the title
+/// line, which ends at the first blank line.
+fn asciidoc_header_attributes<'a>(lines: impl Iterator<Item = &'a str>) -> HashMap<String, String> {
+    lazy_static! {
+        static ref ATTRIBUTE_ENTRY_RE: Regex =
+            Regex::new(r"^:([A-Za-z0-9_][A-Za-z0-9_-]*):(?:\s+(.*))?$").unwrap();
+    }
+
+    lines
+        .map(str::trim)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| {
+            let caps = ATTRIBUTE_ENTRY_RE.captures(line)?;
+            let value = caps.get(2).map(|m| m.as_str().trim()).unwrap_or("");
+            Some((caps[1].to_string(), value.to_string()))
+        })
+        .collect()
+}
+
 fn extract_spec_id(name: &str) -> Option<String> {
     lazy_static! {
         static ref ID_RE: Regex = Regex::new(r"^(\d{4,})").unwrap();
@@ -6189,6 +10283,10 @@ fn slugify_status(status: &str) -> String {
     slugify_author(status)
 }
 
+fn slugify_team(name: &str) -> String {
+    slugify_author(name)
+}
+
 fn collect_status_summaries(specs: &[SpecDocument]) -> Vec<StatusSummary> {
     let mut summaries: HashMap<String, StatusSummary> = HashMap::new();
     for spec in specs.iter().filter(|spec| spec.listed) {
@@ -6311,6 +10409,12 @@ fn validate_project_config_for_check(
     let config = ProjectConfiguration::from_json_value(&value);
     validate_config_value(&value, &config, &mut report, &path);
 
+    let project_root = path.parent().unwrap_or_else(|| Path::new("."));
+    let (_, problems) = Highlighter::from_config(&config.highlight, project_root);
+    for problem in problems {
+        report.add_error(problem, Some(path.display().to_string()));
+    }
+
     (config, report)
 }
 
@@ -6759,47 +10863,310 @@ fn validate_cross_references(
     report
 }
 
-fn collect_references(
-    source: &str,
-    format: DocFormat,
-) -> (Vec<AssetReference>, Vec<CrossReference>) {
-    let raw_refs = match format {
-        DocFormat::Markdown => collect_markdown_references(source),
-        DocFormat::Asciidoc => collect_asciidoc_references(source),
+/// Warn about specs CODEOWNERS assigns to nobody.
+fn validate_ownership(specs: &[LintSpec], spec_root: &Path) -> CategoryReport {
+    let mut report = CategoryReport::default();
+    if specs.is_empty() {
+        return report;
+    }
+    let Some(repo) = open_git_repository(spec_root) else {
+        return report;
+    };
+    let Some(codeowners) = CodeOwners::load(repo.workdir()) else {
+        report.add_warning(
+            format!(
+                "No CODEOWNERS file in {}; specs have no owners",
+                repo.workdir().display()
+            ),
+            None,
+        );
+        return report;
     };
 
-    let mut assets = Vec::new();
-    let mut cross_refs = Vec::new();
-
-    for (target, line) in raw_refs {
-        match classify_reference(&target) {
-            Some(ReferenceKind::Asset(path)) => assets.push(AssetReference { path, line }),
-            Some(ReferenceKind::Cross {
-                raw,
-                target_spec,
-                anchor,
-            }) => cross_refs.push(CrossReference {
-                raw,
-                target_spec,
-                anchor,
-                line,
-            }),
-            None => {}
+    for spec in specs {
+        let doc_path = spec
+            .doc_path
+            .canonicalize()
+            .unwrap_or_else(|_| spec.doc_path.clone());
+        let Ok(relative) = doc_path.strip_prefix(repo.workdir()) else {
+            continue;
+        };
+        if codeowners.owners_of(relative).is_empty() {
+            report.add_warning(
+                format!("Spec {} has no owner in CODEOWNERS", spec.id),
+                Some(spec.doc_path.display().to_string()),
+            );
         }
     }
-
-    (assets, cross_refs)
+    report
 }
 
-fn collect_markdown_references(source: &str) -> Vec<(String, usize)> {
-    let mut options = MdOptions::empty();
-    options.insert(MdOptions::ENABLE_TABLES);
-    options.insert(MdOptions::ENABLE_FOOTNOTES);
-
-    Parser::new_ext(source, options)
-        .into_offset_iter()
-        .filter_map(|(event, range)| match event {
-            pulldown_cmark::Event::Start(Tag::Link { dest_url, .. })
+/// Flag status changes the `[workflow]` doesn't allow. On a branch, each
+/// spec's status is compared with its status on the default branch, as a pull
+/// request would change it; on the default branch, with the last different
+/// status committed for it. New specs should start in an initial status.
+fn validate_workflow(
+    specs: &[LintSpec],
+    spec_root: &Path,
+    project_config: &ProjectConfiguration,
+) -> CategoryReport {
+    let mut report = CategoryReport::default();
+    let workflow = &project_config.workflow;
+    for problem in &workflow.problems {
+        report.add_warning(problem.clone(), None);
+    }
+    if workflow.is_empty() || specs.is_empty() {
+        return report;
+    }
+    let Some(repo) = open_git_repository(spec_root) else {
+        return report;
+    };
+
+    let default_branch = project_config.default_branch.as_deref();
+    let base = repo
+        .base_commit_sha(default_branch)
+        .filter(|base| repo.head_commit_sha().as_ref() != Some(base))
+        .filter(|_| default_branch.is_none() || repo.current_branch().as_deref() != default_branch);
+    let doc_paths: Vec<PathBuf> = specs
+        .iter()
+        .map(|spec| {
+            spec.doc_path
+                .canonicalize()
+                .unwrap_or_else(|_| spec.doc_path.clone())
+        })
+        .collect();
+    let history = match base {
+        Some(_) => HashMap::new(),
+        None => repo.file_versions(&doc_paths),
+    };
+
+    let reader = MetadataReader::new(project_config.clone());
+    for (spec, doc_path) in specs.iter().zip(&doc_paths) {
+        let Ok(relative) = doc_path.strip_prefix(repo.workdir()) else {
+            continue;
+        };
+        let display_name = display_name_from_dir(&spec.dir_name);
+        let status_of = |source: &str, committed: bool| {
+            let metadata = reader.read(source, spec.format, &display_name).metadata;
+            reader.resolve_status(metadata.status, committed)
+        };
+        let committed: Vec<String> = match &base {
+            Some(base) => repo
+                .read_file_at(base, relative)
+                .map(|bytes| status_of(&String::from_utf8_lossy(&bytes), true))
+                .into_iter()
+                .collect(),
+            None => history
+                .get(relative)
+                .map(|versions| {
+                    versions
+                        .iter()
+                        .map(|bytes| status_of(&String::from_utf8_lossy(bytes), true))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        let status = status_of(&spec.source, !committed.is_empty());
+        let location = Some(spec.doc_path.display().to_string());
+
+        let Some(previous) = committed
+            .iter()
+            .find(|previous| !previous.eq_ignore_ascii_case(&status))
+        else {
+            let initial = workflow.initial_states();
+            if committed.is_empty()
+                && workflow.state(&status).is_some()
+                && !initial
+                    .iter()
+                    .any(|state| state.eq_ignore_ascii_case(&status))
+            {
+                report.add_warning(
+                    format!(
+                        "New spec {} starts as {status}; new specs should start as {}",
+                        spec.id,
+                        initial.join(" or ")
+                    ),
+                    location,
+                );
+            }
+            continue;
+        };
+        let allowed = || match workflow.next_states(previous).as_slice() {
+            [] => "none".to_string(),
+            next => next.join(", "),
+        };
+        match workflow.judge(previous, &status) {
+            Transition::Illegal => report.add_error(
+                format!(
+                    "Spec {} moved from {previous} to {status}, which the workflow doesn't allow (from {previous}: {})",
+                    spec.id,
+                    allowed()
+                ),
+                location,
+            ),
+            Transition::FromTerminal => report.add_error(
+                format!(
+                    "Spec {} moved from {previous} to {status}, but {previous} is a terminal status",
+                    spec.id
+                ),
+                location,
+            ),
+            Transition::Unchanged | Transition::Allowed | Transition::Unknown => {}
+        }
+    }
+    report
+}
+
+/// Flags unknown visibility levels, and links from a spec to one more
+/// restricted than itself: readers of the first never see the second, so the
+/// link is published as plain text.
+fn validate_visibility(
+    specs: &[LintSpec],
+    lint_data: &HashMap<String, SpecLintData>,
+    project_config: &ProjectConfiguration,
+) -> CategoryReport {
+    let mut report = CategoryReport::default();
+    for problem in &project_config.visibility.problems {
+        report.add_warning(problem.clone(), None);
+    }
+
+    let reader = MetadataReader::new(project_config.clone());
+    let mut levels = HashMap::new();
+    for spec in specs {
+        let display_name = display_name_from_dir(&spec.dir_name);
+        let metadata = reader
+            .read(&spec.source, spec.format, &display_name)
+            .metadata;
+        if let Some(declared) = metadata.visibility.as_deref() {
+            if Visibility::parse(declared).is_none() {
+                report.add_warning(
+                    format!(
+                        "Unknown visibility '{declared}' in {} (treated as private)",
+                        spec.doc_path.display()
+                    ),
+                    None,
+                );
+            }
+        }
+        let status = reader.resolve_status(metadata.status, false);
+        levels.insert(
+            spec.id.as_str(),
+            reader.resolve_visibility(metadata.visibility.as_deref(), &status),
+        );
+    }
+
+    for spec in specs {
+        let (Some(data), Some(&level)) = (lint_data.get(&spec.id), levels.get(spec.id.as_str()))
+        else {
+            continue;
+        };
+        for reference in &data.cross_refs {
+            let Some(&target) = levels.get(reference.target_spec.as_str()) else {
+                continue;
+            };
+            if target > level {
+                report.add_warning(
+                    format!(
+                        "Spec {} is {level} but links to {target} spec {}",
+                        spec.id, reference.target_spec
+                    ),
+                    Some(format!(
+                        "Referenced in: {}:{}",
+                        spec.doc_path.display(),
+                        reference.line
+                    )),
+                );
+            }
+        }
+    }
+
+    report
+}
+
+/// Flags `file=` snippets whose file, line range or region no longer exists,
+/// so quoted code is fixed before it silently falls back to the inline body.
+fn validate_snippet_references(specs: &[LintSpec], root: &Path) -> CategoryReport {
+    let mut report = CategoryReport::default();
+
+    for spec in specs {
+        if !matches!(spec.format, DocFormat::Markdown) {
+            continue;
+        }
+        for (snippet, line) in collect_markdown_snippets(&spec.source) {
+            if let Err(err) = snippet.load(root) {
+                report.add_error(
+                    format!("Broken snippet: {err}"),
+                    Some(format!(
+                        "Referenced in: {}:{}",
+                        spec.doc_path.display(),
+                        line
+                    )),
+                );
+            }
+        }
+    }
+
+    report
+}
+
+fn collect_markdown_snippets(source: &str) -> Vec<(SnippetRef, usize)> {
+    let mut options = MdOptions::empty();
+    options.insert(MdOptions::ENABLE_TABLES);
+    options.insert(MdOptions::ENABLE_FOOTNOTES);
+
+    Parser::new_ext(source, options)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            pulldown_cmark::Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => Some((
+                SnippetRef::parse(&info)?,
+                line_number_from_offset(source, range.start),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn collect_references(
+    source: &str,
+    format: DocFormat,
+) -> (Vec<AssetReference>, Vec<CrossReference>) {
+    let raw_refs = match format {
+        DocFormat::Markdown => collect_markdown_references(source),
+        DocFormat::Asciidoc => collect_asciidoc_references(source),
+    };
+
+    let mut assets = Vec::new();
+    let mut cross_refs = Vec::new();
+
+    for (target, line) in raw_refs {
+        match classify_reference(&target) {
+            Some(ReferenceKind::Asset(path)) => assets.push(AssetReference { path, line }),
+            Some(ReferenceKind::Cross {
+                raw,
+                target_spec,
+                anchor,
+            }) => cross_refs.push(CrossReference {
+                raw,
+                target_spec,
+                anchor,
+                line,
+            }),
+            None => {}
+        }
+    }
+
+    (assets, cross_refs)
+}
+
+fn collect_markdown_references(source: &str) -> Vec<(String, usize)> {
+    let mut options = MdOptions::empty();
+    options.insert(MdOptions::ENABLE_TABLES);
+    options.insert(MdOptions::ENABLE_FOOTNOTES);
+
+    Parser::new_ext(source, options)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            pulldown_cmark::Event::Start(Tag::Link { dest_url, .. })
             | pulldown_cmark::Event::Start(Tag::Image { dest_url, .. }) => Some((
                 dest_url.to_string(),
                 line_number_from_offset(source, range.start),
@@ -7092,9 +11459,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn renders_asciidoc_tables_admonitions_and_callouts() {
+        let renderer = DocRenderer::new();
+        let src = "= {product} Guide\n:product: Dossiers\n\n\
+                   NOTE: About {product}.\n\n\
+                   [WARNING]\n====\nCareful.\n====\n\n\
+                   .Limits\n|===\n|Name |Value\n\n|a |b \\| c\n|d\n|e\n|===\n\n\
+                   [source,rust]\n----\nfn main() {} // <1>\n----\n<1> Entry point.\n\n\
+                   [quote, Jane Doe, Specs]\n____\nQuoted.\n____\n\n\
+                   ****\nAside.\n****\n";
+        let html = renderer
+            .render_asciidoc(src)
+            .expect("asciidoc render succeeds");
+
+        assert!(html.contains(">Dossiers Guide</h1>"), "title: {html}");
+        assert!(
+            html.contains("admonition--note") && html.contains("<p>About Dossiers.</p>"),
+            "note admonition: {html}"
+        );
+        assert!(
+            html.contains("admonition--warning") && html.contains("Careful."),
+            "warning admonition: {html}"
+        );
+        assert!(
+            html.contains("<thead><tr><th>Name</th><th>Value</th></tr></thead>"),
+            "implicit header row: {html}"
+        );
+        assert!(
+            html.contains("<tr><td>a</td><td>b | c</td></tr><tr><td>d</td><td>e</td></tr>"),
+            "body rows: {html}"
+        );
+        assert!(
+            html.contains("<div class=\"adoc-title\">Limits</div><table>"),
+            "table title: {html}"
+        );
+        assert!(
+            html.contains("fn main() {} <b class=\"conum\" data-value=\"1\">(1)</b>"),
+            "callout marker: {html}"
+        );
+        assert!(
+            html.contains("<ol class=\"adoc-callouts\"><li value=\"1\">Entry point.</li></ol>"),
+            "callout list: {html}"
+        );
+        assert!(
+            html.contains("<blockquote class=\"adoc-block quote\">")
+                && html.contains("&#8212; Jane Doe, <cite>Specs</cite>"),
+            "quote: {html}"
+        );
+        assert!(
+            html.contains("<aside class=\"adoc-block sidebar\">"),
+            "sidebar: {html}"
+        );
+    }
+
+    #[test]
+    fn asciidoc_xrefs_link_to_spec_pages() {
+        let renderer = DocRenderer::new();
+        let src = "See xref:0002-other.adoc[Other], <<../0003-dir/dir.adoc#goals,Dir>> \
+                   and <<local-anchor>>.";
+        let html = renderer
+            .render_asciidoc(src)
+            .expect("asciidoc render succeeds");
+        let spec_ids: HashSet<String> = ["0002".to_string(), "0003".to_string()].into();
+        let html = rewrite_spec_links(&html, &spec_ids, "/", false);
+
+        assert!(html.contains("<a href=\"/0002\">Other</a>"), "xref: {html}");
+        assert!(
+            html.contains("<a href=\"/0003#goals\">Dir</a>"),
+            "angle xref: {html}"
+        );
+        assert!(
+            html.contains("<a href=\"#local-anchor\">local-anchor</a>"),
+            "in-page xref: {html}"
+        );
+    }
+
+    #[test]
+    fn parses_asciidoc_table_columns_and_header_options() {
+        let table = parse_asciidoc_table(&["|===\n|a |b |c |d\n|==="], Some("2*"), Some(true));
+        assert_eq!(table.header, Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(table.rows, vec![vec!["c".to_string(), "d".to_string()]]);
+
+        let table = parse_asciidoc_table(&["|===\n|x\n|y", "|z\n|==="], None, Some(false));
+        assert_eq!(table.header, None);
+        assert_eq!(table.rows.len(), 3);
+
+        assert_eq!(table_column_count("1,2;3"), Some(3));
+        assert_eq!(table_column_count("3*,1"), Some(4));
+    }
+
+    #[test]
+    fn asciidoc_title_substitutes_header_attributes() {
+        let src = "= {product} Guide\n:product: Dossiers\n\nBody {product}.";
+        assert_eq!(
+            extract_asciidoc_leading_title(src).as_deref(),
+            Some("Dossiers Guide")
+        );
+    }
+
     #[test]
     fn fenced_code_block_is_syntax_highlighted() {
-        let html = render_markdown("```rust\nfn main() {}\n```\n");
+        let html = render_markdown("```rust\nfn main() {}\n```\n", &DocRenderer::new());
         assert!(
             html.contains("<pre class=\"hl\">"),
             "not highlighted: {html}"
@@ -7106,11 +11572,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn file_snippets_are_embedded_and_broken_ones_reported() {
+        let temp_root = std::env::temp_dir().join(format!(
+            "dossiers-snippets-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_millis()
+        ));
+        let _ = fs::remove_dir_all(&temp_root);
+        fs::create_dir_all(temp_root.join("src")).expect("create temp root");
+        fs::write(
+            temp_root.join("src/lib.rs"),
+            "// header\npub fn answer() -> u32 {\n    42\n}\n",
+        )
+        .expect("write source");
+
+        let source = "```rust file=src/lib.rs#L2-L4 showLineNumbers\nstale\n```\n\n\
+                      ```rust file=src/gone.rs\nfallback\n```\n";
+        let renderer = DocRenderer::new().with_snippet_root(temp_root.clone());
+        let html = render_markdown(source, &renderer);
+        assert!(html.contains("answer"), "snippet not embedded: {html}");
+        assert!(!html.contains("stale"), "inline body kept: {html}");
+        assert!(html.contains("data-line=\"2\""), "numbering lost: {html}");
+        assert!(html.contains("fallback"), "missing file hid block: {html}");
+
+        let spec = LintSpec {
+            id: "0001".to_string(),
+            dir_name: "0001-demo".to_string(),
+            doc_path: temp_root.join("0001-demo.md"),
+            spec_root: temp_root.clone(),
+            source: source.to_string(),
+            format: DocFormat::Markdown,
+        };
+        let report = validate_snippet_references(&[spec], &temp_root);
+        let _ = fs::remove_dir_all(&temp_root);
+
+        assert_eq!(report.errors.len(), 1, "{:?}", report.errors);
+        assert!(report.errors[0].message.contains("src/gone.rs"));
+        assert!(report.errors[0]
+            .location
+            .as_deref()
+            .is_some_and(|location| location.ends_with(":5")));
+    }
+
+    #[test]
+    fn fence_attributes_number_and_mark_highlighted_lines() {
+        let source = "```rust {2} showLineNumbers\nlet a = 1;\nlet b = 2;\n```\n";
+        let html = render_markdown(source, &DocRenderer::new());
+        assert!(html.contains("<pre class=\"hl hl--numbered\">"), "{html}");
+        assert!(has_highlight_markup(&html));
+        assert!(html.contains("class=\"hl-line\" data-line=\"1\""), "{html}");
+        assert!(
+            html.contains("class=\"hl-line hl-line--marked\" data-line=\"2\""),
+            "{html}"
+        );
+
+        let config = highlight::HighlightConfig {
+            line_numbers: true,
+            ..Default::default()
+        };
+        let (highlighter, _) = Highlighter::from_config(&config, Path::new("."));
+        let renderer = DocRenderer::new().with_highlighter(highlighter);
+        let html = render_markdown("```rust\nlet a = 1;\n```\n", &renderer);
+        assert!(html.contains("hl--numbered"), "{html}");
+        let html = render_markdown("```rust nolinenos\nlet a = 1;\n```\n", &renderer);
+        assert!(html.contains("<pre class=\"hl\">"), "{html}");
+    }
+
     #[test]
     fn mermaid_code_block_passes_through_untouched() {
         // Diagram blocks are rendered client-side from raw source, so they must
         // keep the `mermaid` marker and must not be highlighted.
-        let html = render_markdown("```mermaid\ngraph TD; A-->B;\n```\n");
+        let html = render_markdown("```mermaid\ngraph TD; A-->B;\n```\n", &DocRenderer::new());
         assert!(
             html.contains("class=\"mermaid\""),
             "mermaid marker lost: {html}"
@@ -7126,15 +11661,60 @@ mod tests {
     fn svgbob_code_block_renders_svg_not_highlighted() {
         // svgbob is rendered server-side to inline SVG and must not be treated
         // as a highlightable language.
-        let html = render_markdown("```svgbob\n+--+\n|  |\n+--+\n```\n");
+        let html = render_markdown("```svgbob\n+--+\n|  |\n+--+\n```\n", &DocRenderer::new());
         assert!(html.contains("class=\"svgbob\""), "no svgbob div: {html}");
         assert!(html.contains("<svg"), "svgbob svg missing: {html}");
         assert!(!html.contains("class=\"hl-"), "svgbob highlighted: {html}");
     }
 
+    #[test]
+    fn configured_diagram_blocks_render_inline_svg() {
+        let registry = DiagramRegistry::from_config(
+            &diagram::DiagramConfig::from_json_value(&serde_json::json!({ "cache": false })),
+            Path::new("."),
+        );
+        let renderer = DocRenderer::with_diagrams(registry);
+
+        let html = render_markdown("```dot\ndigraph { a -> b }\n```\n", &renderer);
+        assert!(html.contains("class=\"diagram diagram--dot\""), "{html}");
+        assert!(html.contains("<svg"), "dot svg missing: {html}");
+
+        let html = renderer
+            .render(
+                "= Doc\n\n[source,dot]\n----\ndigraph { a -> b }\n----\n",
+                DocFormat::Asciidoc,
+            )
+            .unwrap();
+        assert!(html.contains("class=\"diagram diagram--dot\""), "{html}");
+
+        // Without a configured registry the block stays (highlighted) source.
+        let html = render_markdown("```dot\ndigraph { a -> b }\n```\n", &DocRenderer::new());
+        assert!(!html.contains("<svg"), "{html}");
+        assert!(html.contains("class=\"language-dot\""), "{html}");
+    }
+
+    #[test]
+    fn failed_mermaid_prerender_falls_back_to_client_runtime() {
+        let mut config = diagram::DiagramConfig::from_json_value(&serde_json::json!({
+            "cache": false
+        }));
+        config.enable_embedded("mermaid");
+        // No Mermaid runtime supplied, so pre-rendering cannot succeed.
+        let renderer =
+            DocRenderer::with_diagrams(DiagramRegistry::from_config(&config, Path::new(".")));
+
+        let html = render_markdown("```mermaid\ngraph TD; A-->B;\n```\n", &renderer);
+        assert!(!html.contains("<svg"), "{html}");
+        assert!(html.contains("class=\"language-mermaid\""), "{html}");
+        assert!(
+            has_mermaid_markup(&html),
+            "client runtime not loaded: {html}"
+        );
+    }
+
     #[test]
     fn unknown_language_renders_as_plain_escaped_code() {
-        let html = render_markdown("```\n<not code>\n```\n");
+        let html = render_markdown("```\n<not code>\n```\n", &DocRenderer::new());
         assert!(
             !html.contains("class=\"hl-"),
             "plain block highlighted: {html}"
@@ -7142,6 +11722,1182 @@ mod tests {
         assert!(html.contains("&lt;not code&gt;"), "not escaped: {html}");
     }
 
+    #[test]
+    fn static_site_builds_from_a_bundle_with_pr_revisions() {
+        use dossiers::bundle::{
+            Asset, Mainline, Manifest, Package, PrChangeSet, PrSpecMeta, SourceMode, Spec,
+            SpecChange, SpecIndexEntry, PACKAGE_VERSION,
+        };
+
+        let temp_root = std::env::temp_dir().join(format!(
+            "dossiers-from-bundle-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_millis()
+        ));
+        let _ = fs::remove_dir_all(&temp_root);
+        fs::create_dir_all(&temp_root).expect("create temp root");
+
+        let spec = |source: &str| Spec {
+            id: "0001".into(),
+            dir_name: "0001-auth".into(),
+            format: dossiers::bundle::DocFormat::Markdown,
+            source_path: "auth.md".into(),
+            source: source.as_bytes().to_vec(),
+            assets: vec![],
+        };
+        let mut mainline_spec = spec("# Auth\n\n![flow](flow.svg)\n");
+        mainline_spec.assets.push(Asset {
+            path: "flow.svg".into(),
+            content_type: None,
+            bytes: b"<svg/>".to_vec(),
+        });
+        let package = Package {
+            manifest: Manifest {
+                package_version: PACKAGE_VERSION,
+                commit: Some("abc123".into()),
+                branch: Some("main".into()),
+                timestamp: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+                source: SourceMode::Push,
+                specs: vec![SpecIndexEntry {
+                    id: "0001".into(),
+                    dir_name: "0001-auth".into(),
+                    source_path: "auth.md".into(),
+                    format: dossiers::bundle::DocFormat::Markdown,
+                    title: "Authentication".into(),
+                    status: "Accepted".into(),
+                    created: None,
+                    updated: chrono::DateTime::from_timestamp(1_690_000_000, 0),
+                    authors: vec!["Ada".into()],
+                    authors_meta: vec![],
+                    approved_by: vec!["grace".into()],
+                    owners: vec!["@acme/identity".into()],
+                    teams: vec![],
+                    extra: Default::default(),
+                    links: vec![],
+                    fields: vec![],
+                    visibility: None,
+                }],
+                base_commit: None,
+                deleted_specs: vec![],
+                deleted_prs: vec![],
+                digests: Default::default(),
+                unchanged: Default::default(),
+            },
+            project_config: Some(b"title = \"Packaged\"\n".to_vec()),
+            mainline: Mainline {
+                specs: vec![mainline_spec],
+            },
+            pr_changes: vec![PrChangeSet {
+                pr_number: 7,
+                branch: "auth-v2".into(),
+                head_sha: "def456".into(),
+                title: "Auth v2".into(),
+                author: None,
+                state: "REVIEW".into(),
+                url: String::new(),
+                created_at: None,
+                updated_at: None,
+                reviewers: vec!["linus".into()],
+                reviews: vec![dossiers::bundle::PrReview {
+                    reviewer: "grace".into(),
+                    state: "CHANGES_REQUESTED".into(),
+                    submitted_at: None,
+                }],
+                labels: vec!["rfc".into()],
+                merge_commit_sha: None,
+                spec_changes: vec![SpecChange::Upsert(spec("# Auth v2\n\n![flow](flow.svg)\n"))],
+                asset_changes: vec![],
+                spec_meta: vec![PrSpecMeta {
+                    spec_id: "0001".into(),
+                    title: "Authentication v2".into(),
+                    status: "REVIEW".into(),
+                    authors: vec![],
+                    authors_meta: vec![],
+                    created: None,
+                    updated: None,
+                    links: vec![],
+                    fields: vec![],
+                    visibility: None,
+                }],
+            }],
+        };
+        let bundle = temp_root.join("pkg.zip");
+        package
+            .write_zip(File::create(&bundle).expect("create bundle"))
+            .expect("write bundle");
+
+        let workspace = temp_root.join("workspace");
+        let (state, static_mounts) =
+            load_bundle_state(&bundle, &workspace, None, Assets::embedded(), false)
+                .expect("load bundle");
+        assert_eq!(state.site_name, "Packaged");
+        assert_eq!(state.generated_at, 1_700_000_000_000);
+        let main = &state.specs_by_id["0001"];
+        assert_eq!(main.title, "Authentication");
+        assert_eq!(main.status, "Accepted");
+        assert_eq!(main.authors, vec!["Ada".to_string()]);
+        let revision = &state.specs_by_id["0001/pr/7"];
+        assert_eq!(revision.title, "Authentication v2");
+        assert!(!revision.listed);
+        assert_eq!(state.revisions["0001"][0].source, RevisionSource::Pull(7));
+
+        let output = temp_root.join("site");
+        write_static_site(state, static_mounts, &output, false).expect("write site");
+        let index = fs::read_to_string(output.join("index.html")).expect("index");
+        let main_page = fs::read_to_string(output.join("0001/index.html")).expect("main page");
+        let revision_page =
+            fs::read_to_string(output.join("0001/pr/7/index.html")).expect("revision page");
+        // The PR did not touch the diagram, so it comes from mainline.
+        let revision_asset = output.join("0001/pr/7/flow.svg").is_file();
+        let main_asset = output.join("0001/flow.svg").is_file();
+        let _ = fs::remove_dir_all(&temp_root);
+
+        assert!(index.contains("Authentication"), "{index}");
+        assert!(main_page.contains("Approved by"), "{main_page}");
+        assert!(
+            revision_page.contains("grace</span><span class=\"meta-note\"> (changes requested)")
+        );
+        assert!(revision_page.contains("linus</span><span class=\"meta-note\"> (pending)"));
+        assert!(revision_page.contains(">rfc</span>"));
+        assert!(!revision_page.contains("Approved by"));
+        assert!(main_page.contains("@acme/identity") && revision_page.contains("@acme/identity"));
+        assert!(revision_asset);
+        assert!(main_asset);
+    }
+
+    #[test]
+    fn push_targets_leave_out_specs_they_do_not_publish() {
+        let root = std::env::temp_dir().join(format!(
+            "dossiers-push-target-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_millis()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        for (dir, status) in [
+            ("0001-retention", "Accepted"),
+            ("0002-travel", "Draft"),
+            ("0003-expenses", "Accepted"),
+        ] {
+            fs::create_dir_all(root.join(dir)).expect("create spec dir");
+            fs::write(
+                root.join(dir).join("README.md"),
+                format!("# {dir}\n\n- Status: {status}\n\nBody\n"),
+            )
+            .expect("write spec");
+        }
+        let target = PushTarget {
+            name: "public".into(),
+            statuses: vec!["accepted".into()],
+            exclude: vec!["0003".into()],
+            ..PushTarget::default()
+        };
+
+        let result = build_package(
+            &root,
+            &root,
+            &ProjectConfiguration::default(),
+            None,
+            Some("main".into()),
+            Some("abc123".into()),
+            true,
+            Some(&target),
+            Visibility::Private,
+            None,
+        );
+        let _ = fs::remove_dir_all(&root);
+        let (package, _) = result.expect("build package");
+
+        let ids: Vec<&str> = package
+            .mainline
+            .specs
+            .iter()
+            .map(|s| s.id.as_str())
+            .collect();
+        assert_eq!(ids, ["0001"]);
+        assert_eq!(package.manifest.specs.len(), 1);
+        assert_eq!(package.manifest.specs[0].status, "Accepted");
+    }
+
+    #[test]
+    fn push_plan_lists_changes_by_kind() {
+        let plan = PushPlan {
+            base: Some("263cddf0123456789".into()),
+            added: vec![("0004".into(), "Four".into())],
+            modified: vec![("0002".into(), "Two".into())],
+            deleted: vec!["0003".into()],
+            prs_opened: vec![12],
+            prs_closed: vec![5],
+            assets_sent: 1,
+            assets_unchanged: 2,
+            bytes: 2048,
+            ..PushPlan::default()
+        };
+        let rendered = plan.render("http://api/sync");
+        assert!(
+            rendered.starts_with("Push plan for http://api/sync (delta against 263cddf):"),
+            "{rendered}"
+        );
+        for line in [
+            "  + spec 0004  Four\n",
+            "  ~ spec 0002  Two\n",
+            "  - spec 0003\n",
+            "  + PR #12\n",
+            "  - PR #5\n",
+            "Plan: 1 to add, 1 to change, 1 to delete; PR revisions: 1 opened, 0 updated, 1 closed.",
+            "Sends 1 asset(s) (2 unchanged, reused from the server) in 2.0 KB.",
+        ] {
+            assert!(rendered.contains(line), "missing {line:?} in {rendered}");
+        }
+        assert!(!rendered.contains("full snapshot"), "{rendered}");
+
+        let full = PushPlan {
+            full_reason: Some("--full".into()),
+            resent: vec![("0001".into(), "One".into())],
+            ..PushPlan::default()
+        }
+        .render("http://api/sync");
+        assert!(
+            full.contains("full snapshot, --full; server state unknown"),
+            "{full}"
+        );
+        assert!(full.contains("  = 1 spec(s) sent again\n"), "{full}");
+        assert!(full.contains("anything it holds that is not in this push is deleted"));
+    }
+
+    #[test]
+    fn push_plans_classify_changes_against_the_synced_base() {
+        use git2::{IndexAddOption, Repository, Signature};
+
+        let root = std::env::temp_dir().join(format!(
+            "dossiers-push-plan-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_nanos()
+        ));
+        let repo_dir = root.join("repo");
+        for (dir, title) in [
+            ("0001-alpha", "Alpha"),
+            ("0002-beta", "Beta"),
+            ("0003-gamma", "Gamma"),
+        ] {
+            fs::create_dir_all(repo_dir.join(dir)).unwrap();
+            fs::write(repo_dir.join(dir).join("README.md"), format!("# {title}\n")).unwrap();
+        }
+        let repo = Repository::init(&repo_dir).unwrap();
+        let sig = Signature::now("Ada", "ada@example.com").unwrap();
+        let commit_all = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.update_all(["*"].iter(), None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap();
+        };
+        commit_all("add specs");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let server_data = root.join("api");
+        std::thread::spawn(move || {
+            actix_web::rt::System::new().block_on(async move {
+                let state = DevApiState {
+                    api: DevApi::new(server_data),
+                    token: Some("secret".into()),
+                };
+                let (server, addr) = bind_dev_api(state, 0).unwrap();
+                tx.send(addr).unwrap();
+                server.await
+            })
+        });
+        let api_url = format!("http://{}", rx.recv().unwrap());
+        run_push(
+            repo_dir.clone(),
+            None,
+            Some(api_url.clone()),
+            Some("acme".into()),
+            Some("secret".into()),
+            None,
+            None,
+            None,
+            true,
+            false,
+            None,
+            Some(30),
+            Some(0),
+            false,
+            false,
+            false,
+            false,
+        )
+        .expect("initial push");
+
+        fs::write(repo_dir.join("0002-beta/README.md"), "# Beta v2\n").unwrap();
+        fs::remove_dir_all(repo_dir.join("0003-gamma")).unwrap();
+        fs::create_dir_all(repo_dir.join("0004-delta")).unwrap();
+        fs::write(repo_dir.join("0004-delta/README.md"), "# Delta\n").unwrap();
+        commit_all("edit beta, drop gamma, add delta");
+
+        let config = ProjectConfiguration::default();
+        let client = reqwest::blocking::Client::new();
+        let delta = prepare_delta(
+            &client,
+            &api_url,
+            "acme",
+            "secret",
+            &repo_dir,
+            &repo_dir,
+            &config,
+            None,
+            None,
+            None,
+            true,
+            None,
+            Visibility::Public,
+            None,
+        )
+        .expect("prepare delta");
+        let state = fetch_sync_state(&client, &api_url, "acme", "secret").expect("sync state");
+        let (package, zip_bytes) = build_package(
+            &repo_dir,
+            &repo_dir,
+            &config,
+            None,
+            None,
+            None,
+            true,
+            None,
+            Visibility::Public,
+            None,
+        )
+        .expect("build package");
+
+        let ids =
+            |lines: &[(String, String)]| lines.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>();
+        let DeltaOutcome::Ready(delta) = delta else {
+            panic!("expected a delta push");
+        };
+        assert_eq!(ids(&delta.plan.added), ["0004"]);
+        assert_eq!(ids(&delta.plan.modified), ["0002"]);
+        assert_eq!(delta.plan.deleted, ["0003"]);
+        assert!(delta.plan.resent.is_empty());
+
+        // A full push is measured against the same base, with the server's
+        // PR heads deciding which revisions are new, moved or closed.
+        let pull = |number: u64, head: &str| dossiers::bundle::PrChangeSet {
+            pr_number: number,
+            branch: format!("pr-{number}"),
+            head_sha: head.into(),
+            title: String::new(),
+            author: None,
+            state: "REVIEW".into(),
+            url: String::new(),
+            created_at: None,
+            updated_at: None,
+            reviewers: vec![],
+            reviews: vec![],
+            labels: vec![],
+            merge_commit_sha: None,
+            spec_changes: vec![],
+            asset_changes: vec![],
+            spec_meta: vec![],
+        };
+        let mut package = package;
+        package.pr_changes = vec![pull(7, "moved"), pull(9, "same"), pull(12, "new")];
+        let state = SyncState {
+            pr_heads: Some(HashMap::from([
+                ("5".to_string(), "closed".to_string()),
+                ("7".to_string(), "old".to_string()),
+                ("9".to_string(), "same".to_string()),
+            ])),
+            ..state
+        };
+        let plan = plan_full_push(
+            Some(&state),
+            &repo_dir,
+            &config,
+            &package,
+            zip_bytes.len(),
+            "--full".into(),
+        );
+        let _ = fs::remove_dir_all(&root);
+        assert!(plan.base.is_some());
+        assert_eq!(ids(&plan.added), ["0004"]);
+        assert_eq!(ids(&plan.modified), ["0002"]);
+        assert_eq!(ids(&plan.resent), ["0001"]);
+        assert_eq!(plan.deleted, ["0003"]);
+        assert_eq!(plan.prs_opened, [12]);
+        assert_eq!(plan.prs_updated, [7]);
+        assert_eq!(plan.prs_resent, [9]);
+        assert_eq!(plan.prs_closed, [5]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_key_files_are_private_and_not_overwritten() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = std::env::temp_dir().join(format!("dossiers-keygen-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("signing.key");
+
+        write_secret_file(&path, "first\n", false).expect("new key file");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let refused = write_secret_file(&path, "second\n", false);
+        let kept = fs::read_to_string(&path).unwrap();
+        write_secret_file(&path, "third\n", true).expect("forced replacement");
+        let replaced = fs::read_to_string(&path).unwrap();
+        let forced_mode = fs::metadata(&path).unwrap().permissions().mode();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(refused.is_err());
+        assert_eq!(kept, "first\n");
+        assert_eq!(replaced, "third\n");
+        assert_eq!(forced_mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn push_retries_back_off_and_stop_at_permanent_errors() {
+        let base = Duration::from_secs(1);
+        assert_eq!(backoff_delay(base, 0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(8));
+        assert_eq!(backoff_delay(base, 40), MAX_RETRY_DELAY);
+
+        let mut attempts = 0;
+        let result = with_retries(3, Duration::ZERO, "chunk 1/2", || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(TransientPushError("server returned 503".into()).into())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut attempts = 0;
+        let result: Result<()> = with_retries(3, Duration::ZERO, "chunk 1/2", || {
+            attempts += 1;
+            bail!("server returned 400 Bad Request")
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result: Result<()> = with_retries(2, Duration::ZERO, "chunk 1/2", || {
+            attempts += 1;
+            Err(TransientPushError("timed out".into()).into())
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn pr_comment_flags_status_changes_and_findings() {
+        let pair = |title: &str, status: &str| Some((title.to_string(), status.to_string()));
+        let specs = vec![
+            PrCommentSpec {
+                spec_id: "RFC-0002".to_string(),
+                base: pair("Retention", "PROPOSED"),
+                head: pair("Retention | archive", "ACCEPTED"),
+                preview_url: Some("https://specs.example/0002/pr/7".to_string()),
+            },
+            PrCommentSpec {
+                spec_id: "RFC-0003".to_string(),
+                base: None,
+                head: pair("Quotas", "DRAFT"),
+                preview_url: None,
+            },
+            PrCommentSpec {
+                spec_id: "RFC-0004".to_string(),
+                base: pair("Legacy", "ACCEPTED"),
+                head: None,
+                preview_url: None,
+            },
+        ];
+        let body = compose_pr_comment(
+            &specs,
+            &[(
+                false,
+                "Missing status in specs/0003-quotas/README.md".to_string(),
+            )],
+        );
+        assert!(body.starts_with(PR_COMMENT_MARKER));
+        assert!(body.contains(
+            "| RFC-0002 | Retention → Retention \\| archive | PROPOSED → **ACCEPTED** | [preview](https://specs.example/0002/pr/7) |"
+        ));
+        assert!(body.contains("| RFC-0003 | Quotas | DRAFT | — |"));
+        assert!(body.contains("| RFC-0004 | ~~Legacy~~ | ~~ACCEPTED~~ | removed |"));
+        assert!(body.contains("changes the status of RFC-0002 from PROPOSED to **ACCEPTED**."));
+        assert!(body.contains("- ! Missing status in specs/0003-quotas/README.md"));
+
+        let quiet = compose_pr_comment(&specs[1..2], &[]);
+        assert!(!quiet.contains("[!IMPORTANT]"));
+        assert!(quiet.contains("No `dossiers check` findings"));
+    }
+
+    #[test]
+    fn branches_and_tags_become_revisions() {
+        use git2::{IndexAddOption, Repository, Signature, Time};
+
+        let root = std::env::temp_dir().join(format!(
+            "dossiers-git-refs-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_nanos()
+        ));
+        fs::create_dir_all(root.join("0001-retention")).unwrap();
+        fs::create_dir_all(root.join("0002-access")).unwrap();
+        let repo = Repository::init(&root).unwrap();
+        let commit = |message: &str, seconds: i64, update_ref: Option<&str>| {
+            let sig = Signature::new("Ada", "ada@example.com", &Time::new(seconds, 0)).unwrap();
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.update_all(["*"].iter(), None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let oid = repo
+                .commit(update_ref, &sig, &sig, message, &tree, &parents)
+                .unwrap();
+            repo.find_object(oid, None).unwrap()
+        };
+        fs::write(
+            root.join("0001-retention/README.md"),
+            "# Retention\n\n30 days.\n",
+        )
+        .unwrap();
+        fs::write(root.join("0002-access/README.md"), "# Access\n").unwrap();
+        let v1 = commit("initial", 1_000, Some("HEAD"));
+        repo.tag_lightweight("policy-v1", &v1, false).unwrap();
+        fs::write(
+            root.join("0001-retention/README.md"),
+            "# Retention\n\n60 days.\n",
+        )
+        .unwrap();
+        let v2 = commit("extend retention", 2_000, Some("HEAD"));
+        repo.tag_lightweight("policy-v2", &v2, false).unwrap();
+        repo.tag_lightweight("unrelated", &v2, false).unwrap();
+        fs::write(root.join("0002-access/README.md"), "# Access v2\n").unwrap();
+        let branch = commit("rework access", 3_000, None);
+        repo.reference("refs/heads/spec/access", branch.id(), false, "test")
+            .unwrap();
+        repo.reference("refs/heads/wip", branch.id(), false, "test")
+            .unwrap();
+        fs::write(root.join("0002-access/README.md"), "# Access\n").unwrap();
+
+        let config = ProjectConfiguration::from_json_value(&serde_json::json!({
+            "revisions": { "branches": ["spec/*"], "tags": ["policy-v*"] },
+        }));
+        let (mut state, mut static_mounts) = build_app_state(
+            &root,
+            &root,
+            "Specs".into(),
+            Assets::embedded(),
+            config.clone(),
+        )
+        .unwrap();
+        augment_with_git_refs(&mut state, &mut static_mounts, &root, &root, &config).unwrap();
+
+        let frozen = &state.specs_by_id["v/policy-v1/0001"];
+        assert!(frozen.source.contains("30 days"));
+        assert!(!frozen.listed);
+        assert_eq!(frozen.revision_of.as_deref(), Some("0001"));
+        assert!(state.specs_by_id["v/policy-v2/0001"]
+            .source
+            .contains("60 days"));
+        assert!(state.specs_by_id.contains_key("v/policy-v2/0002"));
+        assert!(!state.specs_by_id.contains_key("v/unrelated/0001"));
+        assert_eq!(
+            state.specs_by_id["0002/branch/spec-access"].title,
+            "Access v2"
+        );
+        assert!(!state.specs_by_id.contains_key("0001/branch/spec-access"));
+        assert!(!state.specs_by_id.contains_key("0002/branch/wip"));
+
+        let sources = |id: &str| -> Vec<RevisionSource> {
+            state.revisions[id]
+                .iter()
+                .map(|rev| rev.source.clone())
+                .collect()
+        };
+        assert_eq!(
+            sources("0001"),
+            [
+                RevisionSource::Tag("policy-v1".into()),
+                RevisionSource::Tag("policy-v2".into()),
+            ]
+        );
+        // policy-v2 left the access spec alone, so only v1 lists it.
+        assert_eq!(
+            sources("0002"),
+            [
+                RevisionSource::Branch("spec/access".into()),
+                RevisionSource::Tag("policy-v1".into()),
+            ]
+        );
+        let _ = fs::remove_dir_all(git_ref_workspace());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn push_syncs_a_snapshot_then_a_delta_to_the_dev_api() {
+        use git2::{IndexAddOption, Repository, Signature};
+
+        let root = std::env::temp_dir().join(format!(
+            "dossiers-push-dev-api-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_nanos()
+        ));
+        let repo_dir = root.join("repo");
+        let data_dir = root.join("api");
+        fs::create_dir_all(repo_dir.join("0001-alpha")).unwrap();
+        fs::create_dir_all(repo_dir.join("0002-beta")).unwrap();
+        fs::write(repo_dir.join("0001-alpha/alpha.md"), "# Alpha\n").unwrap();
+        fs::write(repo_dir.join("0001-alpha/figure.png"), [137, 80, 78, 71]).unwrap();
+        fs::write(repo_dir.join("0002-beta/beta.md"), "# Beta\n").unwrap();
+
+        let repo = Repository::init(&repo_dir).unwrap();
+        let sig = Signature::now("Ada", "ada@example.com").unwrap();
+        let commit_all = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.update_all(["*"].iter(), None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+                .to_string()
+        };
+        commit_all("add specs");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let server_data = data_dir.clone();
+        std::thread::spawn(move || {
+            actix_web::rt::System::new().block_on(async move {
+                let state = DevApiState {
+                    api: DevApi::new(server_data),
+                    token: Some("secret".into()),
+                };
+                let (server, addr) = bind_dev_api(state, 0).unwrap();
+                tx.send(addr).unwrap();
+                server.await
+            })
+        });
+        let addr = rx.recv().unwrap();
+        let push = || {
+            run_push(
+                repo_dir.clone(),
+                None,
+                Some(format!("http://{addr}")),
+                Some("acme".into()),
+                Some("secret".into()),
+                None,
+                None,
+                None,
+                true,
+                false,
+                None,
+                Some(30),
+                Some(0),
+                false,
+                false,
+                false,
+                false,
+            )
+        };
+
+        push().expect("full push");
+        fs::write(repo_dir.join("0001-alpha/alpha.md"), "# Alpha v2\n").unwrap();
+        fs::remove_dir_all(repo_dir.join("0002-beta")).unwrap();
+        let head = commit_all("edit alpha, drop beta");
+        push().expect("delta push");
+        push().expect("up-to-date push");
+
+        let snapshot = DevApi::new(&data_dir).snapshot("acme").unwrap().unwrap();
+        let received = fs::read_dir(data_dir.join("acme/packages"))
+            .unwrap()
+            .count();
+        let delta = fs::read(data_dir.join("acme/packages/2.zip")).unwrap();
+        let delta = dossiers::bundle::Package::read_zip(std::io::Cursor::new(delta)).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(received, 2, "the up-to-date push sends nothing");
+        assert!(delta.manifest.base_commit.is_some());
+        assert_eq!(delta.manifest.deleted_specs, ["0002"]);
+        assert!(delta.mainline.specs[0].assets.is_empty());
+        assert!(delta
+            .manifest
+            .unchanged
+            .contains_key("main/0001-alpha/figure.png"));
+
+        assert_eq!(snapshot.manifest.commit.as_deref(), Some(head.as_str()));
+        assert_eq!(snapshot.mainline.specs.len(), 1);
+        let alpha = &snapshot.mainline.specs[0];
+        assert_eq!(alpha.source, b"# Alpha v2\n");
+        assert_eq!(alpha.assets[0].bytes, [137, 80, 78, 71]);
+        assert_eq!(snapshot.manifest.specs[0].title, "Alpha v2");
+    }
+
+    /// Fails the first post of one chunk, so a push stops part-way, and
+    /// records every chunk the dev API accepts.
+    struct InterruptedSync {
+        fail_chunk: usize,
+        failed: std::sync::atomic::AtomicBool,
+        /// Applies the next final chunk but answers as if it timed out.
+        drop_final_reply: std::sync::atomic::AtomicBool,
+        accepted: std::sync::Mutex<Vec<usize>>,
+    }
+
+    async fn interrupted_sync(
+        path: web::Path<String>,
+        query: web::Query<DevApiSyncQuery>,
+        request: actix_web::HttpRequest,
+        body: web::Bytes,
+        state: web::Data<DevApiState>,
+        interrupt: web::Data<InterruptedSync>,
+    ) -> HttpResponse {
+        use std::sync::atomic::Ordering;
+        let chunk = query.chunk;
+        if chunk == Some(interrupt.fail_chunk) && !interrupt.failed.swap(true, Ordering::SeqCst) {
+            return HttpResponse::ServiceUnavailable().body("interrupted");
+        }
+        let drop_reply = query.last && interrupt.drop_final_reply.swap(false, Ordering::SeqCst);
+        interrupt.accepted.lock().unwrap().extend(chunk);
+        let response = dev_api_sync(path, query, request.clone(), body, state)
+            .await
+            .respond_to(&request)
+            .map_into_boxed_body();
+        if drop_reply {
+            return HttpResponse::GatewayTimeout().body("timed out");
+        }
+        response
+    }
+
+    #[test]
+    fn interrupted_chunked_push_resumes_with_the_missing_chunks() {
+        use git2::{IndexAddOption, Repository, Signature};
+
+        let root = std::env::temp_dir().join(format!(
+            "dossiers-push-resume-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_nanos()
+        ));
+        let repo_dir = root.join("repo");
+        // Three specs with incompressible assets, each near the chunk limit,
+        // push as three chunks.
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        for name in ["0001-alpha", "0002-beta", "0003-gamma"] {
+            let dir = repo_dir.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("README.md"), format!("# {name}\n")).unwrap();
+            let noise: Vec<u8> = (0..MAX_CHUNK_RAW_BYTES * 4 / 5)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed as u8
+                })
+                .collect();
+            fs::write(dir.join("noise.bin"), noise).unwrap();
+        }
+        let repo = Repository::init(&repo_dir).unwrap();
+        let sig = Signature::now("Ada", "ada@example.com").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "add specs", &tree, &[])
+            .unwrap();
+
+        let interrupt = web::Data::new(InterruptedSync {
+            fail_chunk: 1,
+            failed: Default::default(),
+            drop_final_reply: Default::default(),
+            accepted: Default::default(),
+        });
+        let (tx, rx) = std::sync::mpsc::channel();
+        let server_data = root.join("api");
+        let server_interrupt = interrupt.clone();
+        std::thread::spawn(move || {
+            actix_web::rt::System::new().block_on(async move {
+                let state = web::Data::new(DevApiState {
+                    api: DevApi::new(server_data),
+                    token: Some("secret".into()),
+                });
+                let server = HttpServer::new(move || {
+                    App::new()
+                        .app_data(state.clone())
+                        .app_data(server_interrupt.clone())
+                        .app_data(web::PayloadConfig::new(256 * 1024 * 1024))
+                        .route(
+                            "/api/v1/projects/{project}/sync/state",
+                            web::get().to(dev_api_state),
+                        )
+                        .route(
+                            "/api/v1/projects/{project}/sync",
+                            web::post().to(interrupted_sync),
+                        )
+                        .route(
+                            "/api/v1/projects/{project}/sync/runs/{run_id}",
+                            web::get().to(dev_api_run),
+                        )
+                        .route(
+                            "/api/v1/projects/{project}/snapshot",
+                            web::get().to(dev_api_snapshot),
+                        )
+                })
+                .bind(("127.0.0.1", 0))
+                .unwrap();
+                tx.send(server.addrs()[0]).unwrap();
+                server.run().await
+            })
+        });
+        let addr = rx.recv().unwrap();
+        let sync_url = format!("http://{addr}/api/v1/projects/acme/sync");
+        let push = |retries, full| {
+            run_push(
+                repo_dir.clone(),
+                None,
+                Some(format!("http://{addr}")),
+                Some("acme".into()),
+                Some("secret".into()),
+                None,
+                None,
+                None,
+                true,
+                false,
+                None,
+                Some(30),
+                Some(retries),
+                false,
+                false,
+                false,
+                full,
+            )
+        };
+
+        let interrupted = push(0, false).expect_err("chunk 2 fails");
+        assert!(format!("{interrupted:#}").contains("run the same push again"));
+        let resume: PushResume =
+            serde_json::from_slice(&fs::read(PushResume::path(&sync_url)).unwrap()).unwrap();
+        assert_eq!((resume.chunks.len(), resume.acknowledged), (3, 1));
+        let client = reqwest::blocking::Client::new();
+        assert_eq!(
+            fetch_run_chunks(&client, &sync_url, "secret", &resume.run_id),
+            Some(RunStatus {
+                chunks: vec![0],
+                finalized: false
+            })
+        );
+
+        push(0, false).expect("resumed push");
+        assert_eq!(*interrupt.accepted.lock().unwrap(), [0, 1, 2]);
+        assert!(!PushResume::path(&sync_url).exists());
+        assert_eq!(
+            fetch_run_chunks(&client, &sync_url, "secret", &resume.run_id),
+            Some(RunStatus {
+                chunks: Vec::new(),
+                finalized: true
+            })
+        );
+
+        // A final chunk the server applied but never acknowledged is not
+        // posted again: the retry finds the run finalized.
+        fs::write(repo_dir.join("0001-alpha/README.md"), "# Alpha, revised\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "revise alpha", &tree, &[&parent])
+            .unwrap();
+        interrupt
+            .drop_final_reply
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        push(1, true).expect("push whose final reply was lost");
+        let snapshot = DevApi::new(root.join("api")).snapshot("acme").unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(!PushResume::path(&sync_url).exists());
+        let snapshot = snapshot.expect("snapshot");
+        assert_eq!(snapshot.mainline.specs.len(), 3);
+        assert!(snapshot
+            .mainline
+            .specs
+            .iter()
+            .any(|spec| spec.source.ends_with(b"revised\n")));
+    }
+
+    #[test]
+    fn team_pages_group_their_specs_by_status() {
+        let root = std::env::temp_dir().join(format!("dossiers-teams-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("0001-billing.md"),
+            "---\nstatus: Accepted\nauthors: [Ada Lovelace]\n---\n# Billing\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("0002-refunds.md"),
+            "---\nstatus: Draft\nteam: payments\n---\n# Refunds\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("0003-login.md"),
+            "---\nstatus: Draft\nteams: [Identity]\n---\n# Login\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("0004-invoices.md"),
+            "---\nstatus: Accepted\nauthors: [\"Grace Hopper <grace@example.com>\"]\n---\n# Invoices\n",
+        )
+        .unwrap();
+
+        let config = ProjectConfiguration::from_json_value(&serde_json::json!({
+            "teams": {
+                "payments": {
+                    "name": "Payments",
+                    "members": ["Ada Lovelace", "grace@example.com"],
+                },
+            },
+        }));
+        let (state, static_mounts) =
+            build_app_state(&root, &root, "Specs".into(), Assets::embedded(), config).unwrap();
+        let output = root.join("site");
+        write_static_site(state, static_mounts, &output, false).expect("write site");
+        let payments = fs::read_to_string(output.join("team/payments/index.html"));
+        let identity = fs::read_to_string(output.join("team/identity/index.html"));
+        let index = fs::read_to_string(output.join("index.html")).unwrap();
+        let spec = fs::read_to_string(output.join("0002/index.html")).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        let payments = payments.expect("payments page");
+        assert!(payments.contains("Billing") && payments.contains("Refunds"));
+        assert!(payments.contains("Invoices"), "{payments}");
+        assert!(!payments.contains("Login"));
+        let accepted = payments.find(">Accepted</a>").expect("accepted group");
+        assert!(accepted < payments.find(">Draft</a>").expect("draft group"));
+        assert!(identity.expect("identity page").contains("Login"));
+        assert!(index.contains("href=\"./team/payments\""), "{index}");
+        assert!(index.contains("data-teams=\"payments\""));
+        assert!(spec.contains("href=\"../team/payments\""), "{spec}");
+    }
+
+    #[test]
+    fn codeowners_assign_spec_owners_and_flag_unowned_specs() {
+        let root = std::env::temp_dir().join(format!("dossiers-owners-{}", std::process::id()));
+        let specs_dir = root.join("specs");
+        fs::create_dir_all(specs_dir.join("0001-alpha")).unwrap();
+        fs::create_dir_all(root.join(".github")).unwrap();
+        fs::write(specs_dir.join("0001-alpha/alpha.md"), "# Alpha\n").unwrap();
+        fs::write(specs_dir.join("0002-beta.md"), "# Beta\n").unwrap();
+        fs::write(
+            root.join(".github/CODEOWNERS"),
+            "/specs/0001-*/ @acme/auth @ada\n",
+        )
+        .unwrap();
+        git2::Repository::init(&root).unwrap();
+
+        let config = ProjectConfiguration::default();
+        let loaded = load_specs(&specs_dir, &config).expect("load specs");
+        let reports = check_specs(&specs_dir, &root, &config);
+        let summary = ownership_summary(&loaded.specs);
+        let _ = fs::remove_dir_all(&root);
+
+        let owners: HashMap<&str, &[String]> = loaded
+            .specs
+            .iter()
+            .map(|spec| (spec.id.as_str(), spec.owners.as_slice()))
+            .collect();
+        assert_eq!(owners["0001"], ["@acme/auth", "@ada"]);
+        assert!(owners["0002"].is_empty());
+        assert_eq!(
+            summary.as_deref(),
+            Some("Owners: @acme/auth (1), @ada (1); 1 unowned")
+        );
+        let warnings: Vec<&str> = reports
+            .ownership
+            .warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect();
+        assert_eq!(warnings, ["Spec 0002 has no owner in CODEOWNERS"]);
+        assert!(same_owner("ACME/auth", "@acme/auth"));
+    }
+
+    #[test]
+    fn check_flags_status_changes_the_workflow_forbids() {
+        let root = std::env::temp_dir().join(format!("dossiers-workflow-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let specs_dir = root.join("specs");
+        for dir in ["0001-login", "0002-search"] {
+            fs::create_dir_all(specs_dir.join(dir)).unwrap();
+        }
+        let write = |dir: &str, status: &str| {
+            fs::write(
+                specs_dir.join(dir).join("README.md"),
+                format!("---\nstatus: {status}\n---\n# {dir}\n"),
+            )
+            .unwrap();
+        };
+        let repo = git2::Repository::init(&root).unwrap();
+        let commit = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Ada", "ada@example.com").unwrap();
+            let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        };
+
+        write("0001-login", "Draft");
+        write("0002-search", "Proposed");
+        commit("Add specs");
+        write("0001-login", "Accepted");
+        commit("Accept login");
+        write("0002-search", "Rejected");
+        fs::create_dir_all(specs_dir.join("0003-sso")).unwrap();
+        write("0003-sso", "Implemented");
+
+        let config = ProjectConfiguration::from_json_value(&serde_json::json!({
+            "workflow": {
+                "transitions": [
+                    "Draft -> Proposed -> Accepted -> Implemented",
+                    "Proposed -> Rejected",
+                ]
+            }
+        }));
+        let reports = check_specs(&specs_dir, &root, &config);
+        let _ = fs::remove_dir_all(&root);
+
+        let messages = |list: &[LintMessage]| {
+            list.iter()
+                .map(|message| message.message.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(&reports.workflow.errors),
+            ["Spec 0001 moved from Draft to Accepted, which the workflow doesn't allow (from Draft: Proposed)"]
+        );
+        assert_eq!(
+            messages(&reports.workflow.warnings),
+            ["New spec 0003 starts as Implemented; new specs should start as Draft"]
+        );
+    }
+
+    #[test]
+    fn specs_above_the_audience_are_left_out_and_unlinked() {
+        let temp_root = std::env::temp_dir().join(format!(
+            "dossiers-visibility-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock should be after epoch")
+                .as_millis()
+        ));
+        let _ = fs::remove_dir_all(&temp_root);
+        fs::create_dir_all(&temp_root).expect("create temp root");
+        fs::write(
+            temp_root.join("dossiers.toml"),
+            "[visibility.statuses]\nDraft = \"private\"\n",
+        )
+        .expect("write config");
+        for (dir, source) in [
+            (
+                "0001-open",
+                "---\nstatus: Accepted\n---\n# Open\n\nSee [the plan](../0002-secret/README.md#goals) and \
+                 [the sketch](../0003-sketch/README.md).\n",
+            ),
+            (
+                "0002-secret",
+                "---\nvisibility: private\n---\n# Secret\n\n## Goals\n",
+            ),
+            (
+                "0003-sketch",
+                "---\nstatus: Draft\nvisibility: public\n---\n# Sketch\n",
+            ),
+        ] {
+            fs::create_dir_all(temp_root.join(dir)).expect("create spec dir");
+            fs::write(temp_root.join(dir).join("README.md"), source).expect("write spec");
+        }
+
+        let reloadable = ReloadableAppState {
+            input_path: temp_root.clone(),
+            project_root: temp_root.clone(),
+            config_path: None,
+            assets: Assets::embedded(),
+            snapshot: None,
+        };
+        let mut state = reloadable.load().expect("load should succeed");
+        let mut static_mounts: Vec<StaticMount> = ["0001", "0002", "0003"]
+            .iter()
+            .map(|id| (format!("/{id}"), temp_root.clone()))
+            .collect();
+
+        let (specs, _) = discover_specs_for_check(&temp_root);
+        let lint_data = collect_spec_lint_data(&specs);
+        let config = load_project_configuration(&temp_root, None);
+        let report = validate_visibility(&specs, &lint_data, &config);
+        let _ = fs::remove_dir_all(&temp_root);
+
+        let mut warnings: Vec<&str> = report.warnings.iter().map(|w| w.message.as_str()).collect();
+        warnings.sort();
+        assert_eq!(
+            warnings,
+            [
+                "Spec 0001 is public but links to private spec 0002",
+                "Spec 0001 is public but links to private spec 0003",
+            ]
+        );
+
+        assert_eq!(
+            state.restrict_to_audience(&mut static_mounts, Visibility::Internal),
+            2
+        );
+        assert_eq!(state.spec_ids, HashSet::from(["0001".to_string()]));
+        assert_eq!(static_mounts.len(), 1);
+
+        let open = state.specs_by_id["0001"].clone();
+        let html = render_spec_body(&state, &open, "/0001".into(), "/", false).unwrap();
+        assert!(html.contains("See the plan and the sketch."), "{html}");
+        assert!(!html.contains("0002"), "{html}");
+    }
+
     #[test]
     fn reloadable_state_reloads_documents_on_each_call() {
         let temp_root = std::env::temp_dir().join(format!(
@@ -7163,6 +12919,7 @@ mod tests {
             project_root: temp_root.clone(),
             config_path: None,
             assets: Assets::embedded(),
+            snapshot: None,
         };
 
         let first = state.load().expect("initial load should succeed");
@@ -7210,6 +12967,7 @@ mod tests {
             project_root: temp_root.clone(),
             config_path: None,
             assets: Assets::embedded(),
+            snapshot: None,
         };
 
         let loaded = state.load().expect("load should succeed");
@@ -7250,6 +13008,7 @@ mod tests {
             project_root: temp_root.clone(),
             config_path: None,
             assets: Assets::embedded(),
+            snapshot: None,
         };
         let mut state = reloadable.load().expect("load should succeed");
 
@@ -7257,6 +13016,7 @@ mod tests {
         // After loading only the filesystem spec, next_id should be 2.
         state.create_config = Some(CreateDocConfig {
             github_repo: "owner/repo".into(),
+            repo_url: "https://github.com/owner/repo".into(),
             default_branch: "main".into(),
             subdirectory: None,
             next_id: 2,
@@ -7287,6 +13047,12 @@ mod tests {
                 listed: true,
                 revision_of: None,
                 pr_number: Some(5),
+                git_ref: None,
+                approved_by: Vec::new(),
+                last_commit: None,
+                visibility: Visibility::Public,
+                owners: Vec::new(),
+                teams: Vec::new(),
             },
         );
 
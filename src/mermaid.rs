@@ -0,0 +1,227 @@
+//! Build-time Mermaid rendering in an embedded JavaScript runtime.
+//!
+//! Mermaid only ships as browser JavaScript, so pre-rendering evaluates the
+//! vendored `mermaid.min.js` in QuickJS behind a minimal DOM shim
+//! (`assets/mermaid-prerender.js`). The runtime is created on first use —
+//! loading Mermaid takes a second or two — and reused for every later diagram.
+//! Anything that goes wrong surfaces as an error so the caller can fall back to
+//! the client-side runtime.
+
+use std::fmt;
+#[cfg(feature = "mermaid-prerender")]
+use std::sync::Mutex;
+
+#[cfg(feature = "mermaid-prerender")]
+const PRERENDER_SCRIPT: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/assets/mermaid-prerender.js"
+));
+
+pub struct MermaidRenderer {
+    script: String,
+    fingerprint: String,
+    /// Loaded on first render; a failed load is kept so it is not retried
+    /// for every diagram.
+    #[cfg(feature = "mermaid-prerender")]
+    engine: Mutex<Option<Result<engine::Engine, String>>>,
+}
+
+impl fmt::Debug for MermaidRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MermaidRenderer")
+            .field("script_len", &self.script.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MermaidRenderer {
+    /// A renderer for the given Mermaid runtime source (`mermaid.min.js`).
+    pub fn new(script: String) -> Self {
+        Self {
+            fingerprint: format!("{:x}", md5::compute(script.as_bytes())),
+            script,
+            #[cfg(feature = "mermaid-prerender")]
+            engine: Mutex::default(),
+        }
+    }
+
+    /// A short digest of the Mermaid runtime, so cached SVG is invalidated
+    /// when the vendored version changes.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Render `source` to SVG. `id` becomes the root element's id, which
+    /// Mermaid also uses to scope the diagram's stylesheet.
+    #[cfg(feature = "mermaid-prerender")]
+    pub fn render(&self, id: &str, source: &str) -> Result<String, String> {
+        let mut guard = self
+            .engine
+            .lock()
+            .map_err(|_| "mermaid runtime is poisoned".to_string())?;
+        let engine = guard.get_or_insert_with(|| engine::Engine::load(&self.script));
+        match engine {
+            Ok(engine) => engine.render(id, source),
+            Err(err) => Err(err.clone()),
+        }
+    }
+
+    #[cfg(not(feature = "mermaid-prerender"))]
+    pub fn render(&self, _id: &str, _source: &str) -> Result<String, String> {
+        Err("dossiers was built without the `mermaid-prerender` feature".to_string())
+    }
+}
+
+#[cfg(feature = "mermaid-prerender")]
+mod engine {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use rquickjs::{Context, Ctx, Function, Object, Runtime};
+
+    use super::PRERENDER_SCRIPT;
+
+    /// Upper bound on a single diagram, including layout.
+    const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+    /// Loading Mermaid itself is slower than any one diagram.
+    const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+    const MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
+    pub struct Engine {
+        runtime: Runtime,
+        context: Context,
+        deadline: Arc<Mutex<Instant>>,
+    }
+
+    impl Engine {
+        pub fn load(script: &str) -> Result<Self, String> {
+            let runtime = Runtime::new().map_err(|err| err.to_string())?;
+            runtime.set_memory_limit(MEMORY_LIMIT);
+            let deadline = Arc::new(Mutex::new(Instant::now() + LOAD_TIMEOUT));
+            let handler_deadline = Arc::clone(&deadline);
+            runtime.set_interrupt_handler(Some(Box::new(move || {
+                handler_deadline
+                    .lock()
+                    .map(|deadline| Instant::now() > *deadline)
+                    .unwrap_or(true)
+            })));
+            let context = Context::full(&runtime).map_err(|err| err.to_string())?;
+
+            context.with(|ctx| -> Result<(), String> {
+                eval(&ctx, PRERENDER_SCRIPT, "mermaid shim")?;
+                eval(&ctx, script, "mermaid runtime")?;
+                call::<()>(&ctx, "configure", ())
+            })?;
+
+            Ok(Self {
+                runtime,
+                context,
+                deadline,
+            })
+        }
+
+        pub fn render(&self, id: &str, source: &str) -> Result<String, String> {
+            if let Ok(mut deadline) = self.deadline.lock() {
+                *deadline = Instant::now() + RENDER_TIMEOUT;
+            }
+            self.context
+                .with(|ctx| call::<()>(&ctx, "start", (id, source)))?;
+
+            // Mermaid renders asynchronously: drain promise jobs and timers
+            // until the render settles.
+            loop {
+                while self.runtime.is_job_pending() {
+                    self.runtime
+                        .execute_pending_job()
+                        .map_err(|_| "mermaid job failed".to_string())?;
+                }
+                if self.context.with(|ctx| call::<bool>(&ctx, "poll", ()))? {
+                    break;
+                }
+                if self
+                    .deadline
+                    .lock()
+                    .map(|deadline| Instant::now() > *deadline)
+                    .unwrap_or(true)
+                {
+                    return Err("timed out".to_string());
+                }
+            }
+
+            self.context.with(|ctx| {
+                let result: Object = call(&ctx, "result", ())?;
+                if let Some(err) = result
+                    .get::<_, Option<String>>("error")
+                    .map_err(|err| err.to_string())?
+                {
+                    return Err(err);
+                }
+                result
+                    .get::<_, Option<String>>("svg")
+                    .map_err(|err| err.to_string())?
+                    .ok_or_else(|| "mermaid returned no SVG".to_string())
+            })
+        }
+    }
+
+    fn eval(ctx: &Ctx<'_>, source: &str, what: &str) -> Result<(), String> {
+        ctx.eval::<(), _>(source)
+            .map_err(|err| format!("loading {what}: {}", describe(ctx, err)))
+    }
+
+    fn call<'js, R: rquickjs::FromJs<'js>>(
+        ctx: &Ctx<'js>,
+        name: &str,
+        args: impl rquickjs::function::IntoArgs<'js>,
+    ) -> Result<R, String> {
+        let api: Object = ctx
+            .globals()
+            .get("dossiersMermaid")
+            .map_err(|err| describe(ctx, err))?;
+        let function: Function = api.get(name).map_err(|err| describe(ctx, err))?;
+        function.call(args).map_err(|err| describe(ctx, err))
+    }
+
+    fn describe(ctx: &Ctx<'_>, err: rquickjs::Error) -> String {
+        if !err.is_exception() {
+            return err.to_string();
+        }
+        let exception = ctx.catch();
+        exception
+            .as_exception()
+            .and_then(|exception| exception.message())
+            .or_else(|| {
+                exception
+                    .as_string()
+                    .and_then(|message| message.to_string().ok())
+            })
+            .unwrap_or_else(|| err.to_string())
+    }
+}
+
+#[cfg(all(test, feature = "mermaid-prerender"))]
+mod tests {
+    use super::*;
+
+    const MERMAID_SCRIPT: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/assets/mermaid.min.js"
+    ));
+
+    #[test]
+    fn renders_flowchart_and_reports_syntax_errors() {
+        let renderer = MermaidRenderer::new(MERMAID_SCRIPT.to_string());
+
+        let svg = renderer
+            .render("mermaid-test", "graph TD; A[Start]-->B[Done];")
+            .expect("flowchart should render");
+        assert!(svg.starts_with("<svg id=\"mermaid-test\""), "{svg}");
+        assert!(svg.contains(">Start<"), "label missing: {svg}");
+        assert!(!svg.contains("NaN"), "bad geometry: {svg}");
+
+        let err = renderer
+            .render("mermaid-bad", "graph TD; A-->")
+            .expect_err("incomplete graph should fail");
+        assert!(err.contains("Parse error"), "{err}");
+    }
+}
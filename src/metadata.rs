@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use lazy_static::lazy_static;
 use pulldown_cmark::{html as md_html, Event, Options as MdOptions, Parser};
@@ -7,6 +8,19 @@ use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use serde_yaml::{Mapping as YamlMapping, Value as YamlValue};
 
+use dossiers::authors::{AuthorDirectory, AuthorResolver};
+use dossiers::forge::ForgeKind;
+use dossiers::github::GithubHost;
+use dossiers::gitlab::GitlabHost;
+use dossiers::http_cache::HttpCache;
+
+use crate::avatars::AvatarConfig;
+use crate::diagram::DiagramConfig;
+use crate::highlight::HighlightConfig;
+use crate::revisions::RevisionConfig;
+use crate::teams::TeamDirectory;
+use crate::visibility::{Visibility, VisibilityConfig};
+use crate::workflow::WorkflowConfig;
 use crate::{extract_leading_title, normalize_authors, DocFormat, Link};
 
 #[derive(Debug, Clone, Default)]
@@ -15,12 +29,16 @@ pub struct DocumentMetadata {
     pub status: Option<String>,
     pub created: Option<String>,
     pub updated: Option<String>,
+    /// Declared `visibility`, as written; see [`MetadataReader::resolve_visibility`].
+    pub visibility: Option<String>,
     pub authors: Vec<String>,
     /// Author strings exactly as declared (before `normalize_authors` strips
     /// `<email>`). Empty when no `authors:` frontmatter was present. Used to
     /// resolve avatars by email; display still uses the normalized `authors`.
     pub raw_authors: Vec<String>,
     pub links: Vec<Link>,
+    /// Teams named by `team:` or `teams:`.
+    pub teams: Vec<String>,
     pub extra: HashMap<String, MetadataValue>,
 }
 
@@ -38,6 +56,24 @@ pub struct ProjectConfiguration {
     pub description: Option<String>,
     #[allow(dead_code)]
     pub repository: Option<String>,
+    /// Web base URL of a GitHub Enterprise Server hosting `repository`.
+    pub github_url: Option<String>,
+    /// REST API base of that server, when not `<github_url>/api/v3`.
+    pub github_api_url: Option<String>,
+    /// Directory for cached GitHub API responses, relative to the repository
+    /// root; `None` uses [`dossiers::http_cache::DEFAULT_CACHE_DIR`].
+    pub github_cache_dir: Option<String>,
+    /// Whether GitHub API responses are cached on disk; `None` means yes.
+    pub github_cache: Option<bool>,
+    /// Build PR revisions only from locally fetched heads, never calling the
+    /// forge's API (`offline = true`, or `--offline`).
+    pub offline: bool,
+    /// Where pull requests come from; inferred from the remote when unset.
+    pub forge: Option<ForgeKind>,
+    /// Web base URL of a self-managed GitLab hosting `repository`.
+    pub gitlab_url: Option<String>,
+    /// Where the built site is published, for links in PR comments.
+    pub site_url: Option<String>,
     pub subdirectory: Option<String>,
     pub prefix: Option<String>,
     pub pr_number_as_spec_id: bool,
@@ -60,8 +96,24 @@ pub struct ProjectConfiguration {
     pub new_document_structure: Option<String>,
     pub push_api_url: Option<String>,
     pub push_project: Option<String>,
-    #[allow(dead_code)]
     pub push_include_prs: Option<bool>,
+    /// File holding the hex ed25519 key packages are signed with.
+    pub push_signing_key_file: Option<String>,
+    /// Named `[push.targets.<name>]` destinations, selected with `push --target`.
+    pub push_targets: Vec<PushTarget>,
+    pub diagrams: DiagramConfig,
+    pub highlight: HighlightConfig,
+    pub visibility: VisibilityConfig,
+    /// Branches and tags rendered as revisions, from `[revisions]`.
+    pub revisions: RevisionConfig,
+    /// Teams specs are grouped under, from `[teams]`.
+    pub teams: TeamDirectory,
+    /// Self-hosted avatars for static builds, from `[avatars]`.
+    pub avatars: AvatarConfig,
+    /// Allowed status transitions, from `[workflow]`.
+    pub workflow: WorkflowConfig,
+    /// Canonical author names, aliases and identities, from `[authors]`.
+    pub authors: AuthorDirectory,
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +129,29 @@ pub struct ExtraMetadataField {
     pub aliases: Vec<String>,
 }
 
+/// A named push destination from `[push.targets.<name>]`. Unset connection
+/// fields fall back to the top-level `[push]` values.
+#[derive(Debug, Clone, Default)]
+pub struct PushTarget {
+    pub name: String,
+    pub api_url: Option<String>,
+    pub project: Option<String>,
+    /// Environment variable holding this target's token, read instead of
+    /// `DOSSIERS_TOKEN`.
+    pub token_env: Option<String>,
+    pub include_prs: Option<bool>,
+    /// Statuses to publish. Together with `include` this forms an allow-list;
+    /// when both are empty every spec is published.
+    pub statuses: Vec<String>,
+    /// Spec ids or statuses to publish.
+    pub include: Vec<String>,
+    /// Spec ids or statuses never published, even when allowed above.
+    pub exclude: Vec<String>,
+    /// Most restricted visibility published to this target; overrides
+    /// `[visibility] audience`.
+    pub audience: Option<Visibility>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[derive(Default)]
@@ -219,9 +294,24 @@ impl MetadataReader {
             .new_status
             .clone()
             .or_else(|| self.config.statuses.first().cloned())
+            .or_else(|| {
+                let initial = self.config.workflow.initial_states();
+                initial.first().map(|state| state.to_string())
+            })
             .unwrap_or_else(|| "DRAFT".to_string())
     }
 
+    /// Resolves authors offline, under the names `[authors]` configures.
+    pub(crate) fn author_resolver(&self) -> AuthorResolver {
+        self.config.author_resolver()
+    }
+
+    /// A spec's effective visibility from its declared level and resolved
+    /// status, under the project's `[visibility]` rules.
+    pub(crate) fn resolve_visibility(&self, declared: Option<&str>, status: &str) -> Visibility {
+        self.config.visibility.resolve(declared, status)
+    }
+
     pub(crate) fn resolve_status(&self, provided: Option<String>, git_managed: bool) -> String {
         provided.unwrap_or_else(|| {
             if git_managed {
@@ -324,6 +414,17 @@ impl MetadataReader {
                     }
                     continue;
                 }
+                "visibility" => {
+                    metadata.visibility =
+                        yaml_value_to_string(value).filter(|v| !self.is_empty_value(v));
+                    continue;
+                }
+                // Not reserved: a configured extra field may share the name.
+                "team" | "teams" => {
+                    if let Some(teams) = parse_authors_from_yaml(value) {
+                        metadata.teams = teams;
+                    }
+                }
                 _ => {}
             }
 
@@ -380,6 +481,13 @@ impl MetadataReader {
                 metadata.authors.extend(split_authors(value));
                 return;
             }
+            "visibility" => {
+                if !self.is_empty_value(value) {
+                    metadata.visibility = Some(value.to_string());
+                }
+                return;
+            }
+            "team" | "teams" => metadata.teams.extend(split_authors(value)),
             _ => {}
         }
 
@@ -544,7 +652,118 @@ impl ExtraMetadataField {
     }
 }
 
+impl PushTarget {
+    pub fn from_json_value(name: &str, value: &JsonValue) -> Self {
+        let string = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| value.get(*key).and_then(JsonValue::as_str))
+                .map(|s| s.trim().to_string())
+                .find(|s| !s.is_empty())
+        };
+        let list = |key: &str| {
+            value
+                .get(key)
+                .and_then(JsonValue::as_array)
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(JsonValue::as_str)
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Self {
+            name: name.to_string(),
+            api_url: string(&["api_url", "apiUrl"]),
+            project: string(&["project"]),
+            token_env: string(&["token_env", "tokenEnv"]),
+            include_prs: value
+                .get("include_prs")
+                .or_else(|| value.get("includePrs"))
+                .and_then(JsonValue::as_bool),
+            statuses: list("statuses"),
+            include: list("include"),
+            exclude: list("exclude"),
+            // An unrecognised audience publishes only public specs rather than
+            // falling back to a wider default.
+            audience: string(&["audience"])
+                .map(|raw| Visibility::parse(&raw).unwrap_or(Visibility::Public)),
+        }
+    }
+
+    /// Whether a spec with this id and status is published to the target.
+    /// Statuses compare case-insensitively.
+    pub fn admits(&self, id: &str, status: &str) -> bool {
+        let matches = |entry: &String| entry == id || entry.eq_ignore_ascii_case(status);
+        if self.exclude.iter().any(matches) {
+            return false;
+        }
+        (self.statuses.is_empty() && self.include.is_empty())
+            || self.statuses.iter().any(|s| s.eq_ignore_ascii_case(status))
+            || self.include.iter().any(matches)
+    }
+
+    /// Whether the target publishes only some specs.
+    pub fn is_filtered(&self) -> bool {
+        !(self.statuses.is_empty() && self.include.is_empty() && self.exclude.is_empty())
+    }
+}
+
 impl ProjectConfiguration {
+    /// The GitHub instance the project lives on: `github_url` and
+    /// `github_api_url`, else `$GITHUB_SERVER_URL` and `$GITHUB_API_URL` (set
+    /// by GitHub Actions, including on Enterprise Server), else github.com.
+    pub fn github_host(&self) -> GithubHost {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        match self.github_url.as_deref() {
+            Some(web_url) => GithubHost::new(Some(web_url), self.github_api_url.as_deref()),
+            None => GithubHost::new(
+                env("GITHUB_SERVER_URL").as_deref(),
+                self.github_api_url
+                    .clone()
+                    .or_else(|| env("GITHUB_API_URL"))
+                    .as_deref(),
+            ),
+        }
+    }
+
+    /// Resolves authors offline, under the names `[authors]` configures.
+    pub fn author_resolver(&self) -> AuthorResolver {
+        AuthorResolver::local()
+            .with_host(self.github_host())
+            .with_directory(self.authors.clone())
+    }
+
+    /// The on-disk cache for GitHub API responses, under `repo_root` unless
+    /// `github_cache_dir` is absolute; `None` when `github_cache = false`.
+    pub fn github_cache(&self, repo_root: &Path) -> Option<HttpCache> {
+        if self.github_cache == Some(false) {
+            return None;
+        }
+        let dir = self
+            .github_cache_dir
+            .as_deref()
+            .unwrap_or(dossiers::http_cache::DEFAULT_CACHE_DIR);
+        Some(HttpCache::new(repo_root.join(dir)))
+    }
+
+    /// The GitLab instance the project lives on: `gitlab_url`, else
+    /// `$CI_SERVER_URL` (set by GitLab CI), else gitlab.com.
+    pub fn gitlab_host(&self) -> GitlabHost {
+        let env = std::env::var("CI_SERVER_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        GitlabHost::new(self.gitlab_url.as_deref().or(env.as_deref()))
+    }
+
+    /// The configured forge; a `gitlab_url` alone implies GitLab.
+    pub fn forge_kind(&self) -> Option<ForgeKind> {
+        self.forge
+            .or_else(|| self.gitlab_url.is_some().then_some(ForgeKind::Gitlab))
+    }
+
     pub fn from_json_value(value: &JsonValue) -> Self {
         let name = value
             .get("name")
@@ -570,6 +789,29 @@ impl ProjectConfiguration {
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
 
+        let url = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| value.get(*key).and_then(JsonValue::as_str))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let github_url = url(&["github_url", "githubUrl"]);
+        let github_api_url = url(&["github_api_url", "githubApiUrl"]);
+        let gitlab_url = url(&["gitlab_url", "gitlabUrl"]);
+        let site_url = url(&["site_url", "siteUrl"]);
+        let github_cache_dir = url(&["github_cache_dir", "githubCacheDir"]);
+        let github_cache = ["github_cache", "githubCache"]
+            .iter()
+            .find_map(|key| value.get(*key).and_then(JsonValue::as_bool));
+        let offline = value
+            .get("offline")
+            .and_then(JsonValue::as_bool)
+            .unwrap_or(false);
+        let forge = value
+            .get("forge")
+            .and_then(JsonValue::as_str)
+            .and_then(ForgeKind::parse);
+
         let subdirectory = value
             .get("subdirectory")
             .and_then(JsonValue::as_str)
@@ -719,12 +961,70 @@ impl ProjectConfiguration {
         let push_include_prs = push_section
             .and_then(|v| v.get("include_prs").or_else(|| v.get("includePrs")))
             .and_then(JsonValue::as_bool);
+        let push_signing_key_file = push_section
+            .and_then(|v| {
+                v.get("signing_key_file")
+                    .or_else(|| v.get("signingKeyFile"))
+            })
+            .and_then(JsonValue::as_str)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let push_targets = push_section
+            .and_then(|v| v.get("targets"))
+            .and_then(JsonValue::as_object)
+            .map(|map| {
+                map.iter()
+                    .map(|(name, target)| PushTarget::from_json_value(name, target))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let diagrams = value
+            .get("diagrams")
+            .map(DiagramConfig::from_json_value)
+            .unwrap_or_default();
+        let teams = value
+            .get("teams")
+            .map(TeamDirectory::from_json_value)
+            .unwrap_or_default();
+        let avatars = value
+            .get("avatars")
+            .map(AvatarConfig::from_json_value)
+            .unwrap_or_default();
+        let workflow = value
+            .get("workflow")
+            .map(WorkflowConfig::from_json_value)
+            .unwrap_or_default();
+        let highlight = value
+            .get("highlight")
+            .map(HighlightConfig::from_json_value)
+            .unwrap_or_default();
+        let visibility = value
+            .get("visibility")
+            .map(VisibilityConfig::from_json_value)
+            .unwrap_or_default();
+        let revisions = value
+            .get("revisions")
+            .map(RevisionConfig::from_json_value)
+            .unwrap_or_default();
+        let authors = value
+            .get("authors")
+            .map(AuthorDirectory::from_json_value)
+            .unwrap_or_default();
 
         Self {
             name,
             title,
             description,
             repository,
+            github_url,
+            github_api_url,
+            github_cache_dir,
+            github_cache,
+            offline,
+            forge,
+            gitlab_url,
+            site_url,
             subdirectory,
             prefix,
             pr_number_as_spec_id,
@@ -743,6 +1043,16 @@ impl ProjectConfiguration {
             push_api_url,
             push_project,
             push_include_prs,
+            push_signing_key_file,
+            push_targets,
+            diagrams,
+            highlight,
+            visibility,
+            revisions,
+            teams,
+            avatars,
+            workflow,
+            authors,
         }
     }
 }
@@ -976,7 +1286,15 @@ fn parse_typed_str_value(value: &str, kind: MetadataValueType) -> Option<Metadat
 fn is_standard_key(key: &str) -> bool {
     matches!(
         canonicalize_key(key).as_str(),
-        "title" | "status" | "created" | "updated" | "lastupdated" | "authors" | "author" | "links"
+        "title"
+            | "status"
+            | "created"
+            | "updated"
+            | "lastupdated"
+            | "authors"
+            | "author"
+            | "links"
+            | "visibility"
     )
 }
 
@@ -1015,6 +1333,42 @@ fn render_markdown_html(source: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parses_push_targets() {
+        let config = ProjectConfiguration::from_json_value(&serde_json::json!({
+            "push": {
+                "project": "policies",
+                "targets": {
+                    "internal": { "api_url": "https://dossiers.internal" },
+                    "public": {
+                        "project": "public-policies",
+                        "token_env": "PUBLIC_DOSSIERS_TOKEN",
+                        "includePrs": false,
+                        "statuses": ["Accepted"],
+                        "include": ["0007"],
+                        "exclude": ["0042", "Withdrawn"]
+                    }
+                }
+            }
+        }));
+
+        assert_eq!(config.push_targets.len(), 2);
+        let internal = &config.push_targets[0];
+        assert_eq!(internal.name, "internal");
+        assert!(!internal.is_filtered());
+        assert!(internal.admits("0001", "Draft"));
+
+        let public = &config.push_targets[1];
+        assert_eq!(public.project.as_deref(), Some("public-policies"));
+        assert_eq!(public.token_env.as_deref(), Some("PUBLIC_DOSSIERS_TOKEN"));
+        assert_eq!(public.include_prs, Some(false));
+        assert!(public.admits("0001", "ACCEPTED"));
+        assert!(public.admits("0007", "Draft"));
+        assert!(!public.admits("0002", "Draft"));
+        assert!(!public.admits("0042", "Accepted"));
+        assert!(!public.admits("0007", "Withdrawn"));
+    }
+
     #[test]
     fn parses_leading_list() {
         let doc = "- Title: Demo Doc\n- Status: DRAFT\n\nRest of body";
@@ -0,0 +1,121 @@
+//! Branches and tags rendered as revisions of the specs they touch.
+//!
+//! Besides open pull requests, `build` can preview branches and archive
+//! releases, both read straight from the local clone:
+//!
+//! ```toml
+//! [revisions]
+//! branches = ["spec/*"]
+//! tags = ["policy-v*"]
+//! ```
+//!
+//! A matching branch becomes a revision at `<id>/branch/<name>` of each spec
+//! it changes relative to `default_branch`. A matching tag freezes every spec
+//! as it stood at that tag under `/v/<tag>/<id>`, and is listed as a revision
+//! of the specs it changed since the previous matching tag.
+
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Default)]
+pub struct RevisionConfig {
+    /// Branch name patterns, e.g. `spec/*`; remote-tracking branches match by
+    /// their name on the remote.
+    pub branches: Vec<String>,
+    /// Tag name patterns, e.g. `policy-v*`.
+    pub tags: Vec<String>,
+}
+
+impl RevisionConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let list = |key: &str| {
+            value
+                .get(key)
+                .and_then(JsonValue::as_array)
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(JsonValue::as_str)
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            branches: list("branches"),
+            tags: list("tags"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty() && self.tags.is_empty()
+    }
+
+    pub fn matches_branch(&self, name: &str) -> bool {
+        self.branches
+            .iter()
+            .any(|pattern| pattern_matches(pattern, name))
+    }
+
+    pub fn matches_tag(&self, name: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|pattern| pattern_matches(pattern, name))
+    }
+}
+
+/// Whether `name` matches a ref pattern, where `*` stands for any run of
+/// characters (slashes included) and `?` for exactly one.
+pub fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Classic wildcard matching with backtracking to the last `*`.
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_match_branch_and_tag_names() {
+        assert!(pattern_matches("spec/*", "spec/retention"));
+        assert!(pattern_matches("spec/*", "spec/team/retention"));
+        assert!(!pattern_matches("spec/*", "feature/spec"));
+        assert!(pattern_matches("policy-v*", "policy-v2.1"));
+        assert!(pattern_matches("v?", "v1"));
+        assert!(!pattern_matches("v?", "v10"));
+        assert!(pattern_matches("*-rc*", "policy-v3-rc1"));
+        assert!(pattern_matches("main", "main"));
+        assert!(!pattern_matches("main", "mainline"));
+
+        let config = RevisionConfig::from_json_value(&serde_json::json!({
+            "branches": ["spec/*", " "],
+            "tags": ["policy-v*"],
+        }));
+        assert_eq!(config.branches, ["spec/*"]);
+        assert!(config.matches_tag("policy-v1"));
+        assert!(!config.matches_branch("policy-v1"));
+    }
+}
@@ -0,0 +1,297 @@
+//! Code snippets embedded from repository files.
+//!
+//! A fenced block whose info string carries `file=<path>` is replaced at build
+//! time with that file's contents, so quoted code cannot drift from the real
+//! thing. The path is relative to the repository root and may select part of
+//! the file:
+//!
+//! - `file=src/lib.rs#L10-L40` (or `#L10`) — a line range, 1-based, inclusive;
+//! - `file=src/lib.rs#parse` — a named region between marker comments, e.g.
+//!   `// region: parse` and `// endregion: parse` (`#region parse` also works).
+//!
+//! Marker lines inside a region are dropped, and the region is dedented to its
+//! least-indented line.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetRef {
+    pub path: String,
+    pub selection: Selection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    Whole,
+    Lines { start: usize, end: usize },
+    Region(String),
+}
+
+#[derive(Debug, Error)]
+pub enum SnippetError {
+    #[error("{path} is outside the repository")]
+    OutsideRepository { path: String },
+    #[error("cannot read {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("{path} has {len} lines, lines {start}-{end} do not exist")]
+    LinesOutOfRange {
+        path: String,
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("{path} has no region named '{name}'")]
+    MissingRegion { path: String, name: String },
+}
+
+lazy_static! {
+    static ref LINES: Regex = Regex::new(r"^L(\d+)(?:-L?(\d+))?$").unwrap();
+    static ref REGION_START: Regex =
+        Regex::new(r"^\s*(?://|#|--|;|/\*|<!--)\s*#?region:?\s+([\w.-]+)").unwrap();
+    static ref REGION_END: Regex =
+        Regex::new(r"^\s*(?://|#|--|;|/\*|<!--)\s*#?endregion\b:?\s*([\w.-]+)?").unwrap();
+}
+
+impl SnippetRef {
+    /// The `file=` attribute of a fence info string, if any.
+    pub fn parse(info: &str) -> Option<Self> {
+        let target = info
+            .split_whitespace()
+            .skip(1)
+            .find_map(|word| word.strip_prefix("file="))?
+            .trim_matches('"');
+        let (path, fragment) = target.split_once('#').unwrap_or((target, ""));
+        if path.is_empty() {
+            return None;
+        }
+
+        let selection = match LINES.captures(fragment) {
+            _ if fragment.is_empty() => Selection::Whole,
+            Some(caps) => {
+                let start = caps[1].parse().ok()?;
+                let end = caps
+                    .get(2)
+                    .map_or(Some(start), |end| end.as_str().parse().ok())?;
+                Selection::Lines { start, end }
+            }
+            None => Selection::Region(fragment.to_string()),
+        };
+        Some(Self {
+            path: path.to_string(),
+            selection,
+        })
+    }
+
+    /// The first line of the snippet in its file, when known without reading
+    /// it — used as the default start for line numbers.
+    pub fn first_line(&self) -> Option<usize> {
+        match self.selection {
+            Selection::Lines { start, .. } => Some(start),
+            _ => None,
+        }
+    }
+
+    /// Read the selected code from the file under `root`.
+    pub fn load(&self, root: &Path) -> Result<String, SnippetError> {
+        let path = self.resolve(root)?;
+        let contents = fs::read_to_string(&path).map_err(|source| SnippetError::Read {
+            path: self.path.clone(),
+            source,
+        })?;
+        let lines: Vec<&str> = contents.lines().collect();
+
+        let selected = match &self.selection {
+            Selection::Whole => lines,
+            Selection::Lines { start, end } => {
+                if *start == 0 || start > end || *end > lines.len() {
+                    return Err(SnippetError::LinesOutOfRange {
+                        path: self.path.clone(),
+                        start: *start,
+                        end: *end,
+                        len: lines.len(),
+                    });
+                }
+                lines[start - 1..*end].to_vec()
+            }
+            Selection::Region(name) => {
+                let region =
+                    find_region(&lines, name).ok_or_else(|| SnippetError::MissingRegion {
+                        path: self.path.clone(),
+                        name: name.clone(),
+                    })?;
+                dedent(region.into_iter().filter(|line| !is_marker(line)).collect())
+            }
+        };
+
+        let mut code = selected.join("\n");
+        code.push('\n');
+        Ok(code)
+    }
+
+    fn resolve(&self, root: &Path) -> Result<PathBuf, SnippetError> {
+        let relative = Path::new(&self.path);
+        let within_root =
+            relative
+                .components()
+                .try_fold(0usize, |depth, component| match component {
+                    Component::Normal(_) => Some(depth + 1),
+                    Component::CurDir => Some(depth),
+                    Component::ParentDir => depth.checked_sub(1),
+                    Component::RootDir | Component::Prefix(_) => None,
+                });
+        match within_root {
+            Some(_) => Ok(root.join(relative)),
+            None => Err(SnippetError::OutsideRepository {
+                path: self.path.clone(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for SnippetRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.selection {
+            Selection::Whole => write!(f, "{}", self.path),
+            Selection::Lines { start, end } => write!(f, "{}#L{start}-L{end}", self.path),
+            Selection::Region(name) => write!(f, "{}#{name}", self.path),
+        }
+    }
+}
+
+/// The lines strictly between `region: name` and its matching end marker.
+/// An unnamed `endregion` closes the innermost open region.
+fn find_region<'a>(lines: &[&'a str], name: &str) -> Option<Vec<&'a str>> {
+    let start = lines.iter().position(|line| {
+        REGION_START
+            .captures(line)
+            .is_some_and(|caps| &caps[1] == name)
+    })?;
+
+    let mut depth = 0usize;
+    for (offset, line) in lines[start + 1..].iter().enumerate() {
+        if let Some(caps) = REGION_END.captures(line) {
+            let closes = match caps.get(1) {
+                Some(end) => end.as_str() == name,
+                None => depth == 0,
+            };
+            if closes {
+                return Some(lines[start + 1..start + 1 + offset].to_vec());
+            }
+            if caps.get(1).is_none() {
+                depth = depth.saturating_sub(1);
+            }
+        } else if REGION_START.is_match(line) {
+            depth += 1;
+        }
+    }
+    None
+}
+
+fn is_marker(line: &str) -> bool {
+    REGION_START.is_match(line) || REGION_END.is_match(line)
+}
+
+fn dedent(lines: Vec<&str>) -> Vec<&str> {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dossiers-snippet-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("src/lib.rs"),
+            "use std::fmt;\n\
+             \n\
+             impl Parser {\n\
+             \x20   // region: parse\n\
+             \x20   fn parse(&self) {\n\
+             \x20       // region: inner\n\
+             \x20       self.step();\n\
+             \x20       // endregion: inner\n\
+             \x20   }\n\
+             \x20   // endregion: parse\n\
+             }\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_file_attribute_selections() {
+        let snippet = SnippetRef::parse("rust file=src/lib.rs#L10-L40 {2}").unwrap();
+        assert_eq!(snippet.path, "src/lib.rs");
+        assert_eq!(snippet.selection, Selection::Lines { start: 10, end: 40 });
+        assert_eq!(snippet.first_line(), Some(10));
+
+        let snippet = SnippetRef::parse("rust file=src/lib.rs#L7").unwrap();
+        assert_eq!(snippet.selection, Selection::Lines { start: 7, end: 7 });
+        let snippet = SnippetRef::parse("rust file=src/lib.rs#parse").unwrap();
+        assert_eq!(snippet.selection, Selection::Region("parse".to_string()));
+        let snippet = SnippetRef::parse("toml file=Cargo.toml").unwrap();
+        assert_eq!(snippet.selection, Selection::Whole);
+
+        assert!(SnippetRef::parse("rust").is_none());
+        assert!(SnippetRef::parse("file=src/lib.rs").is_none());
+    }
+
+    #[test]
+    fn loads_line_ranges_and_regions() {
+        let root = fixture("load");
+        let lines = SnippetRef::parse("rust file=src/lib.rs#L1-L1")
+            .unwrap()
+            .load(&root)
+            .unwrap();
+        let region = SnippetRef::parse("rust file=src/lib.rs#parse")
+            .unwrap()
+            .load(&root)
+            .unwrap();
+        fs::remove_dir_all(&root).ok();
+
+        assert_eq!(lines, "use std::fmt;\n");
+        assert_eq!(region, "fn parse(&self) {\n    self.step();\n}\n");
+    }
+
+    #[test]
+    fn reports_missing_files_ranges_and_regions() {
+        let root = fixture("errors");
+        let load = |info: &str| SnippetRef::parse(info).unwrap().load(&root);
+        let missing = load("rust file=src/gone.rs");
+        let range = load("rust file=src/lib.rs#L10-L40");
+        let region = load("rust file=src/lib.rs#render");
+        let outside = load("rust file=../secrets.txt");
+        fs::remove_dir_all(&root).ok();
+
+        assert!(matches!(missing, Err(SnippetError::Read { .. })));
+        assert!(matches!(
+            range,
+            Err(SnippetError::LinesOutOfRange { len: 11, .. })
+        ));
+        assert!(matches!(region, Err(SnippetError::MissingRegion { .. })));
+        assert!(matches!(
+            outside,
+            Err(SnippetError::OutsideRepository { .. })
+        ));
+    }
+}
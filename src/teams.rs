@@ -0,0 +1,165 @@
+//! Teams that specs are grouped under, alongside their individual authors.
+//!
+//! A spec belongs to a team when its frontmatter names it (`team:` or
+//! `teams:`), or when one of its authors or CODEOWNERS owners is a member of a
+//! team configured in `dossiers.toml`:
+//!
+//! ```toml
+//! [teams.payments]
+//! name = "Payments"                 # defaults to the table key
+//! description = "Billing, invoicing and payouts"
+//! members = ["Ada Lovelace", "@acme/payments", "grace@example.com"]
+//! ```
+//!
+//! Members are compared case-insensitively against author names, author
+//! emails (from frontmatter or the commit that added the spec) and owners,
+//! ignoring a leading `@`. Each team gets a `/team/<slug>` page.
+
+use serde_json::Value as JsonValue;
+
+use crate::same_owner;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Team {
+    /// The `[teams.<key>]` table key.
+    pub key: String,
+    /// Display name, which the team's page slug derives from.
+    pub name: String,
+    pub description: Option<String>,
+    /// Author names, CODEOWNERS owners or emails.
+    pub members: Vec<String>,
+}
+
+impl Team {
+    fn is_named(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.key.eq_ignore_ascii_case(name) || self.name.eq_ignore_ascii_case(name)
+    }
+
+    fn has_member(&self, candidate: &str) -> bool {
+        self.members
+            .iter()
+            .any(|member| same_owner(member, candidate))
+    }
+}
+
+/// The `[teams]` section of `dossiers.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct TeamDirectory {
+    pub teams: Vec<Team>,
+}
+
+impl TeamDirectory {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let Some(map) = value.as_object() else {
+            return Self::default();
+        };
+        let string = |entry: &JsonValue, key: &str| {
+            entry
+                .get(key)
+                .and_then(JsonValue::as_str)
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let teams = map
+            .iter()
+            .map(|(key, entry)| Team {
+                key: key.trim().to_string(),
+                name: string(entry, "name").unwrap_or_else(|| key.trim().to_string()),
+                description: string(entry, "description"),
+                members: entry
+                    .get("members")
+                    .and_then(JsonValue::as_array)
+                    .map(|members| {
+                        members
+                            .iter()
+                            .filter_map(JsonValue::as_str)
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Self { teams }
+    }
+
+    /// The configured team called `name` (by display name or table key).
+    pub fn find(&self, name: &str) -> Option<&Team> {
+        self.teams.iter().find(|team| team.is_named(name))
+    }
+
+    /// Names of the teams a spec belongs to: those it declares, under their
+    /// configured names, then the configured teams with a member among its
+    /// authors, their emails, or its owners.
+    pub fn teams_of(
+        &self,
+        declared: &[String],
+        authors: &[String],
+        emails: &[String],
+        owners: &[String],
+    ) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        let mut add = |name: &str| {
+            if !names.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        };
+
+        for name in declared {
+            match self.find(name) {
+                Some(team) => add(&team.name),
+                None => add(name.trim()),
+            }
+        }
+        for team in &self.teams {
+            if authors
+                .iter()
+                .chain(emails)
+                .chain(owners)
+                .any(|candidate| team.has_member(candidate))
+            {
+                add(&team.name);
+            }
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_join_declared_and_member_teams() {
+        let directory = TeamDirectory::from_json_value(&serde_json::json!({
+            "payments": {
+                "name": "Payments",
+                "members": ["Ada Lovelace", "@acme/payments"]
+            },
+            "identity": { "members": ["grace", "linus@example.com"] }
+        }));
+
+        let names = |declared: &[&str], authors: &[&str], owners: &[&str]| {
+            let owned = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            directory.teams_of(&owned(declared), &owned(authors), &[], &owned(owners))
+        };
+
+        assert_eq!(names(&[], &["ada lovelace"], &[]), ["Payments"]);
+        assert_eq!(names(&[], &[], &["@ACME/payments"]), ["Payments"]);
+        assert_eq!(
+            directory.teams_of(&[], &["Linus".into()], &["Linus@Example.com".into()], &[]),
+            ["identity"]
+        );
+        assert_eq!(
+            names(&["IDENTITY", "Platform"], &["Ada Lovelace"], &[]),
+            ["identity", "Platform", "Payments"]
+        );
+        assert!(names(&[], &["Linus"], &["@acme/kernel"]).is_empty());
+        assert_eq!(
+            directory.find("payments").map(|team| team.name.as_str()),
+            Some("Payments")
+        );
+    }
+}
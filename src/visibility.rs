@@ -0,0 +1,179 @@
+//! Who may read a spec once it is published.
+//!
+//! A spec's level comes from its `visibility` metadata (or the configured
+//! default), raised by any `[visibility.statuses]` rule for its status, so a
+//! rule like `Draft = "private"` holds whatever the document declares.
+//! `build`, `bundle` and `push` publish only the specs at or below their
+//! audience's level:
+//!
+//! ```toml
+//! [visibility]
+//! default = "public"
+//! audience = "internal"
+//!
+//! [visibility.statuses]
+//! Draft = "private"
+//! ```
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Visibility {
+    #[default]
+    Public,
+    Internal,
+    Private,
+}
+
+impl Visibility {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "public" => Some(Self::Public),
+            "internal" => Some(Self::Internal),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+
+    /// The level recorded in a package. An unrecognised value is treated as
+    /// private so a typo never publishes a spec more widely than intended.
+    pub fn from_wire(raw: Option<&str>) -> Self {
+        raw.map_or(Self::Public, |raw| {
+            Self::parse(raw).unwrap_or(Self::Private)
+        })
+    }
+
+    /// The level as recorded in a package; `None` for public, the default.
+    pub fn to_wire(self) -> Option<String> {
+        (self != Self::Public).then(|| self.as_str().to_string())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Internal => "internal",
+            Self::Private => "private",
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Self::parse(raw).ok_or_else(|| {
+            format!("unknown visibility '{raw}' (expected public, internal or private)")
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VisibilityConfig {
+    /// Level of specs that declare none.
+    pub default: Visibility,
+    /// Most restricted level published when no `--audience` is given; `None`
+    /// means internal, so only private specs are held back.
+    pub audience: Option<Visibility>,
+    /// Status (lowercased) → the least restricted level a spec with that
+    /// status may have.
+    pub statuses: Vec<(String, Visibility)>,
+    /// Unrecognised levels in the section, reported by `check`.
+    pub problems: Vec<String>,
+}
+
+impl VisibilityConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let mut problems = Vec::new();
+        let mut level = |key: &str, raw: &JsonValue| match raw.as_str().map(Visibility::parse) {
+            Some(Some(level)) => Some(level),
+            _ => {
+                problems.push(format!(
+                    "[visibility] {key} must be public, internal or private (found {raw})"
+                ));
+                None
+            }
+        };
+
+        let default = value
+            .get("default")
+            .and_then(|raw| level("default", raw))
+            .unwrap_or_default();
+        let audience = value.get("audience").and_then(|raw| level("audience", raw));
+        let statuses = value
+            .get("statuses")
+            .and_then(JsonValue::as_object)
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(status, raw)| {
+                        let key = format!("statuses.{status}");
+                        level(&key, raw).map(|level| (status.trim().to_ascii_lowercase(), level))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            default,
+            audience,
+            statuses,
+            problems,
+        }
+    }
+
+    /// The effective level of a spec: its declared level (or the default),
+    /// raised to what its status requires. An unrecognised declaration counts
+    /// as private.
+    pub fn resolve(&self, declared: Option<&str>, status: &str) -> Visibility {
+        let declared = declared.map_or(self.default, |raw| {
+            Visibility::parse(raw).unwrap_or(Visibility::Private)
+        });
+        let status = status.trim().to_ascii_lowercase();
+        self.statuses
+            .iter()
+            .filter(|(rule, _)| *rule == status)
+            .map(|(_, level)| *level)
+            .fold(declared, Visibility::max)
+    }
+
+    /// The audience published to when none is given on the command line.
+    pub fn audience(&self) -> Visibility {
+        self.audience.unwrap_or(Visibility::Internal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_rules_only_ever_restrict() {
+        let config = VisibilityConfig::from_json_value(&serde_json::json!({
+            "default": "internal",
+            "statuses": { "Draft": "private", "Accepted": "public", "Odd": "secret" }
+        }));
+
+        assert_eq!(config.resolve(None, "Review"), Visibility::Internal);
+        assert_eq!(config.resolve(Some("public"), "Review"), Visibility::Public);
+        assert_eq!(config.resolve(Some("Public"), "DRAFT"), Visibility::Private);
+        // A rule cannot widen what the document declares.
+        assert_eq!(
+            config.resolve(Some("internal"), "Accepted"),
+            Visibility::Internal
+        );
+        assert_eq!(
+            config.resolve(Some("secret"), "Review"),
+            Visibility::Private
+        );
+        assert_eq!(config.audience(), Visibility::Internal);
+        assert_eq!(config.problems.len(), 1);
+        assert!(config.problems[0].contains("statuses.Odd"));
+    }
+}
@@ -0,0 +1,247 @@
+//! The status workflow: which status changes a spec may go through.
+//!
+//! ```toml
+//! [workflow]
+//! transitions = [
+//!   "Draft -> Proposed -> Accepted -> Implemented",
+//!   "Proposed -> Rejected",
+//! ]
+//! terminal = ["Implemented", "Rejected"]
+//! ```
+//!
+//! Each entry is a chain of allowed moves, written with `->` or `→`. A status
+//! no transition leaves, or one listed under `terminal`, is terminal: specs
+//! that reach it stay there. Statuses nothing leads to are where new specs
+//! start. `check` compares each spec's status with the one it last had in git
+//! (or on mainline, for a branch) and flags moves the workflow doesn't allow;
+//! the status index draws the workflow as a diagram.
+
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowConfig {
+    /// Statuses in the order they first appear.
+    pub states: Vec<String>,
+    /// Allowed `(from, to)` moves.
+    pub transitions: Vec<(String, String)>,
+    /// Statuses declared terminal, beyond those without outgoing moves.
+    pub terminal: Vec<String>,
+    /// Malformed entries in the section, reported by `check`.
+    pub problems: Vec<String>,
+}
+
+/// How a spec's status moved, judged against the workflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transition {
+    /// The status did not change.
+    Unchanged,
+    Allowed,
+    /// The workflow has no such move.
+    Illegal,
+    /// The previous status is terminal.
+    FromTerminal,
+    /// One of the statuses is not part of the workflow, so the move is not
+    /// judged.
+    Unknown,
+}
+
+impl WorkflowConfig {
+    pub fn from_json_value(value: &JsonValue) -> Self {
+        let mut config = Self::default();
+        let list = |key: &str| -> Vec<&JsonValue> {
+            match value.get(key) {
+                Some(JsonValue::Array(items)) => items.iter().collect(),
+                Some(other) => vec![other],
+                None => Vec::new(),
+            }
+        };
+
+        for entry in list("transitions") {
+            let Some(chain) = entry.as_str() else {
+                config.problems.push(format!(
+                    "[workflow] transitions must be strings (found {entry})"
+                ));
+                continue;
+            };
+            let steps: Vec<&str> = chain
+                .split("->")
+                .flat_map(|part| part.split('→'))
+                .map(str::trim)
+                .collect();
+            if steps.len() < 2 || steps.iter().any(|step| step.is_empty()) {
+                config.problems.push(format!(
+                    "[workflow] transition '{chain}' must name at least two statuses, e.g. \"Draft -> Proposed\""
+                ));
+                continue;
+            }
+            for pair in steps.windows(2) {
+                let from = config.intern(pair[0]);
+                let to = config.intern(pair[1]);
+                if !config.allows(&from, &to) {
+                    config.transitions.push((from, to));
+                }
+            }
+        }
+
+        for entry in list("terminal") {
+            match entry.as_str().map(str::trim).filter(|s| !s.is_empty()) {
+                Some(status) => {
+                    let status = config.intern(status);
+                    if config.transitions.iter().any(|(from, _)| *from == status) {
+                        config.problems.push(format!(
+                            "[workflow] terminal status '{status}' has transitions out of it"
+                        ));
+                    }
+                    config.terminal.push(status);
+                }
+                None => config.problems.push(format!(
+                    "[workflow] terminal must list statuses (found {entry})"
+                )),
+            }
+        }
+        config
+    }
+
+    /// The workflow's spelling of `status`, adding it when new.
+    fn intern(&mut self, status: &str) -> String {
+        match self.state(status) {
+            Some(known) => known.to_string(),
+            None => {
+                self.states.push(status.to_string());
+                status.to_string()
+            }
+        }
+    }
+
+    /// The declared spelling of `status`, compared case-insensitively.
+    pub fn state(&self, status: &str) -> Option<&str> {
+        self.states
+            .iter()
+            .find(|state| state.eq_ignore_ascii_case(status.trim()))
+            .map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    fn allows(&self, from: &str, to: &str) -> bool {
+        self.transitions
+            .iter()
+            .any(|(f, t)| f.eq_ignore_ascii_case(from) && t.eq_ignore_ascii_case(to))
+    }
+
+    pub fn is_terminal(&self, status: &str) -> bool {
+        let Some(state) = self.state(status) else {
+            return false;
+        };
+        self.terminal.iter().any(|t| t == state)
+            || !self.transitions.iter().any(|(from, _)| from == state)
+    }
+
+    /// Statuses no transition leads to, where new specs start.
+    pub fn initial_states(&self) -> Vec<&str> {
+        self.states
+            .iter()
+            .filter(|state| !self.transitions.iter().any(|(_, to)| to == *state))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Statuses specs stay in once they reach them.
+    pub fn terminal_states(&self) -> Vec<&str> {
+        self.states
+            .iter()
+            .filter(|state| self.is_terminal(state))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Judge a move from `from` to `to`.
+    pub fn judge(&self, from: &str, to: &str) -> Transition {
+        if from.trim().eq_ignore_ascii_case(to.trim()) {
+            return Transition::Unchanged;
+        }
+        let (Some(from), Some(to)) = (self.state(from), self.state(to)) else {
+            return Transition::Unknown;
+        };
+        if self.allows(from, to) {
+            Transition::Allowed
+        } else if self.is_terminal(from) {
+            Transition::FromTerminal
+        } else {
+            Transition::Illegal
+        }
+    }
+
+    /// Statuses `from` may move to.
+    pub fn next_states(&self, from: &str) -> Vec<&str> {
+        self.transitions
+            .iter()
+            .filter(|(f, _)| f.eq_ignore_ascii_case(from.trim()))
+            .map(|(_, to)| to.as_str())
+            .collect()
+    }
+
+    /// The workflow as a Graphviz DOT graph, terminal statuses shaded and drawn
+    /// with a double border (which the embedded renderer leaves out).
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph workflow {\n  rankdir=LR;\n");
+        for (index, state) in self.states.iter().enumerate() {
+            let style = if self.is_terminal(state) {
+                ", style=filled, peripheries=2"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "  s{index} [label=\"{}\", shape=box{style}];\n",
+                state.replace('\\', "\\\\").replace('"', "\\\"")
+            ));
+        }
+        for (from, to) in &self.transitions {
+            let index = |status: &str| self.states.iter().position(|s| s == status);
+            if let (Some(from), Some(to)) = (index(from), index(to)) {
+                dot.push_str(&format!("  s{from} -> s{to};\n"));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn judges_moves_against_declared_chains() {
+        let workflow = WorkflowConfig::from_json_value(&serde_json::json!({
+            "transitions": [
+                "Draft -> Proposed -> Accepted → Implemented",
+                "Proposed -> Rejected",
+                "Accepted -> Bogus ->",
+            ],
+            "terminal": ["Rejected"],
+        }));
+
+        assert_eq!(workflow.problems.len(), 1, "{:?}", workflow.problems);
+        assert_eq!(workflow.judge("draft", "PROPOSED"), Transition::Allowed);
+        assert_eq!(workflow.judge("Draft", "Accepted"), Transition::Illegal);
+        assert_eq!(
+            workflow.judge("Implemented", "Draft"),
+            Transition::FromTerminal
+        );
+        assert_eq!(
+            workflow.judge("Rejected", "Proposed"),
+            Transition::FromTerminal
+        );
+        assert_eq!(workflow.judge("Draft", "Draft"), Transition::Unchanged);
+        assert_eq!(workflow.judge("Draft", "Someday"), Transition::Unknown);
+        assert_eq!(workflow.initial_states(), ["Draft"]);
+        assert_eq!(workflow.next_states("proposed"), ["Accepted", "Rejected"]);
+        assert!(workflow
+            .to_dot()
+            .contains("s4 [label=\"Rejected\", shape=box, style=filled, peripheries=2]"));
+        assert!(workflow.to_dot().contains("s1 -> s4;"));
+    }
+}